@@ -0,0 +1,154 @@
+use crate::{eval, greedy_ai::GreedyAI, AIAgent, AgentConfig, SearchStats};
+use azul_core::{GamePhase, GameState, Move};
+use std::any::Any;
+
+const DEFAULT_DEPTH: u32 = 3;
+
+/// A depth-limited minimax search with alpha-beta pruning, built for 2-player games. The root
+/// player maximizes its margin over the best opponent and every other seat minimizes it, so with
+/// more players it plays paranoid.
+///
+/// The search doesn't look past the end of the drafting round, since the refill is unknown. A
+/// round end is scored exactly (tiling, and the bonuses if it was the final round); any other
+/// leaf gets the static evaluation in `eval::evaluate`. Deterministic, which makes it a good
+/// fixed opponent for measuring MCTS strength. The search walks one state, undoing each move
+/// on the way back up, rather than copying the position at every node. See `MaxNAI` for a
+/// search in which each opponent plays for its own score.
+pub struct AlphaBetaAI {
+    depth: u32,
+    nodes_searched: u64,
+}
+
+impl AlphaBetaAI {
+    pub fn new(depth: u32) -> Self {
+        Self { depth: depth.max(1), nodes_searched: 0 }
+    }
+
+    /// Positions visited by the last `get_move`.
+    pub fn nodes_searched(&self) -> u64 {
+        self.nodes_searched
+    }
+
+    /// The best move for the player to move and its searched value: the margin over the best
+    /// opponent, in points.
+    pub fn best_move_value(&mut self, game_state: &GameState) -> Option<(Move, f32)> {
+        self.nodes_searched = 0;
+        let root_player = game_state.current_player_idx;
+        let mut state = game_state.clone();
+        let mut alpha = f32::NEG_INFINITY;
+        let mut best_move = None;
+        for m in ordered_moves(&mut state) {
+            let undo = state.apply_move_undoable(&m);
+            let value = self.search(&mut state, self.depth - 1, alpha, f32::INFINITY, root_player);
+            state.undo(undo);
+            if best_move.is_none() || value > alpha {
+                alpha = value;
+                best_move = Some(m);
+            }
+        }
+        best_move.map(|m| (m, alpha))
+    }
+
+    /// The searched value of playing `m`, on the same scale as `best_move_value`.
+    pub fn move_value(&mut self, game_state: &GameState, m: &Move) -> f32 {
+        let mut child = game_state.clone();
+        child.apply_move(m);
+        self.search(&mut child, self.depth - 1, f32::NEG_INFINITY, f32::INFINITY, game_state.current_player_idx)
+    }
+
+    fn search(&mut self, state: &mut GameState, depth: u32, mut alpha: f32, mut beta: f32, root_player: usize) -> f32 {
+        self.nodes_searched += 1;
+        if depth == 0 || state.phase() != GamePhase::Drafting {
+            return margin(&leaf_values(state), root_player);
+        }
+
+        let maximizing = state.current_player_idx == root_player;
+        let mut best = if maximizing { f32::NEG_INFINITY } else { f32::INFINITY };
+        for m in ordered_moves(state) {
+            let undo = state.apply_move_undoable(&m);
+            let value = self.search(state, depth - 1, alpha, beta, root_player);
+            state.undo(undo);
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl Default for AlphaBetaAI {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH)
+    }
+}
+
+impl AIAgent for AlphaBetaAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        self.best_move_value(game_state).map(|(m, _)| m)
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "AlphaBetaAI"
+    }
+
+    fn reset(&mut self) {
+        self.nodes_searched = 0;
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(depth) = config.depth {
+            self.depth = depth.max(1);
+        }
+    }
+
+    fn search_stats(&self) -> Option<SearchStats> {
+        Some(SearchStats { iterations: 0, nodes: self.nodes_searched })
+    }
+}
+
+/// Legal moves, most promising first, so that the pruning cuts early.
+pub(crate) fn ordered_moves(state: &mut GameState) -> Vec<Move> {
+    let mut scored: Vec<(Move, f32)> = state.get_legal_moves().into_iter()
+        .map(|m| {
+            let value = GreedyAI::evaluate_move_in_place(state, &m);
+            (m, value)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().map(|(m, _)| m).collect()
+}
+
+/// Each player's value at a search leaf. Mid-round this is the static evaluation; at the end of
+/// the round the walls are tiled exactly, and if that ends the game the final scores are used.
+pub(crate) fn leaf_values(state: &GameState) -> Vec<f32> {
+    if state.phase() == GamePhase::Drafting {
+        return state.players.iter().map(|p| eval::evaluate(p, state)).collect();
+    }
+    let mut tiled = state.clone();
+    tiled.run_tiling_phase();
+    if tiled.end_game_triggered {
+        tiled.apply_end_game_scoring();
+        return tiled.players.iter().map(|p| p.score as f32).collect();
+    }
+    tiled.players.iter().map(|p| eval::evaluate(p, &tiled)).collect()
+}
+
+/// How far `player` is ahead of the best of the others.
+pub(crate) fn margin(values: &[f32], player: usize) -> f32 {
+    let best_opponent = values.iter().enumerate()
+        .filter(|&(idx, _)| idx != player)
+        .map(|(_, &v)| v)
+        .fold(f32::NEG_INFINITY, f32::max);
+    values[player] - best_opponent
+}
@@ -0,0 +1,115 @@
+//! Position evaluation for analysis boards: each player's chances from here, and the moves an
+//! MCTS search prefers for the player to move.
+
+use crate::heuristic_ai::HeuristicAI;
+use crate::mcts_heuristic_ai::{MctsHeuristicAI, VALUE_SCALE};
+use crate::mcts_lib::MctsConfig;
+use crate::AIAgent;
+use azul_core::{GamePhase, GameState, Move, Winner};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Playouts behind the win probabilities.
+pub const WIN_PLAYOUTS: u32 = 100;
+/// Candidate moves reported.
+pub const TOP_MOVES: usize = 5;
+
+/// A root move of the search.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateMove {
+    #[cfg_attr(feature = "serde", serde(rename = "move"))]
+    pub mv: Move,
+    /// The move's share of the search's visits, from 0 to 1.
+    pub visit_share: f32,
+    /// The mover's expected margin over the best opponent after the move, in points.
+    pub value: f32,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionEvaluation {
+    /// Each player's chance of winning, summing to 1, with shared victories split. Exact once
+    /// the game is over.
+    pub win_probabilities: Vec<f32>,
+    /// The search's favorite moves, most visited first; empty when there is no move to make.
+    pub top_moves: Vec<CandidateMove>,
+    /// The player to move's expected margin over the best opponent, in points, if searched.
+    pub value: Option<f32>,
+    /// Simulations the search ran.
+    pub iterations: u32,
+}
+
+/// The engine `evaluate_position` searches with: MCTS without the endgame solver, which would
+/// settle late positions without the visit counts that the evaluation reports.
+pub fn analysis_engine(iterations: u32) -> MctsHeuristicAI {
+    MctsHeuristicAI::new(iterations.max(1)).with_config(MctsConfig { endgame_nodes: 0, ..MctsConfig::default() })
+}
+
+/// Evaluates `game_state` with an `iterations`-long search and `WIN_PLAYOUTS` playouts.
+pub fn evaluate_position(game_state: &GameState, iterations: u32) -> PositionEvaluation {
+    let mut engine = analysis_engine(iterations);
+    if game_state.phase() == GamePhase::Drafting {
+        engine.get_move(game_state);
+    }
+    PositionEvaluation::from_search(game_state, &engine)
+}
+
+impl PositionEvaluation {
+    /// Builds the evaluation from `engine`, which has just searched `game_state` (or hasn't
+    /// searched at all, outside the drafting phase), and `WIN_PLAYOUTS` playouts.
+    pub fn from_search(game_state: &GameState, engine: &MctsHeuristicAI) -> Self {
+        let win_probabilities = win_probabilities(game_state, WIN_PLAYOUTS, &mut StdRng::from_entropy());
+        let searched = (game_state.phase() == GamePhase::Drafting).then(|| engine.analysis(usize::MAX)).flatten();
+        let Some(analysis) = searched else {
+            return Self { win_probabilities, top_moves: Vec::new(), value: None, iterations: 0 };
+        };
+        let total_visits: u32 = analysis.top_moves.iter().map(|stats| stats.visits).sum();
+        let top_moves = analysis.top_moves.iter().take(TOP_MOVES)
+            .map(|stats| CandidateMove {
+                mv: stats.mv.clone(),
+                visit_share: stats.visits as f32 / total_visits.max(1) as f32,
+                value: stats.q * VALUE_SCALE,
+            })
+            .collect();
+        Self {
+            win_probabilities,
+            top_moves,
+            value: engine.root_value().map(|value| value * VALUE_SCALE),
+            iterations: engine.search_stats().map_or(0, |stats| stats.iterations),
+        }
+    }
+}
+
+/// Each player's share of wins in `playouts` HeuristicAI games from determinized copies of
+/// `game_state`, with shared victories split. A finished game counts its actual result.
+pub fn win_probabilities<R: Rng + ?Sized>(game_state: &GameState, playouts: u32, rng: &mut R) -> Vec<f32> {
+    let mut wins = vec![0.0; game_state.players.len()];
+    if game_state.is_game_over() {
+        credit(&mut wins, &game_state.winner(), 1.0);
+        return wins;
+    }
+    let playouts = playouts.max(1);
+    for _ in 0..playouts {
+        let mut state = game_state.clone();
+        state.determinize(rng);
+        let mut agent = HeuristicAI::new();
+        while !state.is_game_over() {
+            let next_move = if state.phase() == GamePhase::Drafting { agent.get_move(&state) } else { None };
+            if state.advance_with_rng(next_move.as_ref(), rng).is_err() {
+                break;
+            }
+        }
+        credit(&mut wins, &state.winner(), 1.0 / playouts as f32);
+    }
+    wins
+}
+
+fn credit(wins: &mut [f32], winner: &Winner, weight: f32) {
+    let players = winner.players();
+    for &player in players {
+        wins[player] += weight / players.len() as f32;
+    }
+}
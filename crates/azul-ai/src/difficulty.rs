@@ -0,0 +1,177 @@
+use crate::{
+    greedy_ai::GreedyAI, heuristic_ai::HeuristicAI, mcts_heuristic_ai::MctsHeuristicAI, AIAgent, AgentConfig, SearchStats,
+};
+use azul_core::{GameState, Move};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+
+/// Playing strength presets for opponents of human players, weakest first. Each level maps to
+/// an agent and a search budget, and the lower levels also make deliberate mistakes.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+    Max,
+}
+
+/// How a difficulty level plays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultySettings {
+    /// MCTS iterations per move, or `None` for a level played by a rule-based agent.
+    pub iterations: Option<u32>,
+    /// The chance that a move is sampled instead of taken from the agent.
+    pub mistake_rate: f64,
+    /// The softmax temperature, in points, for sampled moves: higher picks worse moves more
+    /// often.
+    pub temperature: f32,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] =
+        [Difficulty::Beginner, Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Max];
+
+    pub fn settings(self) -> DifficultySettings {
+        let (iterations, mistake_rate, temperature) = match self {
+            Difficulty::Beginner => (None, 0.4, 4.0),
+            Difficulty::Easy => (None, 0.2, 2.0),
+            Difficulty::Medium => (Some(300), 0.05, 1.0),
+            Difficulty::Hard => (Some(1500), 0.0, 1.0),
+            Difficulty::Max => (Some(8000), 0.0, 1.0),
+        };
+        DifficultySettings { iterations, mistake_rate, temperature }
+    }
+
+    /// The agent for this level: GreedyAI for Beginner, HeuristicAI for Easy, and MCTS above.
+    pub fn create_agent(self) -> Box<dyn AIAgent> {
+        let settings = self.settings();
+        let inner: Box<dyn AIAgent> = match (self, settings.iterations) {
+            (_, Some(iterations)) => Box::new(MctsHeuristicAI::new(iterations)),
+            (Difficulty::Beginner, None) => Box::new(GreedyAI),
+            (_, None) => Box::new(HeuristicAI::new()),
+        };
+        if settings.mistake_rate > 0.0 {
+            Box::new(HandicappedAgent::new(inner, self, settings))
+        } else {
+            inner
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Max => "max",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL.into_iter()
+            .find(|level| level.to_string() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("unknown difficulty '{}' (expected beginner, easy, medium, hard, or max)", s))
+    }
+}
+
+/// Plays the inner agent's moves, except that with probability `mistake_rate` it samples a
+/// move instead, weighted by a softmax over `GreedyAI::evaluate_move`.
+struct HandicappedAgent {
+    inner: Box<dyn AIAgent>,
+    difficulty: Difficulty,
+    mistake_rate: f64,
+    temperature: f32,
+    rng: StdRng,
+}
+
+impl HandicappedAgent {
+    fn new(inner: Box<dyn AIAgent>, difficulty: Difficulty, settings: DifficultySettings) -> Self {
+        Self {
+            inner,
+            difficulty,
+            mistake_rate: settings.mistake_rate,
+            temperature: settings.temperature,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    fn sample_move(&mut self, game_state: &GameState) -> Option<Move> {
+        let scored: Vec<(Move, f32)> = game_state.get_legal_moves().into_iter()
+            .map(|m| {
+                let value = GreedyAI::evaluate_move(game_state, &m);
+                (m, value)
+            })
+            .collect();
+        let best = scored.iter().map(|(_, v)| *v).fold(f32::NEG_INFINITY, f32::max);
+        let weights: Vec<f32> = scored.iter().map(|(_, v)| ((v - best) / self.temperature).exp()).collect();
+        let mut pick = self.rng.gen::<f32>() * weights.iter().sum::<f32>();
+        for ((m, _), weight) in scored.iter().zip(&weights) {
+            if pick < *weight {
+                return Some(m.clone());
+            }
+            pick -= weight;
+        }
+        scored.last().map(|(m, _)| m.clone())
+    }
+}
+
+impl AIAgent for HandicappedAgent {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        if self.rng.gen_bool(self.mistake_rate) {
+            self.inner.stop_ponder();
+            return self.sample_move(game_state);
+        }
+        self.inner.get_move(game_state)
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.difficulty {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Max => "Max",
+        }
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(seed) = config.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+        self.inner.configure(config);
+    }
+
+    fn start_ponder(&mut self, game_state: &GameState) {
+        self.inner.start_ponder(game_state);
+    }
+
+    fn stop_ponder(&mut self) {
+        self.inner.stop_ponder();
+    }
+
+    fn search_stats(&self) -> Option<SearchStats> {
+        self.inner.search_stats()
+    }
+}
@@ -0,0 +1,154 @@
+use crate::alpha_beta_ai::margin;
+use azul_core::{compact::CompactState, GamePhase, GameState, Move};
+
+/// The solved value of the position: the move to play and the final scores it leads to if
+/// everyone plays on perfectly.
+#[derive(Debug, Clone)]
+pub struct EndgameSolution {
+    pub best_move: Move,
+    pub final_scores: Vec<f32>,
+    pub nodes_searched: u64,
+}
+
+/// Solves the final round exactly. Once the end of the game has been triggered, all that's
+/// left is this round's drafting and then the tiling and bonuses, none of which involve a draw,
+/// so the remaining tree can be searched to the end.
+///
+/// Two-player positions are solved by alpha-beta on the score margin, which is exact for a
+/// zero-sum game; with more players every seat maximizes its own margin (MaxN). The search
+/// gives up once it has visited `max_nodes` positions. It plays on packed `CompactState`s, so
+/// the positions it visits cost no allocations.
+pub struct EndgameSolver {
+    max_nodes: u64,
+    nodes_searched: u64,
+}
+
+impl EndgameSolver {
+    pub fn new(max_nodes: u64) -> Self {
+        Self { max_nodes, nodes_searched: 0 }
+    }
+
+    /// Whether `state` is in the final drafting round, where `solve` can run.
+    pub fn applies(state: &GameState) -> bool {
+        state.end_game_triggered && state.phase() == GamePhase::Drafting
+    }
+
+    /// The exact best move for the player to move, or `None` if the position isn't in the
+    /// final round or the search ran over its node budget.
+    pub fn solve(&mut self, state: &GameState) -> Option<EndgameSolution> {
+        if !Self::applies(state) {
+            return None;
+        }
+        self.nodes_searched = 0;
+        let root_player = state.current_player_idx;
+        let two_player = state.players.len() == 2;
+        let state = CompactState::from(state);
+
+        let mut best: Option<(Move, Vec<f32>)> = None;
+        let mut alpha = f32::NEG_INFINITY;
+        for m in ordered_moves(&state) {
+            let mut child = state;
+            child.apply_move(&m);
+            let scores = if two_player {
+                self.alpha_beta(&child, alpha, f32::INFINITY, root_player)?
+            } else {
+                self.max_n(&child)?
+            };
+            let value = margin(&scores, root_player);
+            if best.is_none() || value > alpha {
+                alpha = value;
+                best = Some((m, scores));
+            }
+        }
+        best.map(|(best_move, final_scores)| EndgameSolution {
+            best_move,
+            final_scores,
+            nodes_searched: self.nodes_searched,
+        })
+    }
+
+    /// Counts a visited position; false once the budget is spent.
+    fn visit(&mut self) -> bool {
+        self.nodes_searched += 1;
+        self.nodes_searched <= self.max_nodes
+    }
+
+    fn alpha_beta(&mut self, state: &CompactState, mut alpha: f32, mut beta: f32, root_player: usize) -> Option<Vec<f32>> {
+        if !self.visit() {
+            return None;
+        }
+        if state.phase() != GamePhase::Drafting {
+            return Some(final_scores(state));
+        }
+
+        let maximizing = state.current_player_idx() == root_player;
+        let mut best: Option<Vec<f32>> = None;
+        for m in ordered_moves(state) {
+            let mut child = *state;
+            child.apply_move(&m);
+            let scores = self.alpha_beta(&child, alpha, beta, root_player)?;
+            let value = margin(&scores, root_player);
+            let improves = best.as_ref().is_none_or(|b| {
+                let best_value = margin(b, root_player);
+                if maximizing { value > best_value } else { value < best_value }
+            });
+            if improves {
+                best = Some(scores);
+            }
+            if maximizing {
+                alpha = alpha.max(value);
+            } else {
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    fn max_n(&mut self, state: &CompactState) -> Option<Vec<f32>> {
+        if !self.visit() {
+            return None;
+        }
+        if state.phase() != GamePhase::Drafting {
+            return Some(final_scores(state));
+        }
+
+        let mover = state.current_player_idx();
+        let mut best: Option<Vec<f32>> = None;
+        for m in state.get_legal_moves() {
+            let mut child = *state;
+            child.apply_move(&m);
+            let scores = self.max_n(&child)?;
+            if best.as_ref().is_none_or(|b| margin(&scores, mover) > margin(b, mover)) {
+                best = Some(scores);
+            }
+        }
+        best
+    }
+}
+
+/// Legal moves, those that gain the mover the most this round first, so that the pruning cuts
+/// early.
+fn ordered_moves(state: &CompactState) -> Vec<Move> {
+    let mover = state.current_player_idx();
+    let mut scored: Vec<(Move, i32)> = state.get_legal_moves().into_iter()
+        .map(|m| {
+            let mut child = *state;
+            child.apply_move(&m);
+            let gain = child.players()[mover].projected_round_points();
+            (m, gain)
+        })
+        .collect();
+    scored.sort_by_key(|&(_, gain)| core::cmp::Reverse(gain));
+    scored.into_iter().map(|(m, _)| m).collect()
+}
+
+/// The final scores once the last round has been tiled and the bonuses added.
+fn final_scores(state: &CompactState) -> Vec<f32> {
+    let mut finished = *state;
+    finished.run_tiling_phase();
+    finished.apply_end_game_scoring();
+    finished.players().iter().map(|p| p.score as f32).collect()
+}
@@ -0,0 +1,106 @@
+//! Positional knowledge shared by the agents: what a board is worth beyond its score, and the
+//! building blocks (adjacency, column and color progress, floor exposure) the rule-based
+//! agents weigh moves with. `evaluate` is the static evaluation used by the searches.
+
+use azul_core::{GamePhase, GameState, Move, PlayerBoard, Tile, NUM_COLS, NUM_ROWS, WALL_LAYOUT};
+
+// Weights of `evaluate`, in points.
+const PARTIAL_LINE_WEIGHT: f32 = 1.0;
+const WALL_PROGRESS_WEIGHT: f32 = 0.5;
+const FLOOR_RISK_WEIGHT: f32 = 0.5;
+
+// The end-game bonuses that `wall_progress` builds toward.
+const ROW_BONUS: f32 = 2.0;
+const COLUMN_BONUS: f32 = 7.0;
+const COLOR_BONUS: f32 = 10.0;
+
+/// The static evaluation of `board` in `game_state`, in points: the current score, what the
+/// round would score if it ended now (full lines minus floor exposure), credit for partly
+/// filled pattern lines, progress toward the end-game bonuses, and the risk of being forced to
+/// take tiles that can only go to the floor.
+pub fn evaluate(board: &PlayerBoard, game_state: &GameState) -> f32 {
+    board.score as f32
+        + board.projected_round_points() as f32
+        + PARTIAL_LINE_WEIGHT * partial_lines(board)
+        + WALL_PROGRESS_WEIGHT * wall_progress(board)
+        - FLOOR_RISK_WEIGHT * floor_risk(board, game_state)
+}
+
+/// How much `m` changes `evaluate` for the player making it.
+pub fn move_gain(game_state: &GameState, m: &Move) -> f32 {
+    let player_idx = game_state.current_player_idx;
+    let before = evaluate(&game_state.players[player_idx], game_state);
+    let mut next_state = game_state.clone();
+    next_state.apply_move(m);
+    evaluate(&next_state.players[player_idx], &next_state) - before
+}
+
+/// The wall column that `tile` goes to in `row`.
+pub fn wall_column(row: usize, tile: Tile) -> Option<usize> {
+    WALL_LAYOUT[row].iter().position(|&t| t == tile)
+}
+
+/// Wall tiles next to the slot that `tile` fills in `row`; 0 if the color has no slot there.
+pub fn adjacency(board: &PlayerBoard, row: usize, tile: Tile) -> usize {
+    let Some(col) = wall_column(row, tile) else { return 0 };
+    let neighbors = [
+        (row.checked_sub(1), Some(col)),
+        (Some(row + 1), Some(col)),
+        (Some(row), col.checked_sub(1)),
+        (Some(row), Some(col + 1)),
+    ];
+    neighbors.iter()
+        .filter_map(|&(r, c)| board.wall.get(r?)?.get(c?).copied().flatten())
+        .count()
+}
+
+/// Filled slots in wall column `col`.
+pub fn column_fill(board: &PlayerBoard, col: usize) -> usize {
+    (0..NUM_ROWS).filter(|&row| board.wall[row][col].is_some()).count()
+}
+
+/// Filled slots in the columns on either side of the slot that `tile` fills in `row`.
+pub fn neighbor_column_fill(board: &PlayerBoard, row: usize, tile: Tile) -> usize {
+    let Some(col) = wall_column(row, tile) else { return 0 };
+    let left = col.checked_sub(1).map_or(0, |c| column_fill(board, c));
+    let right = if col + 1 < NUM_COLS { column_fill(board, col + 1) } else { 0 };
+    left + right
+}
+
+/// Credit for pattern lines that are started but not full, by how full they are.
+pub fn partial_lines(board: &PlayerBoard) -> f32 {
+    board.pattern_lines.iter().enumerate()
+        .filter(|(idx, line)| !line.is_empty() && line.len() < idx + 1)
+        .map(|(idx, line)| line.len() as f32 / (idx + 1) as f32)
+        .sum()
+}
+
+/// Progress toward the row, column, and color bonuses: each bonus counts by its squared fill
+/// fraction, so nearly finished ones count the most.
+pub fn wall_progress(board: &PlayerBoard) -> f32 {
+    let progress = |filled: usize, bonus: f32| bonus * (filled as f32 / 5.0).powi(2);
+    let mut total = 0.0;
+    for row in &board.wall {
+        total += progress(row.iter().filter(|t| t.is_some()).count(), ROW_BONUS);
+    }
+    for col in 0..NUM_COLS {
+        total += progress(column_fill(board, col), COLUMN_BONUS);
+    }
+    for color in WALL_LAYOUT[0] {
+        total += progress(board.wall.iter().flatten().filter(|&&t| t == Some(color)).count(), COLOR_BONUS);
+    }
+    total
+}
+
+/// Tiles still on the table during drafting that no pattern line of `board` can take, shared
+/// among the players: what this player may end up forced to put on the floor.
+pub fn floor_risk(board: &PlayerBoard, game_state: &GameState) -> f32 {
+    if game_state.phase() != GamePhase::Drafting {
+        return 0.0;
+    }
+    let unplaceable = game_state.factories.iter().flatten()
+        .chain(game_state.center.iter())
+        .filter(|&&tile| !(0..NUM_ROWS).any(|row| board.is_placement_valid(row, tile)))
+        .count();
+    unplaceable as f32 / game_state.players.len() as f32
+}
@@ -0,0 +1,99 @@
+use crate::{
+    alpha_beta_ai::margin, mcts_heuristic_ai::heuristic_rollout, mcts_lib::RolloutCutoff, AIAgent, AgentConfig,
+};
+use azul_core::{GameState, Move};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::any::Any;
+
+const DEFAULT_ROLLOUTS: u32 = 10;
+
+/// Flat Monte Carlo: every legal move gets the same number of HeuristicAI rollouts to the end of
+/// the game, and the move with the best average margin over the best opponent is played. No
+/// tree, so each move's rollouts are independent of the others. A mid-tier benchmark between
+/// HeuristicAI and MCTS.
+///
+/// Each rollout starts from a determinized copy of the position, so a seeded game's bag order
+/// isn't visible.
+pub struct FlatMcAI {
+    rollouts_per_move: u32,
+    rng: StdRng,
+    move_values: Vec<(Move, f32)>,
+}
+
+impl FlatMcAI {
+    pub fn new(rollouts_per_move: u32) -> Self {
+        Self { rollouts_per_move: rollouts_per_move.max(1), rng: StdRng::from_entropy(), move_values: Vec::new() }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The average rollout margin of each legal move in the last `get_move`.
+    pub fn move_values(&self) -> &[(Move, f32)] {
+        &self.move_values
+    }
+
+    fn average_margin(&mut self, game_state: &GameState, m: &Move) -> f32 {
+        let player = game_state.current_player_idx;
+        let mut after_move = game_state.clone();
+        after_move.apply_move(m);
+        let total: f32 = (0..self.rollouts_per_move)
+            .map(|_| {
+                let mut start = after_move.clone();
+                start.determinize(&mut self.rng);
+                margin(&heuristic_rollout(&start, &mut self.rng, RolloutCutoff::default()), player)
+            })
+            .sum();
+        total / self.rollouts_per_move as f32
+    }
+}
+
+impl Default for FlatMcAI {
+    fn default() -> Self {
+        Self::new(DEFAULT_ROLLOUTS)
+    }
+}
+
+impl AIAgent for FlatMcAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        let moves = game_state.get_legal_moves();
+        self.move_values = moves.into_iter()
+            .map(|m| {
+                let value = self.average_margin(game_state, &m);
+                (m, value)
+            })
+            .collect();
+        let mut best: Option<&(Move, f32)> = None;
+        for entry in &self.move_values {
+            if best.is_none_or(|b| entry.1 > b.1) {
+                best = Some(entry);
+            }
+        }
+        best.map(|(m, _)| m.clone())
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "FlatMcAI"
+    }
+
+    fn reset(&mut self) {
+        self.move_values.clear();
+    }
+
+    /// `iterations` sets the rollouts per move.
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(rollouts) = config.iterations {
+            self.rollouts_per_move = rollouts.max(1);
+        }
+        if let Some(seed) = config.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+    }
+}
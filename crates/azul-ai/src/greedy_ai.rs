@@ -0,0 +1,60 @@
+use crate::{eval, AIAgent};
+use azul_core::{GameState, Move, MoveDestination};
+use std::any::Any;
+
+/// A one-ply agent: it previews every legal move and plays the one with the best expected score.
+/// Stronger than SimpleAI, cheaper than HeuristicAI's look at the table, and fast enough to use
+/// as a rollout policy.
+pub struct GreedyAI;
+
+impl GreedyAI {
+    /// The value of `m` for the player to move: the change in the points the round would score
+    /// if it ended now (completed lines minus floor penalty), plus the adjacency a partly filled
+    /// pattern line is building toward, weighted by how full the line is.
+    pub fn evaluate_move(game_state: &GameState, m: &Move) -> f32 {
+        Self::evaluate_move_in_place(&mut game_state.clone(), m)
+    }
+
+    /// `evaluate_move` for callers that walk one state: plays `m` on `game_state` and takes it
+    /// back, leaving the state as it was.
+    pub fn evaluate_move_in_place(game_state: &mut GameState, m: &Move) -> f32 {
+        let player_idx = game_state.current_player_idx;
+        let before = game_state.players[player_idx].projected_round_points();
+        let undo = game_state.apply_move_undoable(m);
+        let board = &game_state.players[player_idx];
+        let immediate = (board.projected_round_points() - before) as f32;
+
+        let potential = match m.destination {
+            MoveDestination::PatternLine(idx) if board.pattern_lines[idx].len() < idx + 1 => {
+                let fill = board.pattern_lines[idx].len() as f32 / (idx + 1) as f32;
+                eval::adjacency(board, idx, m.tile) as f32 * fill
+            }
+            _ => 0.0,
+        };
+        game_state.undo(undo);
+        immediate + potential
+    }
+}
+
+impl AIAgent for GreedyAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        let mut best: Option<(Move, f32)> = None;
+        let mut state = game_state.clone();
+        for m in game_state.get_legal_moves() {
+            let value = Self::evaluate_move_in_place(&mut state, &m);
+            // Ties keep the earlier move, so the choice is deterministic.
+            if best.as_ref().is_none_or(|(_, best_value)| value > *best_value) {
+                best = Some((m, value));
+            }
+        }
+        best.map(|(m, _)| m)
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "GreedyAI"
+    }
+}
@@ -1,15 +1,84 @@
-use crate::{
-    ai::AIAgent, GameState, Move, MoveDestination, MoveSource, PlayerBoard, Tile, WALL_LAYOUT,
-};
+use crate::{eval, AIAgent};
+use azul_core::{GameState, Move, MoveDestination, MoveSource, PlayerBoard, Tile};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
+/// The weights of the general move score that `HeuristicAI` falls back on when none of its
+/// priority rules applies. A missing field in a weights file keeps its default.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct HeuristicWeights {
+    /// Per tile that overflows to the floor, from a pattern line or by choosing the floor.
+    pub floor_tile_penalty: f32,
+    /// Per tile that lands on the pattern line.
+    pub tile_placed: f32,
+    /// For filling the pattern line, so it moves to the wall this round.
+    pub line_completion: f32,
+    /// Per wall tile next to the slot the line will fill.
+    pub adjacency: f32,
+    /// Per tile in the wall columns beside that slot.
+    pub column_progress: f32,
+}
+
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        Self { floor_tile_penalty: 20.0, tile_placed: 10.0, line_completion: 15.0, adjacency: 5.0, column_progress: 3.0 }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl HeuristicWeights {
+    /// Reads weights from a JSON file such as `{"line_completion": 18.0}`.
+    pub fn from_json_file(path: &str) -> Result<Self, String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("can't open weights file {}: {}", path, e))?;
+        serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| format!("invalid weights file {}: {}", path, e))
+    }
+
+    pub fn save_json_file(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("can't write weights file {}: {}", path, e))
+    }
+}
+
 /// An AI that uses a series of prioritized, rule-based heuristics to select a move.
 /// It plays strategically but does not look ahead more than one turn.
-pub struct HeuristicAI;
+#[derive(Default)]
+pub struct HeuristicAI {
+    pub weights: HeuristicWeights,
+}
+
+impl HeuristicAI {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_weights(weights: HeuristicWeights) -> Self {
+        Self { weights }
+    }
+
+    /// A position score in the units of the move score, for agents that search ahead with
+    /// HeuristicAI's judgement: points already scored or due this round (each worth a placed
+    /// tile), then the move-score terms for every started pattern line, minus the floor.
+    pub fn evaluate_board(&self, board: &PlayerBoard) -> f32 {
+        let w = &self.weights;
+        let mut value = (board.score as i32 + board.projected_round_points()) as f32 * w.tile_placed;
+        for (idx, line) in board.pattern_lines.iter().enumerate() {
+            let Some(&tile) = line.first() else { continue };
+            value += line.len() as f32 * w.tile_placed;
+            if line.len() == idx + 1 {
+                value += w.line_completion;
+            }
+            value += eval::adjacency(board, idx, tile) as f32 * w.adjacency;
+            value += eval::neighbor_column_fill(board, idx, tile) as f32 * w.column_progress;
+        }
+        value - board.floor_items_count() as f32 * w.floor_tile_penalty
+    }
+}
 
 impl AIAgent for HeuristicAI {
     /// Selects a move by evaluating heuristics in a specific order of priority.
-    /// The `&mut self` is included to conform to the AIAgent trait, but this AI is stateless.
     fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
         // Efficiency: Legal moves are generated once and passed to the helper functions.
         let legal_moves = game_state.get_legal_moves();
@@ -37,12 +106,16 @@ impl AIAgent for HeuristicAI {
         }
 
         // Main Heuristic: Find the best general-purpose move
-        find_best_general_move(game_state, &legal_moves)
+        find_best_general_move(game_state, &legal_moves, &self.weights)
     }
 
     fn as_any(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn name(&self) -> &str {
+        "HeuristicAI"
+    }
 }
 
 // --- Heuristic Functions (Updated to accept `&[Move]`) ---
@@ -59,11 +132,9 @@ fn find_big_grab_move(game_state: &GameState, legal_moves: &[Move]) -> Option<Mo
             if tile_count >= 3 {
                 let line = &current_player.pattern_lines[idx];
                 let space_available = (idx + 1) - line.len();
-                if tile_count == space_available {
-                    if (idx as i32) > best_row_index {
-                        best_row_index = idx as i32;
-                        best_option = Some(m.clone());
-                    }
+                if tile_count == space_available && (idx as i32) > best_row_index {
+                    best_row_index = idx as i32;
+                    best_option = Some(m.clone());
                 }
             }
         }
@@ -93,7 +164,7 @@ fn find_untouched_center_move(game_state: &GameState, legal_moves: &[Move]) -> O
     let best_column_placement = center_moves.iter()
         .filter_map(|m| if let MoveDestination::PatternLine(idx) = m.destination { Some((m, idx)) } else { None })
         .filter(|(m, idx)| count_tiles_at_source(game_state, &m.source, m.tile) >= 2 && *idx >= 2)
-        .max_by_key(|(m, idx)| calculate_column_progress(current_player, *idx, m.tile));
+        .max_by_key(|(m, idx)| eval::wall_column(*idx, m.tile).map_or(0, |col| eval::column_fill(current_player, col)));
     if let Some((best_move, _)) = best_column_placement { return Some((*best_move).clone()); }
 
     let best_single_tile_completion = center_moves.iter()
@@ -122,15 +193,15 @@ fn find_first_move_priority(game_state: &GameState, legal_moves: &[Move]) -> Opt
             let line = &current_player.pattern_lines[1];
             tile_count >= 2 - line.len()
         })
-        .max_by_key(|m| calculate_adjacency_score(current_player, 1, m.tile))
+        .max_by_key(|m| eval::adjacency(current_player, 1, m.tile))
         .cloned()
 }
 
-fn find_best_general_move(game_state: &GameState, legal_moves: &[Move]) -> Option<Move> {
+fn find_best_general_move(game_state: &GameState, legal_moves: &[Move], weights: &HeuristicWeights) -> Option<Move> {
     let current_player = &game_state.players[game_state.current_player_idx];
 
-    legal_moves.iter().max_by_key(|m| {
-        let mut score: i32 = 0;
+    let move_score = |m: &Move| -> f32 {
+        let mut score = 0.0;
         let tile_count = count_tiles_at_source(game_state, &m.source, m.tile);
 
         // Type Safety: Use a match statement to handle different destinations.
@@ -139,27 +210,29 @@ fn find_best_general_move(game_state: &GameState, legal_moves: &[Move]) -> Optio
                 let line = &current_player.pattern_lines[idx];
                 let space_available = (idx + 1) - line.len();
                 let tiles_placed = tile_count.min(space_available);
-                let tiles_to_floor = (tile_count as i32 - space_available as i32).max(0);
+                let tiles_to_floor = tile_count.saturating_sub(space_available);
 
-                score -= tiles_to_floor * 20;
-                score += (tiles_placed as i32) * 10;
+                score -= tiles_to_floor as f32 * weights.floor_tile_penalty;
+                score += tiles_placed as f32 * weights.tile_placed;
                 if tile_count >= space_available {
-                    score += 15;
-                }
-                score += calculate_adjacency_score(current_player, idx, m.tile) * 5;
-
-                if let Some(col_idx) = WALL_LAYOUT[idx].iter().position(|&t| t == m.tile) {
-                    if col_idx > 0 { score += calculate_column_progress_by_index(current_player, col_idx - 1) * 3; }
-                    if col_idx < 4 { score += calculate_column_progress_by_index(current_player, col_idx + 1) * 3; }
+                    score += weights.line_completion;
                 }
+                score += eval::adjacency(current_player, idx, m.tile) as f32 * weights.adjacency;
+                score += eval::neighbor_column_fill(current_player, idx, m.tile) as f32 * weights.column_progress;
             }
             MoveDestination::Floor => {
                 // The `-1` ensures this is always slightly worse than any non-flooring move.
-                score = -((tile_count as i32) * 20) - 1;
+                score = -(tile_count as f32 * weights.floor_tile_penalty) - 1.0;
             }
         }
         score
-    }).cloned()
+    };
+
+    // On ties the last move wins, as with `max_by_key`.
+    legal_moves.iter()
+        .map(|m| (m, move_score(m)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(m, _)| m.clone())
 }
 
 // --- Utility Functions (Unchanged but used by the refactored code) ---
@@ -174,26 +247,3 @@ fn count_tiles_at_source(game_state: &GameState, source: &MoveSource, tile: Tile
         MoveSource::Center => game_state.center.iter().filter(|&&t| t == tile).count(),
     }
 }
-
-fn calculate_column_progress(player: &PlayerBoard, row_idx: usize, tile: Tile) -> i32 {
-    if let Some(col_idx) = WALL_LAYOUT[row_idx].iter().position(|&t| t == tile) {
-        return calculate_column_progress_by_index(player, col_idx);
-    }
-    0
-}
-
-fn calculate_column_progress_by_index(player: &PlayerBoard, col_idx: usize) -> i32 {
-    (0..5).filter(|&r| player.wall[r][col_idx].is_some()).count() as i32
-}
-
-fn calculate_adjacency_score(player: &PlayerBoard, row_idx: usize, tile: Tile) -> i32 {
-    if let Some(col_idx) = WALL_LAYOUT[row_idx].iter().position(|&t| t == tile) {
-        let mut score = 0;
-        if col_idx > 0 && player.wall[row_idx][col_idx - 1].is_some() { score += 1; }
-        if col_idx < 4 && player.wall[row_idx][col_idx + 1].is_some() { score += 1; }
-        if row_idx > 0 && player.wall[row_idx - 1][col_idx].is_some() { score += 1; }
-        if row_idx < 4 && player.wall[row_idx + 1][col_idx].is_some() { score += 1; }
-        return score;
-    }
-    0
-}
\ No newline at end of file
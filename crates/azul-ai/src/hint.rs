@@ -0,0 +1,80 @@
+use crate::{mcts_heuristic_ai::MctsHeuristicAI, AIAgent};
+use azul_core::{GameState, Move, MoveDestination, MoveSource};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A suggested move for the player to move, with what it does for them this round.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    #[cfg_attr(feature = "serde", serde(rename = "move"))]
+    pub best_move: Move,
+    /// The change in what the round would score if it ended now: completed lines minus the
+    /// floor penalty.
+    pub expected_points: i32,
+    pub tiles_taken: usize,
+    /// Tiles that don't fit on the pattern line (or all of them, for a floor move) and are lost
+    /// to the floor.
+    pub tiles_to_floor: usize,
+    /// The search's value for the move, from the player's point of view.
+    pub value: Option<f32>,
+    /// One sentence for the player, e.g. "Take 3 blue from factory 2 onto line 4: +4 points
+    /// this round, and every tile fits on the line."
+    pub explanation: String,
+}
+
+impl Hint {
+    /// Describes `m` in `game_state` without searching.
+    pub fn for_move(game_state: &GameState, m: &Move, value: Option<f32>) -> Self {
+        let player_idx = game_state.current_player_idx;
+        let board = &game_state.players[player_idx];
+        let tiles_taken = match m.source {
+            MoveSource::Factory(idx) => game_state.factories[idx].iter().filter(|&&t| t == m.tile).count(),
+            MoveSource::Center => game_state.center.iter().filter(|&&t| t == m.tile).count(),
+        };
+        let mut next_state = game_state.clone();
+        next_state.apply_move(m);
+        let next_board = &next_state.players[player_idx];
+        let on_line = match m.destination {
+            MoveDestination::PatternLine(idx) => next_board.pattern_lines[idx].len() - board.pattern_lines[idx].len(),
+            MoveDestination::Floor => 0,
+        };
+        let tiles_to_floor = tiles_taken - on_line;
+        let expected_points = next_board.projected_round_points() - board.projected_round_points();
+
+        let source = match m.source {
+            MoveSource::Factory(idx) => format!("factory {}", idx + 1),
+            MoveSource::Center => "the center".to_string(),
+        };
+        let destination = match m.destination {
+            MoveDestination::PatternLine(idx) => format!("line {}", idx + 1),
+            MoveDestination::Floor => "the floor".to_string(),
+        };
+        let floor = match (&m.destination, tiles_to_floor) {
+            (MoveDestination::Floor, _) => "and every tile goes to the floor".to_string(),
+            (_, 0) => "and every tile fits on the line".to_string(),
+            (_, n) => format!("but {} of {} tiles go to the floor", n, tiles_taken),
+        };
+        let marker = if m.source == MoveSource::Center && game_state.first_player_marker_in_center {
+            " (taking the first-player marker)"
+        } else {
+            ""
+        };
+        let color = format!("{:?}", m.tile).to_lowercase();
+        let unit = if expected_points.abs() == 1 { "point" } else { "points" };
+        let explanation = format!(
+            "Take {} {} from {} onto {}{}: {:+} {} this round, {}.",
+            tiles_taken, color, source, destination, marker, expected_points, unit, floor
+        );
+
+        Self { best_move: m.clone(), expected_points, tiles_taken, tiles_to_floor, value, explanation }
+    }
+}
+
+/// Runs an `iterations`-long MCTS search for the player to move and explains the move it
+/// finds. `None` if there is no legal move.
+pub fn hint(game_state: &GameState, iterations: u32) -> Option<Hint> {
+    let mut engine = MctsHeuristicAI::new(iterations.max(1));
+    let best_move = engine.get_move(game_state)?;
+    Some(Hint::for_move(game_state, &best_move, engine.root_value()))
+}
@@ -1,4 +1,5 @@
-use crate::{ai::AIAgent, GameState, Move};
+use crate::AIAgent;
+use azul_core::{GameState, Move};
 use std::any::Any; // Add this import
 
 // The HumanAgent is a placeholder for web UI interaction.
@@ -14,4 +15,8 @@ impl AIAgent for HumanAgent {
     fn as_any(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn name(&self) -> &str {
+        "HumanAgent"
+    }
 }
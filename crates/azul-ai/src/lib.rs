@@ -0,0 +1,181 @@
+//! AI agents for the Azul rules engine: rule-based heuristics, MCTS, and the neural network
+//! agent (behind the "nn" feature).
+
+use azul_core::{GameState, Move, Ranking};
+use mcts_lib::MctsConfig;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+pub mod alpha_beta_ai;
+pub mod analysis;
+pub mod random_ai;
+pub mod simple_ai;
+pub mod difficulty;
+pub mod endgame_solver;
+pub mod eval;
+pub mod flat_mc_ai;
+pub mod greedy_ai;
+pub mod heuristic_ai;
+pub mod hint;
+pub mod human_agent;
+pub mod lookahead_ai;
+pub mod max_n_ai;
+pub mod mcts_lib;
+pub mod mcts_heuristic_ai;
+pub mod puzzle;
+pub mod registry;
+pub mod review;
+pub mod training_io;
+
+// These modules will only be compiled when the "nn" feature is enabled.
+#[cfg(feature = "nn")]
+pub mod nn;
+#[cfg(feature = "nn")]
+pub mod mcts_nn_ai;
+
+
+/// Settings that can be changed on an existing agent with `AIAgent::configure`. Each agent
+/// applies the ones that mean something to it and ignores the rest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentConfig {
+    /// Search iterations per move, for MCTS agents.
+    pub iterations: Option<u32>,
+    /// Search depth in plies, for the alpha-beta and MaxN agents.
+    pub depth: Option<u32>,
+    /// Reseeds agents that make random choices.
+    pub seed: Option<u64>,
+    /// Search settings for MCTS agents.
+    pub mcts: Option<MctsConfig>,
+}
+
+/// How much an agent searched for its last move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchStats {
+    /// MCTS simulations run; 0 for a move found another way, e.g. by the endgame solver.
+    pub iterations: u32,
+    /// Nodes in the search tree afterwards, or positions searched by a tree-less search.
+    pub nodes: u64,
+}
+
+/// How far a search begun by `AIAgent::begin_search` has got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchProgress {
+    /// Simulations run so far.
+    pub iterations: u32,
+    /// Simulations the agent means to run; 0 when it already knows its move, e.g. from the
+    /// endgame solver.
+    pub target: u32,
+}
+
+impl SearchProgress {
+    pub fn is_done(&self) -> bool {
+        self.iterations >= self.target
+    }
+}
+
+/// A player. The tools that play many games at once call `get_move` from a bounded pool of
+/// worker threads (`--threads`), so an agent that searches in parallel should split its work
+/// with rayon, which runs it on that same pool, instead of starting threads of its own.
+pub trait AIAgent {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move>;
+    fn as_any(&mut self) -> &mut dyn Any;
+
+    /// A short name for logs and match reports, e.g. "MctsHeuristicAI".
+    fn name(&self) -> &str;
+
+    /// Gets the agent ready for a new game, e.g. by dropping an MCTS search tree, while keeping
+    /// what is expensive to rebuild, such as a loaded network.
+    fn reset(&mut self) {}
+
+    /// Applies the settings in `config` that this agent has.
+    fn configure(&mut self, _config: &AgentConfig) {}
+
+    /// Starts thinking in the background about `game_state`, a position where someone else is
+    /// to move. The next `get_move` stops pondering and reuses what it found. Agents that
+    /// can't ponder ignore this.
+    fn start_ponder(&mut self, _game_state: &GameState) {}
+
+    /// Stops background thinking started by `start_ponder`, e.g. when the game ends.
+    fn stop_ponder(&mut self) {}
+
+    /// How much searching went into the last `get_move`, for agents that search.
+    fn search_stats(&self) -> Option<SearchStats> {
+        None
+    }
+
+    /// Starts a search of `game_state` that `search_step` then runs a slice at a time, for
+    /// callers that can't block for a whole `get_move`, such as a browser's main thread.
+    /// Returns `None` if the agent can't search in slices; `get_move` is the way to ask it.
+    fn begin_search(&mut self, _game_state: &GameState) -> Option<SearchProgress> {
+        None
+    }
+
+    /// Runs up to `iterations` more simulations of the search begun by `begin_search`.
+    /// Returns `None` if there is no such search.
+    fn search_step(&mut self, _iterations: u32) -> Option<SearchProgress> {
+        None
+    }
+
+    /// Ends the search begun by `begin_search` and returns the move it found so far.
+    fn end_search(&mut self) -> Option<Move> {
+        None
+    }
+}
+
+// --- Training Data ---
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrainingData {
+    pub state_input: Vec<f32>,
+    pub mcts_policy: Vec<f32>,
+    /// 1.0 at the policy indices of the legal moves, 0.0 elsewhere. Data recorded before the
+    /// mask was added has none, and every index then counts as legal.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub legal_mask: Vec<f32>,
+    /// The final result for the player to move, from `rank_outcome`.
+    pub outcome: f32,
+    /// The final score difference for the player to move, from `score_margin`. Games that
+    /// ended by resignation have none, and neither does data recorded before it was added.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub score_margin: Option<f32>,
+}
+
+/// A player's result as a value target in [-1, 1], spread evenly by finishing place: 1.0 for
+/// first, -1.0 for last, and, in a 4-player game, 1/3 and -1/3 in between. Tied players share
+/// the average of the places they cover, so a 2-player draw is 0.0.
+pub fn rank_outcome(rankings: &[Ranking], player: usize) -> f32 {
+    let Some(rank) = rankings.iter().find(|r| r.player == player).map(|r| r.rank) else { return 0.0 };
+    if rankings.len() < 2 {
+        return 0.0;
+    }
+    let tied = rankings.iter().filter(|r| r.rank == rank).count();
+    let place = rank as f32 + (tied - 1) as f32 / 2.0;
+    1.0 - 2.0 * (place - 1.0) / (rankings.len() - 1) as f32
+}
+
+/// The score difference that `score_margin` maps to about ±0.76 (tanh(1)).
+pub const SCORE_MARGIN_SCALE: f32 = 20.0;
+
+/// A player's final score minus the best of the other players' scores, squashed into (-1, 1)
+/// by `tanh(difference / SCORE_MARGIN_SCALE)`. The leader's margin is its lead over the
+/// runner-up; everyone else's is how far they finished behind the leader.
+pub fn score_margin(rankings: &[Ranking], player: usize) -> f32 {
+    let Some(own) = rankings.iter().find(|r| r.player == player).map(|r| r.score as f32) else { return 0.0 };
+    let Some(best_other) = rankings.iter().filter(|r| r.player != player).map(|r| r.score as f32).reduce(f32::max) else {
+        return 0.0;
+    };
+    ((own - best_other) / SCORE_MARGIN_SCALE).tanh()
+}
+
+/// A position labeled by a deep search, used as a fixed benchmark for comparing models.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LabeledPosition {
+    pub state: GameState,
+    pub best_move: Move,
+    pub value: f32,
+    pub search_iterations: u32,
+}
@@ -0,0 +1,107 @@
+use crate::{
+    alpha_beta_ai::{margin, ordered_moves},
+    heuristic_ai::{HeuristicAI, HeuristicWeights},
+    AIAgent, AgentConfig,
+};
+use azul_core::{GamePhase, GameState, Move};
+use std::any::Any;
+
+const DEFAULT_DEPTH: u32 = 2;
+
+/// HeuristicAI's judgement with a few plies of lookahead: every move sequence up to `depth`
+/// plies (the agent's move, then the replies) is searched, and the positions at the end are
+/// scored with `HeuristicAI::evaluate_board`. Opponents are assumed to minimize the agent's
+/// margin, with alpha-beta pruning. Deterministic and far cheaper than MCTS.
+pub struct LookaheadAI {
+    depth: u32,
+    evaluator: HeuristicAI,
+}
+
+impl LookaheadAI {
+    pub fn new(depth: u32) -> Self {
+        Self { depth: depth.max(1), evaluator: HeuristicAI::new() }
+    }
+
+    pub fn with_weights(mut self, weights: HeuristicWeights) -> Self {
+        self.evaluator = HeuristicAI::with_weights(weights);
+        self
+    }
+
+    /// Each player's evaluation; at the end of the round the walls are tiled first, and after
+    /// the final round the bonuses are added.
+    fn leaf_values(&self, state: &GameState) -> Vec<f32> {
+        if state.phase() == GamePhase::Drafting {
+            return state.players.iter().map(|p| self.evaluator.evaluate_board(p)).collect();
+        }
+        let mut tiled = state.clone();
+        tiled.run_tiling_phase();
+        if tiled.end_game_triggered {
+            tiled.apply_end_game_scoring();
+        }
+        tiled.players.iter().map(|p| self.evaluator.evaluate_board(p)).collect()
+    }
+
+    fn search(&self, state: &mut GameState, depth: u32, mut alpha: f32, mut beta: f32, root_player: usize) -> f32 {
+        if depth == 0 || state.phase() != GamePhase::Drafting {
+            return margin(&self.leaf_values(state), root_player);
+        }
+
+        let maximizing = state.current_player_idx == root_player;
+        let mut best = if maximizing { f32::NEG_INFINITY } else { f32::INFINITY };
+        for m in ordered_moves(state) {
+            let undo = state.apply_move_undoable(&m);
+            let value = self.search(state, depth - 1, alpha, beta, root_player);
+            state.undo(undo);
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl Default for LookaheadAI {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH)
+    }
+}
+
+impl AIAgent for LookaheadAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        let root_player = game_state.current_player_idx;
+        let mut state = game_state.clone();
+        let mut alpha = f32::NEG_INFINITY;
+        let mut best_move = None;
+        for m in ordered_moves(&mut state) {
+            let undo = state.apply_move_undoable(&m);
+            let value = self.search(&mut state, self.depth - 1, alpha, f32::INFINITY, root_player);
+            state.undo(undo);
+            if best_move.is_none() || value > alpha {
+                alpha = value;
+                best_move = Some(m);
+            }
+        }
+        best_move
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "LookaheadAI"
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(depth) = config.depth {
+            self.depth = depth.max(1);
+        }
+    }
+}
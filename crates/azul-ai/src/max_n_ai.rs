@@ -0,0 +1,143 @@
+use crate::{
+    alpha_beta_ai::{leaf_values, margin, AlphaBetaAI},
+    AIAgent, AgentConfig,
+};
+use azul_core::{GamePhase, GameState, Move};
+use std::any::Any;
+
+const DEFAULT_DEPTH: u32 = 2;
+const DEFAULT_MAX_NODES: u64 = 200_000;
+
+/// A depth-limited MaxN search for 3-4 player games. Each leaf gets a value vector holding every
+/// player's margin over the best of the others, and at each node the player to move picks the
+/// child that is best for itself. Leaves are valued as in `AlphaBetaAI`.
+///
+/// MaxN can't prune, so the search gets a node budget. When a search runs over it, the move
+/// comes from a paranoid alpha-beta search of the same depth instead, which cuts far more of
+/// the tree.
+pub struct MaxNAI {
+    depth: u32,
+    max_nodes: u64,
+    nodes_searched: u64,
+    root_values: Vec<(Move, Vec<f32>)>,
+    used_fallback: bool,
+}
+
+impl MaxNAI {
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth: depth.max(1),
+            max_nodes: DEFAULT_MAX_NODES,
+            nodes_searched: 0,
+            root_values: Vec::new(),
+            used_fallback: false,
+        }
+    }
+
+    pub fn with_max_nodes(mut self, max_nodes: u64) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// The value vector backed up for each root move by the last search; empty if it fell back.
+    /// Useful for checking MCTS value estimates against exact backups in small positions.
+    pub fn root_values(&self) -> &[(Move, Vec<f32>)] {
+        &self.root_values
+    }
+
+    /// Whether the last move came from the paranoid fallback.
+    pub fn used_fallback(&self) -> bool {
+        self.used_fallback
+    }
+
+    /// The backed-up value vector of `state`, or `None` once the node budget is spent.
+    fn search(&mut self, state: &mut GameState, depth: u32) -> Option<Vec<f32>> {
+        self.nodes_searched += 1;
+        if self.nodes_searched > self.max_nodes {
+            return None;
+        }
+        if depth == 0 || state.phase() != GamePhase::Drafting {
+            return Some(margins(&leaf_values(state)));
+        }
+
+        let mover = state.current_player_idx;
+        let mut best: Option<Vec<f32>> = None;
+        for m in state.get_legal_moves() {
+            let undo = state.apply_move_undoable(&m);
+            let values = self.search(state, depth - 1);
+            state.undo(undo);
+            let values = values?;
+            if best.as_ref().is_none_or(|b| values[mover] > b[mover]) {
+                best = Some(values);
+            }
+        }
+        best
+    }
+
+    fn search_root(&mut self, game_state: &GameState) -> Option<Vec<(Move, Vec<f32>)>> {
+        let mut root_values = Vec::new();
+        let mut state = game_state.clone();
+        for m in game_state.get_legal_moves() {
+            let undo = state.apply_move_undoable(&m);
+            let values = self.search(&mut state, self.depth - 1);
+            state.undo(undo);
+            root_values.push((m, values?));
+        }
+        Some(root_values)
+    }
+}
+
+impl Default for MaxNAI {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH)
+    }
+}
+
+impl AIAgent for MaxNAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        self.nodes_searched = 0;
+        let root_player = game_state.current_player_idx;
+        match self.search_root(game_state) {
+            Some(root_values) => {
+                self.used_fallback = false;
+                self.root_values = root_values;
+                let mut best: Option<&(Move, Vec<f32>)> = None;
+                for entry in &self.root_values {
+                    if best.is_none_or(|b| entry.1[root_player] > b.1[root_player]) {
+                        best = Some(entry);
+                    }
+                }
+                best.map(|(m, _)| m.clone())
+            }
+            None => {
+                self.used_fallback = true;
+                self.root_values.clear();
+                AlphaBetaAI::new(self.depth).get_move(game_state)
+            }
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "MaxNAI"
+    }
+
+    fn reset(&mut self) {
+        self.nodes_searched = 0;
+        self.root_values.clear();
+        self.used_fallback = false;
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(depth) = config.depth {
+            self.depth = depth.max(1);
+        }
+    }
+}
+
+fn margins(values: &[f32]) -> Vec<f32> {
+    (0..values.len()).map(|player| margin(values, player)).collect()
+}
@@ -0,0 +1,272 @@
+use crate::{
+    alpha_beta_ai::margin,
+    endgame_solver::EndgameSolver,
+    eval,
+    heuristic_ai::HeuristicAI,
+    mcts_lib::{Mcts, MctsConfig, MctsPolicy, Ponder, RolloutCutoff, SearchAnalysis, SteppedSearch},
+    AIAgent, AgentConfig, SearchProgress, SearchStats,
+};
+use azul_core::{GamePhase, GameState, Move};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Rollout margins are divided by this to land in roughly [-1, 1], the range the PUCT
+/// exploration term is tuned for.
+pub const VALUE_SCALE: f32 = 100.0;
+
+#[derive(Clone)]
+struct HeuristicPolicy {
+    cutoff: RolloutCutoff,
+    prior_temperature: f32,
+    /// Draws the refills in rollouts.
+    rng: RefCell<StdRng>,
+}
+
+impl MctsPolicy for HeuristicPolicy {
+    // MODIFIED: This function now runs a simulation to get a value,
+    // which is required by the new AlphaGo-style search algorithm.
+    fn evaluate(&self, game_state: &GameState) -> (f32, HashMap<Move, f32>) {
+        // Priors: a softmax over how much each move improves the static evaluation.
+        let gains: Vec<(Move, f32)> = game_state.get_legal_moves().into_iter()
+            .map(|m| {
+                let gain = eval::move_gain(game_state, &m);
+                (m, gain)
+            })
+            .collect();
+        let best_gain = gains.iter().map(|(_, gain)| *gain).fold(f32::NEG_INFINITY, f32::max);
+        let weights: Vec<f32> = gains.iter().map(|(_, gain)| ((gain - best_gain) / self.prior_temperature).exp()).collect();
+        let total: f32 = weights.iter().sum();
+        let policy = gains.into_iter().zip(weights).map(|((m, _), weight)| (m, weight / total)).collect();
+
+        // The value part: run one simulation and score it as the mover's margin over the best
+        // opponent. The search backs values up as zero-sum, so a raw score would have each
+        // player minimizing its opponent's score instead of maximizing its own.
+        let scores = self.run_simulation(game_state);
+        let value = margin(&scores, game_state.current_player_idx) / VALUE_SCALE;
+        
+        (value, policy)
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = RefCell::new(StdRng::seed_from_u64(seed));
+    }
+}
+
+// Added a helper function for the simulation logic.
+impl HeuristicPolicy {
+    fn run_simulation(&self, game_state: &GameState) -> Vec<f32> {
+        // Rollouts must not follow a seeded game's draw sequence, or the search would see the
+        // real future refills.
+        heuristic_rollout(game_state, &mut *self.rng.borrow_mut(), self.cutoff)
+    }
+}
+
+/// Plays `game_state` out with HeuristicAI in every seat, drawing refills from `rng`, and
+/// returns each player's value: the final scores, or `eval::evaluate` where `cutoff` stops the
+/// rollout first.
+pub(crate) fn heuristic_rollout<R: Rng + ?Sized>(game_state: &GameState, rng: &mut R, cutoff: RolloutCutoff) -> Vec<f32> {
+    let mut sim_state = game_state.clone();
+    let mut simulation_agent = HeuristicAI::new();
+    let mut moves = 0;
+    let mut rounds = 0;
+    loop {
+        if cutoff.moves.is_some_and(|limit| moves >= limit) || cutoff.rounds.is_some_and(|limit| rounds >= limit) {
+            return sim_state.players.iter().map(|p| eval::evaluate(p, &sim_state)).collect();
+        }
+        let next_move = match sim_state.phase() {
+            GamePhase::Drafting => match simulation_agent.get_move(&sim_state) {
+                Some(best_move) => Some(best_move),
+                None => break,
+            },
+            GamePhase::Tiling => None,
+            GamePhase::GameOver => break,
+        };
+        if sim_state.advance_with_rng(next_move.as_ref(), rng).is_err() {
+            break;
+        }
+        if next_move.is_some() {
+            moves += 1;
+        } else {
+            rounds += 1;
+        }
+    }
+    sim_state.players.iter().map(|p| p.score as f32).collect()
+}
+
+pub struct MctsHeuristicAI {
+    mcts: Option<Mcts<HeuristicPolicy>>,
+    iterations: u32,
+    config: MctsConfig,
+    /// Seeds each new search tree, if set by `configure`.
+    seed: Option<u64>,
+    ponder: Option<Ponder<HeuristicPolicy>>,
+    last_search: Option<SearchStats>,
+    stepped: Option<SteppedSearch>,
+}
+
+impl MctsHeuristicAI {
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            mcts: None,
+            iterations,
+            config: MctsConfig::default(),
+            seed: None,
+            ponder: None,
+            last_search: None,
+            stepped: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: MctsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn new_tree(&self, game_state: &GameState) -> Mcts<HeuristicPolicy> {
+        let policy = HeuristicPolicy {
+            cutoff: self.config.rollout_cutoff,
+            prior_temperature: self.config.prior_temperature,
+            rng: RefCell::new(StdRng::from_entropy()),
+        };
+        let mut mcts = Mcts::new(game_state.clone(), policy, self.config.clone());
+        if let Some(seed) = self.seed {
+            mcts.reseed(seed);
+        }
+        mcts
+    }
+
+    /// The search value of the last chosen move, if a search has been run.
+    pub fn root_value(&self) -> Option<f32> {
+        self.mcts.as_ref().and_then(|mcts| mcts.root_value())
+    }
+
+    /// The `top_n` most visited moves of the last search and its principal variation, or `None`
+    /// if no search has been run (or the last move was solved exactly). Not available while
+    /// pondering.
+    pub fn analysis(&self, top_n: usize) -> Option<SearchAnalysis> {
+        self.mcts.as_ref()
+            .filter(|mcts| mcts.tree[0].visit_count > 0)
+            .map(|mcts| mcts.analysis(top_n))
+    }
+
+    /// Gets ready to search `game_state`: returns the endgame solver's move if it settles the
+    /// position, and otherwise moves the tree to `game_state`.
+    fn prepare_search(&mut self, game_state: &GameState) -> Option<Move> {
+        self.stop_ponder();
+        if self.config.endgame_nodes > 0 {
+            if let Some(solution) = EndgameSolver::new(self.config.endgame_nodes).solve(game_state) {
+                self.last_search = Some(SearchStats { iterations: 0, nodes: solution.nodes_searched });
+                // Moves the tree along too, so that root_value and analysis don't describe an
+                // earlier position.
+                if let Some(mcts) = &mut self.mcts {
+                    mcts.sync_tree_with_state(game_state);
+                }
+                return Some(solution.best_move);
+            }
+        }
+        if self.mcts.is_none() {
+            self.mcts = Some(self.new_tree(game_state));
+        }
+        self.mcts.as_mut().unwrap().sync_tree_with_state(game_state);
+        None
+    }
+}
+
+impl AIAgent for MctsHeuristicAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        if let Some(solved) = self.prepare_search(game_state) {
+            return Some(solved);
+        }
+        let mcts = self.mcts.as_mut().unwrap();
+        mcts.run_search(self.iterations);
+        self.last_search = Some(SearchStats { iterations: self.iterations, nodes: mcts.tree.len() as u64 });
+        mcts.best_move()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "MctsHeuristicAI"
+    }
+
+    fn reset(&mut self) {
+        self.stop_ponder();
+        self.mcts = None;
+        self.stepped = None;
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        self.stop_ponder();
+        if let Some(iterations) = config.iterations {
+            self.iterations = iterations;
+        }
+        if let Some(mcts_config) = &config.mcts {
+            self.config = mcts_config.clone();
+            if let Some(mcts) = &mut self.mcts {
+                mcts.config = mcts_config.clone();
+                mcts.policy_handler.cutoff = mcts_config.rollout_cutoff;
+                mcts.policy_handler.prior_temperature = mcts_config.prior_temperature;
+            }
+        }
+        if let Some(seed) = config.seed {
+            self.seed = Some(seed);
+            if let Some(mcts) = &mut self.mcts {
+                mcts.reseed(seed);
+            }
+        }
+    }
+
+    fn start_ponder(&mut self, game_state: &GameState) {
+        self.stop_ponder();
+        let mut mcts = self.mcts.take()
+            .unwrap_or_else(|| self.new_tree(game_state));
+        mcts.sync_tree_with_state(game_state);
+        self.ponder = Some(mcts.ponder());
+    }
+
+    fn stop_ponder(&mut self) {
+        if let Some(ponder) = self.ponder.take() {
+            self.mcts = Some(ponder.stop());
+        }
+    }
+
+    fn search_stats(&self) -> Option<SearchStats> {
+        self.last_search
+    }
+
+    fn begin_search(&mut self, game_state: &GameState) -> Option<SearchProgress> {
+        let stepped = match self.prepare_search(game_state) {
+            Some(solved) => SteppedSearch::Solved(solved),
+            None => SteppedSearch::Tree { iterations: 0 },
+        };
+        let progress = stepped.progress(self.iterations);
+        self.stepped = Some(stepped);
+        Some(progress)
+    }
+
+    fn search_step(&mut self, iterations: u32) -> Option<SearchProgress> {
+        let stepped = self.stepped.as_mut()?;
+        if let SteppedSearch::Tree { iterations: done } = stepped {
+            let slice = iterations.min(self.iterations.saturating_sub(*done));
+            self.mcts.as_mut().expect("a stepped search has a tree").run_search(slice);
+            *done += slice;
+        }
+        Some(stepped.progress(self.iterations))
+    }
+
+    fn end_search(&mut self) -> Option<Move> {
+        match self.stepped.take()? {
+            SteppedSearch::Solved(solved) => Some(solved),
+            SteppedSearch::Tree { iterations } => {
+                let mcts = self.mcts.as_ref()?;
+                self.last_search = Some(SearchStats { iterations, nodes: mcts.tree.len() as u64 });
+                mcts.best_move()
+            }
+        }
+    }
+}
@@ -0,0 +1,612 @@
+use crate::SearchProgress;
+use azul_core::{compact::CompactState, GamePhase, GameState, Move};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Iterations a pondering thread runs between checks for `Ponder::stop`.
+const PONDER_BATCH: u32 = 64;
+/// The tree size at which pondering pauses when `MctsConfig::max_nodes` isn't set, so that a
+/// long think by the opponent can't exhaust memory.
+const PONDER_MAX_NODES: usize = 2_000_000;
+/// Added to the value of every node on the path to a leaf that is waiting in a batch, as if
+/// the player who chose each move there had lost, so that the rest of the batch looks elsewhere.
+const VIRTUAL_LOSS: f32 = 1.0;
+
+/// Where a rollout stops early and is scored with `eval::evaluate` instead of played to the
+/// end of the game. Both limits unset means full rollouts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RolloutCutoff {
+    /// Drafting moves after which the rollout stops.
+    pub moves: Option<u32>,
+    /// Round ends (tiling included) after which the rollout stops.
+    pub rounds: Option<u32>,
+}
+
+/// Search parameters. Options in agent specs (`mctsheuristic:5000:cpuct=2.0`) are parsed by
+/// `set_option`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsConfig {
+    /// The PUCT exploration constant.
+    pub c_puct: f32,
+    /// First-play urgency: the value assumed for a move that hasn't been visited yet.
+    pub fpu: f32,
+    /// Once the tree holds this many nodes, leaves are still evaluated but no longer expanded.
+    pub max_nodes: Option<usize>,
+    /// How many sampled refills a chance node keeps before it starts revisiting them.
+    pub max_chance_outcomes: usize,
+    /// Leaves the bag order and a seeded game's RNG out of the positions the policy evaluates,
+    /// so that the search can't use them. Together with the sampled refills at chance nodes,
+    /// this makes the search an information-set MCTS.
+    pub determinize: bool,
+    /// Node budget for the exact `EndgameSolver` in the final round; 0 turns it off.
+    pub endgame_nodes: u64,
+    /// Early stopping for rollouts, for policies that play them.
+    pub rollout_cutoff: RolloutCutoff,
+    /// Softmax temperature, in points, for priors computed from `eval::move_gain` by policies
+    /// without a network. Lower trusts the evaluation more; infinity gives uniform priors.
+    pub prior_temperature: f32,
+    /// Leaves selected (with virtual loss) before they are evaluated together by
+    /// `MctsPolicy::evaluate_batch`. 1 evaluates each leaf as soon as it is reached.
+    pub batch_size: usize,
+    /// Stops `run_search` early once this much time has passed, even if it has iterations
+    /// left to run.
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            c_puct: 1.41,
+            fpu: 0.0,
+            max_nodes: None,
+            max_chance_outcomes: 8,
+            determinize: true,
+            endgame_nodes: 100_000,
+            rollout_cutoff: RolloutCutoff::default(),
+            prior_temperature: 2.0,
+            batch_size: 1,
+            time_limit: None,
+        }
+    }
+}
+
+impl MctsConfig {
+    /// The defaults with each `key=value` option applied in turn.
+    pub fn from_options<'a>(options: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        let mut config = Self::default();
+        for option in options {
+            config.set_option(option)?;
+        }
+        Ok(config)
+    }
+
+    /// Applies one `key=value` option: `cpuct`, `fpu`, `maxnodes`, `chance`, `determinize`,
+    /// `endgame`, `rolloutdepth`, `rolloutrounds`, `priortemp`, `batch`, or `time` (seconds).
+    pub fn set_option(&mut self, option: &str) -> Result<(), String> {
+        let (key, value) = option.split_once('=')
+            .ok_or_else(|| format!("expected key=value, got '{}'", option))?;
+        self.set(key, value)
+    }
+
+    /// Sets the option `key` to `value`; see `set_option` for the keys.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("invalid value for {}: '{}'", key, value);
+        match key.trim().to_lowercase().as_str() {
+            "cpuct" => self.c_puct = value.parse().map_err(|_| invalid())?,
+            "fpu" => self.fpu = value.parse().map_err(|_| invalid())?,
+            "maxnodes" => self.max_nodes = Some(value.parse().map_err(|_| invalid())?),
+            "chance" => self.max_chance_outcomes = value.parse::<usize>().map_err(|_| invalid())?.max(1),
+            "determinize" => self.determinize = value.parse().map_err(|_| invalid())?,
+            "endgame" => self.endgame_nodes = value.parse().map_err(|_| invalid())?,
+            "rolloutdepth" => self.rollout_cutoff.moves = Some(value.parse().map_err(|_| invalid())?),
+            "rolloutrounds" => self.rollout_cutoff.rounds = Some(value.parse().map_err(|_| invalid())?),
+            "priortemp" => {
+                let temperature: f32 = value.parse().map_err(|_| invalid())?;
+                if temperature.is_nan() || temperature <= 0.0 {
+                    return Err(invalid());
+                }
+                self.prior_temperature = temperature;
+            }
+            "batch" => self.batch_size = value.parse::<usize>().map_err(|_| invalid())?.max(1),
+            "time" => {
+                let seconds: f64 = value.parse().map_err(|_| invalid())?;
+                self.time_limit = Some(Duration::try_from_secs_f64(seconds).map_err(|_| invalid())?);
+            }
+            _ => return Err(format!(
+                "unknown MCTS option '{}' (expected cpuct, fpu, maxnodes, chance, determinize, endgame, rolloutdepth, rolloutrounds, priortemp, batch, or time)",
+                key
+            )),
+        }
+        Ok(())
+    }
+}
+
+/// A search begun by `AIAgent::begin_search` on one of the MCTS agents.
+#[derive(Debug, Clone)]
+pub enum SteppedSearch {
+    /// Simulations run so far on the agent's tree.
+    Tree { iterations: u32 },
+    /// The endgame solver settled the position.
+    Solved(Move),
+}
+
+impl SteppedSearch {
+    pub fn progress(&self, target: u32) -> SearchProgress {
+        match self {
+            SteppedSearch::Tree { iterations } => SearchProgress { iterations: *iterations, target },
+            SteppedSearch::Solved(_) => SearchProgress { iterations: 0, target: 0 },
+        }
+    }
+}
+
+/// Search statistics for one root move.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveStats {
+    #[cfg_attr(feature = "serde", serde(rename = "move"))]
+    pub mv: Move,
+    pub visits: u32,
+    /// The mean value of the move for the player to move at the root, in the policy's value
+    /// units; 0 if it was never visited.
+    pub q: f32,
+    pub prior: f32,
+}
+
+/// What a search found: the most visited root moves and the line it expects to be played.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchAnalysis {
+    /// Simulations through the root, including any kept from earlier searches.
+    pub visits: u32,
+    /// Root moves, most visited first.
+    pub top_moves: Vec<MoveStats>,
+    /// The most visited move at each node from the root down. It stops at the end of the
+    /// drafting round, since what follows depends on the refill.
+    pub principal_variation: Vec<Move>,
+}
+
+pub trait MctsPolicy: Clone {
+    fn evaluate(&self, game_state: &GameState) -> (f32, HashMap<Move, f32>);
+
+    /// `evaluate` for several positions at once, in order. Policies that can share work
+    /// between positions, such as one network pass for the whole batch, override this.
+    fn evaluate_batch(&self, game_states: &[GameState]) -> Vec<(f32, HashMap<Move, f32>)> {
+        game_states.iter().map(|state| self.evaluate(state)).collect()
+    }
+
+    /// Restarts the policy's random numbers, if it uses any (e.g. for rollouts), from `seed`.
+    fn reseed(&mut self, _seed: u64) {}
+}
+
+/// A search tree node. Nodes don't hold their position: it is rebuilt on the way down by
+/// replaying move edges from the nearest snapshot. Only the root and the sampled refills below
+/// chance nodes keep one, since a refill can't be replayed.
+pub struct Node {
+    pub parent: Option<usize>,
+    pub children: Vec<(Move, usize)>,
+    /// For a node at the end of a drafting round: the next-round states reached by tiling and
+    /// refilling with sampled draws.
+    pub chance_children: Vec<usize>,
+    pub visit_count: u32,
+    pub total_action_value: f32,
+    pub prior_probability: f32,
+    /// The player to move here, which decides the sign of backed-up values.
+    pub player_to_move: usize,
+    /// The position at this node, for the root and chance outcomes.
+    pub snapshot: Option<Box<CompactState>>,
+}
+
+impl Node {
+    fn new(parent: Option<usize>, prior: f32, player_to_move: usize, snapshot: Option<CompactState>) -> Self {
+        Self {
+            parent,
+            children: Vec::new(),
+            chance_children: Vec::new(),
+            visit_count: 0,
+            total_action_value: 0.0,
+            prior_probability: prior,
+            player_to_move,
+            snapshot: snapshot.map(Box::new),
+        }
+    }
+
+    pub fn mean_action_value(&self) -> f32 {
+        if self.visit_count == 0 {
+            0.0
+        } else {
+            self.total_action_value / self.visit_count as f32
+        }
+    }
+}
+
+pub struct Mcts<P: MctsPolicy> {
+    pub tree: Vec<Node>,
+    pub policy_handler: P,
+    pub config: MctsConfig,
+    /// The root position in full; the tree keeps it packed.
+    root: GameState,
+    /// Draws the refills at chance nodes.
+    rng: StdRng,
+}
+
+impl<P: MctsPolicy + Clone> Mcts<P> {
+    pub fn new(initial_state: GameState, policy_handler: P, config: MctsConfig) -> Self {
+        Self {
+            tree: vec![Node::new(None, 1.0, initial_state.current_player_idx, Some(CompactState::from(&initial_state)))],
+            policy_handler,
+            config,
+            root: initial_state,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Makes the search's random choices, and its policy's, repeat from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.policy_handler.reseed(self.rng.gen());
+    }
+
+    /// Drops the search tree and its statistics, keeping the policy and settings.
+    pub fn clear(&mut self) {
+        self.tree.truncate(1);
+        let root = &mut self.tree[0];
+        root.children.clear();
+        root.chance_children.clear();
+        root.visit_count = 0;
+        root.total_action_value = 0.0;
+    }
+
+    pub fn root_state(&self) -> &GameState {
+        &self.root
+    }
+
+    /// Moves the root to `current_game_state`. If the tree already reached that position by
+    /// drafting moves (the last move searched and the replies since), the subtree below it is
+    /// kept with its statistics; otherwise the search starts over from a fresh tree.
+    pub fn sync_tree_with_state(&mut self, current_game_state: &GameState) {
+        let target = CompactState::from(current_game_state);
+        match self.find_node(&target) {
+            Some(node_idx) => {
+                if node_idx != 0 {
+                    self.reroot(node_idx);
+                }
+                self.tree[0].snapshot = Some(Box::new(target));
+            }
+            None => self.tree = vec![Node::new(None, 1.0, current_game_state.current_player_idx, Some(target))],
+        }
+        self.root = current_game_state.clone();
+    }
+
+    /// The node reached from the root by up to one move per player that leads to `target`.
+    fn find_node(&self, target: &CompactState) -> Option<usize> {
+        let max_depth = target.players().len();
+        let root = *self.tree[0].snapshot.as_deref().expect("the root keeps its position");
+        let mut stack = vec![(0, root, 0)];
+        while let Some((node_idx, state, depth)) = stack.pop() {
+            if state == *target {
+                return Some(node_idx);
+            }
+            if depth == max_depth {
+                continue;
+            }
+            for (m, child_idx) in &self.tree[node_idx].children {
+                let mut child_state = state;
+                child_state.apply_move(m);
+                stack.push((*child_idx, child_state, depth + 1));
+            }
+        }
+        None
+    }
+
+    /// Makes `new_root` the root, dropping every node outside its subtree. The caller gives it
+    /// its position.
+    fn reroot(&mut self, new_root: usize) {
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([new_root]);
+        while let Some(node_idx) = queue.pop_front() {
+            order.push(node_idx);
+            let node = &self.tree[node_idx];
+            queue.extend(node.children.iter().map(|(_, child_idx)| *child_idx));
+            queue.extend(node.chance_children.iter().copied());
+        }
+
+        let mut new_index = vec![usize::MAX; self.tree.len()];
+        for (new_idx, &old_idx) in order.iter().enumerate() {
+            new_index[old_idx] = new_idx;
+        }
+        let mut old_tree: Vec<Option<Node>> = std::mem::take(&mut self.tree).into_iter().map(Some).collect();
+        self.tree = order.iter()
+            .map(|&old_idx| {
+                let mut node = old_tree[old_idx].take().expect("each node is visited once");
+                node.parent = node.parent.map(|parent| new_index[parent]);
+                for (_, child_idx) in &mut node.children {
+                    *child_idx = new_index[*child_idx];
+                }
+                for child_idx in &mut node.chance_children {
+                    *child_idx = new_index[*child_idx];
+                }
+                node
+            })
+            .collect();
+
+        let root = &mut self.tree[0];
+        root.parent = None;
+        root.prior_probability = 1.0;
+    }
+
+    pub fn best_move(&self) -> Option<Move> {
+        if self.tree.is_empty() { return None; }
+        
+        let root = &self.tree[0];
+        root.children.iter()
+            .max_by_key(|(_, child_idx)| self.tree[*child_idx].visit_count)
+            .map(|(m, _)| m.clone())
+    }
+
+    /// The value of the most visited root move from the root player's point of view.
+    pub fn root_value(&self) -> Option<f32> {
+        let root = self.tree.first()?;
+        root.children.iter()
+            .max_by_key(|(_, child_idx)| self.tree[*child_idx].visit_count)
+            .map(|(_, child_idx)| -self.tree[*child_idx].mean_action_value())
+    }
+
+    /// The `top_n` most visited root moves and the principal variation.
+    pub fn analysis(&self, top_n: usize) -> SearchAnalysis {
+        let root = &self.tree[0];
+        let mut top_moves: Vec<MoveStats> = root.children.iter()
+            .map(|(m, child_idx)| {
+                let child = &self.tree[*child_idx];
+                MoveStats {
+                    mv: m.clone(),
+                    visits: child.visit_count,
+                    q: if child.visit_count == 0 { 0.0 } else { -child.mean_action_value() },
+                    prior: child.prior_probability,
+                }
+            })
+            .collect();
+        top_moves.sort_by(|a, b| b.visits.cmp(&a.visits).then(b.q.total_cmp(&a.q)));
+        top_moves.truncate(top_n);
+
+        let mut principal_variation = Vec::new();
+        let mut node_idx = 0;
+        while let Some((m, child_idx)) = self.tree[node_idx].children.iter()
+            .max_by_key(|(_, child_idx)| self.tree[*child_idx].visit_count)
+        {
+            if self.tree[*child_idx].visit_count == 0 {
+                break;
+            }
+            principal_variation.push(m.clone());
+            node_idx = *child_idx;
+        }
+
+        SearchAnalysis { visits: root.visit_count, top_moves, principal_variation }
+    }
+
+    pub fn run_search(&mut self, iterations: u32) {
+        // Only read the clock when there is a limit; it isn't available on every target.
+        let deadline = self.config.time_limit.map(|limit| Instant::now() + limit);
+        if self.config.batch_size > 1 {
+            self.run_batched_search(iterations, deadline);
+            return;
+        }
+        for _ in 0..iterations {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            let (leaf_idx, leaf_state) = self.selection();
+            let (value, policy) = self.policy_handler.evaluate(&leaf_state);
+            self.expansion(leaf_idx, &leaf_state, policy);
+            self.backpropagation(leaf_idx, value);
+        }
+    }
+
+    /// `run_search` in batches of `batch_size` leaves. Each leaf's path carries a virtual loss
+    /// until the batch is evaluated, which steers the later selections of the batch to other
+    /// leaves.
+    fn run_batched_search(&mut self, iterations: u32, deadline: Option<Instant>) {
+        let mut remaining = iterations as usize;
+        while remaining > 0 && deadline.is_none_or(|deadline| Instant::now() < deadline) {
+            let batch_size = self.config.batch_size.min(remaining);
+            let mut leaves = Vec::with_capacity(batch_size);
+            let mut states = Vec::with_capacity(batch_size);
+            for _ in 0..batch_size {
+                let (leaf_idx, leaf_state) = self.selection();
+                self.apply_virtual_loss(leaf_idx, 1.0);
+                leaves.push(leaf_idx);
+                states.push(leaf_state);
+            }
+
+            let evaluations = self.policy_handler.evaluate_batch(&states);
+            for ((leaf_idx, leaf_state), (value, policy)) in leaves.into_iter().zip(&states).zip(evaluations) {
+                self.apply_virtual_loss(leaf_idx, -1.0);
+                self.expansion(leaf_idx, leaf_state, policy);
+                self.backpropagation(leaf_idx, value);
+            }
+            remaining -= batch_size;
+        }
+    }
+
+    /// Adds (`sign` 1.0) or removes (-1.0) a pending visit with a virtual loss on every node
+    /// from `leaf_idx` up to the root.
+    fn apply_virtual_loss(&mut self, leaf_idx: usize, sign: f32) {
+        let mut current_idx = Some(leaf_idx);
+        while let Some(idx) = current_idx {
+            let node = &mut self.tree[idx];
+            if sign > 0.0 {
+                node.visit_count += 1;
+            } else {
+                node.visit_count -= 1;
+            }
+            // A node's value is from the point of view of the player to move there, so a win
+            // for them is a loss for whoever chose the move leading to it.
+            node.total_action_value += sign * VIRTUAL_LOSS;
+            current_idx = node.parent;
+        }
+    }
+
+    /// Walks down to a leaf, replaying each move edge on a packed copy of the root position.
+    /// Returns the leaf and its position.
+    fn selection(&mut self) -> (usize, GameState) {
+        let mut current_idx = 0;
+        let mut state = *self.tree[0].snapshot.as_deref().expect("the root keeps its position");
+        let mut in_root_round = true;
+        loop {
+            let node = &self.tree[current_idx];
+            if node.visit_count > 0 && state.phase() == GamePhase::Tiling {
+                match self.select_chance_outcome(current_idx, &state) {
+                    Some(child_idx) => {
+                        current_idx = child_idx;
+                        state = *self.tree[child_idx].snapshot.as_deref().expect("chance outcomes keep their position");
+                        in_root_round = false;
+                        continue;
+                    }
+                    None => return (current_idx, self.leaf_state(&state, in_root_round)),
+                }
+            }
+            if node.children.is_empty() {
+                return (current_idx, self.leaf_state(&state, in_root_round));
+            }
+
+            let (best_move, best_child_idx) = node.children.iter()
+                .max_by(|(_, a_idx), (_, b_idx)| {
+                    let a_score = self.puct_score(*a_idx, node.visit_count);
+                    let b_score = self.puct_score(*b_idx, node.visit_count);
+                    a_score.partial_cmp(&b_score).unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            
+            state.apply_move(best_move);
+            current_idx = *best_child_idx;
+        }
+    }
+
+    /// Unpacks a leaf for the policy. Drafting leaves the bag alone, so without determinization
+    /// a leaf still in the root's round gets back the root's bag order and RNG, which packing
+    /// drops.
+    fn leaf_state(&self, state: &CompactState, in_root_round: bool) -> GameState {
+        let mut leaf = state.to_game_state();
+        if in_root_round && !self.config.determinize {
+            leaf.tile_bag = self.root.tile_bag.clone();
+            leaf.rng = self.root.rng.clone();
+        }
+        leaf
+    }
+
+    /// Steps through a chance node at a round boundary. Until the node holds
+    /// `max_chance_outcomes` outcomes, each visit samples a fresh refill and adds it as a new,
+    /// unexpanded child; after that, or once the tree is full, the least visited outcome is
+    /// revisited. Returns `None` if there is no outcome to descend to.
+    fn select_chance_outcome(&mut self, node_idx: usize, state: &CompactState) -> Option<usize> {
+        let node = &self.tree[node_idx];
+        // The final round's tiling draws nothing, so it has a single outcome.
+        let max_outcomes = if state.end_game_triggered() { 1 } else { self.config.max_chance_outcomes };
+        if node.chance_children.len() >= max_outcomes || self.tree_is_full() {
+            return node.chance_children.iter().copied()
+                .min_by_key(|&child_idx| self.tree[child_idx].visit_count);
+        }
+
+        let mut next_round = *state;
+        next_round.advance_with_rng(None, &mut self.rng).ok()?;
+        let child_idx = self.tree.len();
+        self.tree.push(Node::new(Some(node_idx), 1.0, next_round.current_player_idx(), Some(next_round)));
+        self.tree[node_idx].chance_children.push(child_idx);
+        Some(child_idx)
+    }
+
+    fn tree_is_full(&self) -> bool {
+        self.config.max_nodes.is_some_and(|max_nodes| self.tree.len() >= max_nodes)
+    }
+
+    /// Adds a child for each move in `policy`, unless the tree is full or the leaf was already
+    /// expanded (by an earlier leaf of the same batch). Children are added in the order of
+    /// `get_legal_moves` rather than the map's, so that a seeded search breaks ties the same
+    /// way every run.
+    fn expansion(&mut self, leaf_idx: usize, leaf_state: &GameState, mut policy: HashMap<Move, f32>) {
+        if self.tree_is_full() || !self.tree[leaf_idx].children.is_empty() {
+            return;
+        }
+
+        // Every drafting move passes the turn to the next seat.
+        let next_player = (leaf_state.current_player_idx + 1) % leaf_state.players.len();
+        for legal_move in leaf_state.get_legal_moves() {
+            let Some(prior_prob) = policy.remove(&legal_move) else { continue };
+            let new_node = Node::new(Some(leaf_idx), prior_prob, next_player, None);
+            let new_node_idx = self.tree.len();
+            self.tree.push(new_node);
+            self.tree[leaf_idx].children.push((legal_move, new_node_idx));
+        }
+    }
+
+    fn backpropagation(&mut self, start_idx: usize, value: f32) {
+        let player_at_leaf = self.tree[start_idx].player_to_move;
+        
+        let mut current_idx = Some(start_idx);
+        while let Some(idx) = current_idx {
+            let node = &mut self.tree[idx];
+            node.visit_count += 1;
+            
+            if node.player_to_move == player_at_leaf {
+                node.total_action_value += value;
+            } else {
+                node.total_action_value -= value;
+            }
+            
+            current_idx = node.parent;
+        }
+    }
+
+    fn puct_score(&self, node_idx: usize, parent_visit_count: u32) -> f32 {
+        let node = &self.tree[node_idx];
+        let q_value = if node.visit_count == 0 { self.config.fpu } else { -node.mean_action_value() };
+        let p_value = node.prior_probability;
+
+        let exploration_term = self.config.c_puct * p_value * (parent_visit_count as f32).sqrt() / (1.0 + node.visit_count as f32);
+
+        q_value + exploration_term
+    }
+}
+
+/// A search that keeps growing the tree on a background thread, e.g. while the opponent thinks.
+/// Native targets only, since it needs a thread.
+pub struct Ponder<P: MctsPolicy> {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Mcts<P>>,
+}
+
+impl<P: MctsPolicy + Send + 'static> Mcts<P> {
+    /// Moves the search onto a background thread until `Ponder::stop` hands it back. The tree
+    /// stops growing at `max_nodes`, or at two million nodes if that isn't set.
+    pub fn ponder(mut self) -> Ponder<P> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let node_limit = self.config.max_nodes.unwrap_or(PONDER_MAX_NODES);
+        let handle = thread::spawn(move || {
+            while !stop_flag.load(Ordering::Relaxed) {
+                if self.tree.len() >= node_limit {
+                    thread::park_timeout(Duration::from_millis(10));
+                } else {
+                    self.run_search(PONDER_BATCH);
+                }
+            }
+            self
+        });
+        Ponder { stop, handle }
+    }
+}
+
+impl<P: MctsPolicy> Ponder<P> {
+    /// Stops the background search and returns the tree it grew.
+    pub fn stop(self) -> Mcts<P> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle.thread().unpark();
+        self.handle.join().expect("the pondering thread panicked")
+    }
+}
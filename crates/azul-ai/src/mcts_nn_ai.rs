@@ -0,0 +1,441 @@
+// MODIFIED: This entire module will only be compiled when the "nn" feature is enabled.
+#![cfg(feature = "nn")]
+
+use crate::{
+    endgame_solver::EndgameSolver,
+    mcts_lib::{Mcts, MctsConfig, MctsPolicy, Ponder, SearchAnalysis, SteppedSearch},
+    nn::NeuralNetwork,
+    AIAgent, AgentConfig, SearchProgress, SearchStats, TrainingData,
+};
+use azul_core::{GameState, Move, MoveDestination, MoveSource, Tile, NUM_ROWS};
+use rand::seq::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::any::Any;
+use std::collections::HashMap;
+
+// --- Constants for Network Architecture ---
+const NUM_FACTORIES: usize = 9;
+const NUM_COLORS: usize = 5;
+const MAX_CENTER_TILES: usize = 27;
+const MAX_PLAYERS: usize = 4;
+const PATTERN_LINE_SLOTS: usize = 5 * 5;
+const WALL_SLOTS: usize = 5 * 5;
+const FLOOR_SLOTS: usize = 7;
+
+// Policy sources are the factories, then the center; destinations the pattern lines, then the floor.
+const NUM_SOURCES: usize = NUM_FACTORIES + 1;
+const NUM_DESTINATIONS: usize = NUM_ROWS + 1;
+
+/// The length of the network input that `state_to_input` encodes.
+pub const INPUT_SIZE: usize = (NUM_FACTORIES * NUM_COLORS * 4)
+                        + (MAX_CENTER_TILES * NUM_COLORS)
+                        + (MAX_PLAYERS * (1 + PATTERN_LINE_SLOTS + WALL_SLOTS + FLOOR_SLOTS + 1))
+                        + 1;
+/// One policy output per source × color × destination.
+pub const POLICY_SIZE: usize = NUM_SOURCES * NUM_COLORS * NUM_DESTINATIONS;
+const HIDDEN_SIZES: [usize; 2] = [256, 256];
+
+// --- Helper Functions ---
+fn color_to_index(tile: Tile) -> usize {
+    match tile {
+        Tile::Blue => 0, Tile::Yellow => 1, Tile::Red => 2,
+        Tile::Black => 3, Tile::White => 4,
+    }
+}
+
+/// Fails if `network` doesn't fit this agent's input encoding and policy layout.
+pub fn check_network(network: &NeuralNetwork) -> Result<(), String> {
+    if network.input_size() != INPUT_SIZE || network.policy_size() != POLICY_SIZE {
+        return Err(format!(
+            "Network has {} inputs and {} policy outputs, expected {} and {}",
+            network.input_size(), network.policy_size(), INPUT_SIZE, POLICY_SIZE
+        ));
+    }
+    Ok(())
+}
+
+/// 1.0 at the policy index of every legal move in `game_state`, 0.0 elsewhere. Training masks
+/// the policy logits with it.
+pub fn legal_policy_mask(game_state: &GameState) -> Vec<f32> {
+    let mut mask = vec![0.0; POLICY_SIZE];
+    for m in game_state.get_legal_moves() {
+        if let Some(idx) = move_to_policy_index(&m) {
+            mask[idx] = 1.0;
+        }
+    }
+    mask
+}
+
+/// The policy output for `m`, or `None` if its source or destination is out of range.
+pub fn move_to_policy_index(m: &Move) -> Option<usize> {
+    let source_idx = match m.source {
+        MoveSource::Factory(idx) if idx < NUM_FACTORIES => idx,
+        MoveSource::Factory(_) => return None,
+        MoveSource::Center => NUM_FACTORIES,
+    };
+    let destination_idx = match m.destination {
+        MoveDestination::PatternLine(row) if row < NUM_ROWS => row,
+        MoveDestination::PatternLine(_) => return None,
+        MoveDestination::Floor => NUM_ROWS,
+    };
+    Some((source_idx * NUM_COLORS + color_to_index(m.tile)) * NUM_DESTINATIONS + destination_idx)
+}
+
+/// `sample` with its non-empty factories shuffled among their slots, moving their policy and
+/// legal-mask entries along with them. Factories are interchangeable, so this is the same
+/// position with the same value and the same best moves. Empty factories stay where they are,
+/// so the result is always a position the game can reach. Samples of another layout are
+/// returned unchanged.
+pub fn shuffle_factories<R: Rng + ?Sized>(sample: &TrainingData, rng: &mut R) -> TrainingData {
+    const FACTORY_INPUTS: usize = NUM_COLORS * 4;
+    const SOURCE_OUTPUTS: usize = NUM_COLORS * NUM_DESTINATIONS;
+    if sample.state_input.len() != INPUT_SIZE || sample.mcts_policy.len() != POLICY_SIZE {
+        return sample.clone();
+    }
+    let occupied: Vec<usize> = (0..NUM_FACTORIES)
+        .filter(|&f| sample.state_input[f * FACTORY_INPUTS..(f + 1) * FACTORY_INPUTS].iter().any(|&x| x != 0.0))
+        .collect();
+    let mut targets = occupied.clone();
+    targets.shuffle(rng);
+
+    let mut shuffled = sample.clone();
+    let has_mask = sample.legal_mask.len() == POLICY_SIZE;
+    for (&from, &to) in occupied.iter().zip(&targets) {
+        shuffled.state_input[to * FACTORY_INPUTS..(to + 1) * FACTORY_INPUTS]
+            .copy_from_slice(&sample.state_input[from * FACTORY_INPUTS..(from + 1) * FACTORY_INPUTS]);
+        shuffled.mcts_policy[to * SOURCE_OUTPUTS..(to + 1) * SOURCE_OUTPUTS]
+            .copy_from_slice(&sample.mcts_policy[from * SOURCE_OUTPUTS..(from + 1) * SOURCE_OUTPUTS]);
+        if has_mask {
+            shuffled.legal_mask[to * SOURCE_OUTPUTS..(to + 1) * SOURCE_OUTPUTS]
+                .copy_from_slice(&sample.legal_mask[from * SOURCE_OUTPUTS..(from + 1) * SOURCE_OUTPUTS]);
+        }
+    }
+    shuffled
+}
+
+#[derive(Clone)]
+struct NnPolicy {
+    nn: NeuralNetwork,
+}
+
+impl MctsPolicy for NnPolicy {
+    fn evaluate(&self, game_state: &GameState) -> (f32, HashMap<Move, f32>) {
+        let input = self.state_to_input(game_state);
+        let (raw_policy, value) = self.nn.forward(&input);
+        let legal_moves = game_state.get_legal_moves();
+        let policy_map = self.mask_and_normalize_policy(&legal_moves, &raw_policy);
+        (value, policy_map)
+    }
+
+    fn evaluate_batch(&self, game_states: &[GameState]) -> Vec<(f32, HashMap<Move, f32>)> {
+        let inputs: Vec<Vec<f32>> = game_states.iter().map(|state| self.state_to_input(state)).collect();
+        self.nn.forward_batch(&inputs).into_iter().zip(game_states)
+            .map(|((raw_policy, value), state)| {
+                (value, self.mask_and_normalize_policy(&state.get_legal_moves(), &raw_policy))
+            })
+            .collect()
+    }
+}
+
+impl NnPolicy {
+    fn state_to_input(&self, game_state: &GameState) -> Vec<f32> {
+        let mut input = vec![0.0; INPUT_SIZE];
+        let mut offset = 0;
+        for factory_idx in 0..NUM_FACTORIES {
+            if let Some(factory) = game_state.factories.get(factory_idx) {
+                for tile in factory {
+                    let color_idx = color_to_index(*tile);
+                    for slot in 0..4 {
+                        let index = offset + (color_idx * 4) + slot;
+                        if input[index] == 0.0 { input[index] = 1.0; break; }
+                    }
+                }
+            }
+            offset += NUM_COLORS * 4;
+        }
+        for (i, tile) in game_state.center.iter().enumerate().take(MAX_CENTER_TILES) {
+            let color_idx = color_to_index(*tile);
+            input[offset + (i * NUM_COLORS) + color_idx] = 1.0;
+        }
+        offset += MAX_CENTER_TILES * NUM_COLORS;
+        for player_idx in 0..MAX_PLAYERS {
+            if let Some(player) = game_state.players.get(player_idx) {
+                input[offset] = player.score as f32 / 100.0;
+                offset += 1;
+                for (row_idx, line) in player.pattern_lines.iter().enumerate() {
+                    for i in 0..line.len() { input[offset + (row_idx * 5) + i] = 1.0; }
+                }
+                offset += PATTERN_LINE_SLOTS;
+                for (row_idx, row) in player.wall.iter().enumerate() {
+                    for (col_idx, tile_option) in row.iter().enumerate() {
+                        if tile_option.is_some() { input[offset + (row_idx * 5) + col_idx] = 1.0; }
+                    }
+                }
+                offset += WALL_SLOTS;
+                for i in 0..player.floor_line.len().min(FLOOR_SLOTS) { input[offset + i] = 1.0; }
+                offset += FLOOR_SLOTS;
+                if player.has_first_player_marker { input[offset] = 1.0; }
+                offset += 1;
+            } else {
+                offset += 1 + PATTERN_LINE_SLOTS + WALL_SLOTS + FLOOR_SLOTS + 1;
+            }
+        }
+        input[offset] = (game_state.current_player_idx as f32 + 1.0) / MAX_PLAYERS as f32;
+        input
+    }
+
+    /// Priors from the policy logits: a softmax over the legal moves.
+    fn mask_and_normalize_policy(&self, legal_moves: &[Move], raw_policy: &[f32]) -> HashMap<Move, f32> {
+        let logits: Vec<(&Move, f32)> = legal_moves.iter()
+            .filter_map(|m| move_to_policy_index(m).and_then(|idx| raw_policy.get(idx)).map(|&logit| (m, logit)))
+            .collect();
+        let max_logit = logits.iter().map(|(_, logit)| *logit).fold(f32::NEG_INFINITY, f32::max);
+        let total: f32 = logits.iter().map(|(_, logit)| (logit - max_logit).exp()).sum();
+        let mut final_policy = HashMap::new();
+        if total.is_finite() && total > 0.0 {
+            for (m, logit) in logits {
+                final_policy.insert(m.clone(), (logit - max_logit).exp() / total);
+            }
+        }
+        if final_policy.is_empty() && !legal_moves.is_empty() {
+            let prob = 1.0 / legal_moves.len() as f32;
+            for m in legal_moves { final_policy.insert(m.clone(), prob); }
+        }
+        final_policy
+    }
+}
+
+pub struct MctsNnAI {
+    mcts: Option<Mcts<NnPolicy>>,
+    iterations: u32,
+    model_path: Option<String>,
+    model_bytes: Option<Vec<u8>>,
+    /// A network to search with from the next `get_move` on, set by `set_network`.
+    network: Option<NeuralNetwork>,
+    config: MctsConfig,
+    /// Seeds the search tree when it's created, if set by `configure`.
+    seed: Option<u64>,
+    ponder: Option<Ponder<NnPolicy>>,
+    last_search: Option<SearchStats>,
+    stepped: Option<SteppedSearch>,
+}
+
+impl MctsNnAI {
+    /// An agent that loads its network on the first `get_move`: from `model_bytes` if given,
+    /// else from the weights file at `model_path`. If neither is given, or loading fails, it
+    /// plays with a randomly initialized network.
+    pub fn new(iterations: u32, model_path: Option<String>, model_bytes: Option<Vec<u8>>) -> Self {
+        Self {
+            mcts: None,
+            iterations,
+            model_path,
+            model_bytes,
+            network: None,
+            config: MctsConfig::default(),
+            seed: None,
+            ponder: None,
+            last_search: None,
+            stepped: None,
+        }
+    }
+
+    /// Replaces the network, e.g. once the web front-end has finished downloading a model.
+    /// The search tree is dropped, since its values came from the old network.
+    pub fn set_network(&mut self, network: NeuralNetwork) -> Result<(), String> {
+        check_network(&network)?;
+        self.stop_ponder();
+        self.mcts = None;
+        self.network = Some(network);
+        Ok(())
+    }
+
+    fn load_network(&mut self) -> NeuralNetwork {
+        if let Some(network) = self.network.take() {
+            return network;
+        }
+        let loaded = match (&self.model_bytes, &self.model_path) {
+            (Some(bytes), _) => Some(NeuralNetwork::from_bytes(bytes)),
+            (None, Some(path)) => Some(std::fs::read(path)
+                .map_err(|e| format!("Could not read {}: {}", path, e))
+                .and_then(|bytes| NeuralNetwork::from_bytes(&bytes))),
+            (None, None) => None,
+        };
+        // A seeded agent gets the same random network every time.
+        let random_network = || match self.seed {
+            Some(seed) => NeuralNetwork::new_with_rng(INPUT_SIZE, &HIDDEN_SIZES, POLICY_SIZE, &mut StdRng::seed_from_u64(seed)),
+            None => NeuralNetwork::new(INPUT_SIZE, &HIDDEN_SIZES, POLICY_SIZE),
+        };
+        match loaded.map(|result| result.and_then(|nn| check_network(&nn).map(|_| nn))) {
+            Some(Ok(nn)) => nn,
+            Some(Err(e)) => {
+                eprintln!("Failed to load model: {}. Using a random network.", e);
+                random_network()
+            }
+            None => random_network(),
+        }
+    }
+
+    pub fn with_config(mut self, config: MctsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn get_mcts_policy(&self) -> Option<Vec<f32>> {
+        if let Some(mcts) = &self.mcts {
+            let root = &mcts.tree[0];
+            if root.visit_count == 0 { return None; }
+            let mut policy_vec = vec![0.0; POLICY_SIZE];
+            for (mv, child_idx) in &root.children {
+                if let Some(policy_idx) = move_to_policy_index(mv) {
+                    let child_visits = mcts.tree[*child_idx].visit_count;
+                    policy_vec[policy_idx] = child_visits as f32 / root.visit_count as f32;
+                }
+            }
+            return Some(policy_vec);
+        }
+        None
+    }
+
+    pub fn state_to_input(&self, game_state: &GameState) -> Option<Vec<f32>> {
+        self.mcts.as_ref().map(|mcts| mcts.policy_handler.state_to_input(game_state))
+    }
+
+    /// The search value of the last chosen move, if a search has been run.
+    pub fn root_value(&self) -> Option<f32> {
+        self.mcts.as_ref().and_then(|mcts| mcts.root_value())
+    }
+
+    /// The `top_n` most visited moves of the last search and its principal variation, or `None`
+    /// if no search has been run (or the last move was solved exactly). Not available while
+    /// pondering.
+    pub fn analysis(&self, top_n: usize) -> Option<SearchAnalysis> {
+        self.mcts.as_ref()
+            .filter(|mcts| mcts.tree[0].visit_count > 0)
+            .map(|mcts| mcts.analysis(top_n))
+    }
+
+    /// Gets ready to search `game_state`: returns the endgame solver's move if it settles the
+    /// position, and otherwise loads the network if needed and moves the tree to `game_state`.
+    fn prepare_search(&mut self, game_state: &GameState) -> Option<Move> {
+        self.stop_ponder();
+        if self.config.endgame_nodes > 0 {
+            if let Some(solution) = EndgameSolver::new(self.config.endgame_nodes).solve(game_state) {
+                self.last_search = Some(SearchStats { iterations: 0, nodes: solution.nodes_searched });
+                // Moves the tree along too, so that root_value and analysis don't describe an
+                // earlier position.
+                if let Some(mcts) = &mut self.mcts {
+                    mcts.sync_tree_with_state(game_state);
+                }
+                return Some(solution.best_move);
+            }
+        }
+        if self.mcts.is_none() {
+            let nn = self.load_network();
+            let policy_handler = NnPolicy { nn };
+            let mut mcts = Mcts::new(game_state.clone(), policy_handler, self.config.clone());
+            if let Some(seed) = self.seed {
+                mcts.reseed(seed);
+            }
+            self.mcts = Some(mcts);
+        }
+        self.mcts.as_mut().unwrap().sync_tree_with_state(game_state);
+        None
+    }
+}
+
+impl AIAgent for MctsNnAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        if let Some(solved) = self.prepare_search(game_state) {
+            return Some(solved);
+        }
+        let mcts = self.mcts.as_mut().unwrap();
+        mcts.run_search(self.iterations);
+        self.last_search = Some(SearchStats { iterations: self.iterations, nodes: mcts.tree.len() as u64 });
+        mcts.best_move()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any { self }
+
+    fn name(&self) -> &str {
+        "MctsNnAI"
+    }
+
+    fn reset(&mut self) {
+        self.stop_ponder();
+        // The tree is cleared rather than dropped so that the network doesn't have to be reloaded.
+        if let Some(mcts) = &mut self.mcts {
+            mcts.clear();
+        }
+        self.stepped = None;
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        self.stop_ponder();
+        if let Some(iterations) = config.iterations {
+            self.iterations = iterations;
+        }
+        if let Some(mcts_config) = &config.mcts {
+            self.config = mcts_config.clone();
+            if let Some(mcts) = &mut self.mcts {
+                mcts.config = mcts_config.clone();
+            }
+        }
+        if let Some(seed) = config.seed {
+            self.seed = Some(seed);
+            if let Some(mcts) = &mut self.mcts {
+                mcts.reseed(seed);
+            }
+        }
+    }
+
+    fn start_ponder(&mut self, game_state: &GameState) {
+        self.stop_ponder();
+        // The network is loaded by the first `get_move`; until then there's nothing to ponder with.
+        if let Some(mut mcts) = self.mcts.take() {
+            mcts.sync_tree_with_state(game_state);
+            self.ponder = Some(mcts.ponder());
+        }
+    }
+
+    fn stop_ponder(&mut self) {
+        if let Some(ponder) = self.ponder.take() {
+            self.mcts = Some(ponder.stop());
+        }
+    }
+
+    fn search_stats(&self) -> Option<SearchStats> {
+        self.last_search
+    }
+
+    fn begin_search(&mut self, game_state: &GameState) -> Option<SearchProgress> {
+        let stepped = match self.prepare_search(game_state) {
+            Some(solved) => SteppedSearch::Solved(solved),
+            None => SteppedSearch::Tree { iterations: 0 },
+        };
+        let progress = stepped.progress(self.iterations);
+        self.stepped = Some(stepped);
+        Some(progress)
+    }
+
+    fn search_step(&mut self, iterations: u32) -> Option<SearchProgress> {
+        let stepped = self.stepped.as_mut()?;
+        if let SteppedSearch::Tree { iterations: done } = stepped {
+            let slice = iterations.min(self.iterations.saturating_sub(*done));
+            self.mcts.as_mut().expect("a stepped search has a tree").run_search(slice);
+            *done += slice;
+        }
+        Some(stepped.progress(self.iterations))
+    }
+
+    fn end_search(&mut self) -> Option<Move> {
+        match self.stepped.take()? {
+            SteppedSearch::Solved(solved) => Some(solved),
+            SteppedSearch::Tree { iterations } => {
+                let mcts = self.mcts.as_ref()?;
+                self.last_search = Some(SearchStats { iterations, nodes: mcts.tree.len() as u64 });
+                mcts.best_move()
+            }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+// This entire module will only be compiled when the "nn" feature is enabled.
+#![cfg(feature = "nn")]
+
+//! Inference for the network trained by the `train` binary, in plain Rust so that it also runs
+//! in WebAssembly. `train` exports the weights with `NeuralNetwork::to_bytes`.
+//!
+//! Weights file layout (little-endian): the `WEIGHTS_MAGIC` bytes, a u32 format version, a u32
+//! layer count, then for each layer its u32 output and input sizes, the weight matrix row by
+//! row, and the biases, all as f32. The layers are the hidden ones in order, then the policy
+//! head, then the value head.
+
+use ndarray::{Array1, Array2};
+use rand::Rng;
+
+/// The first bytes of every weights file.
+pub const WEIGHTS_MAGIC: [u8; 4] = *b"AZNN";
+/// The file extension `train` gives exported weights.
+pub const WEIGHTS_EXTENSION: &str = "weights";
+const WEIGHTS_VERSION: u32 = 1;
+
+/// A fully connected layer.
+#[derive(Debug, Clone)]
+pub struct Dense {
+    weights: Array2<f32>,
+    biases: Array1<f32>,
+}
+
+impl Dense {
+    /// A layer from its weights, given row by row (one row per output), and biases.
+    pub fn from_parts(input_size: usize, output_size: usize, weights: Vec<f32>, biases: Vec<f32>) -> Result<Self, String> {
+        let weights = Array2::from_shape_vec((output_size, input_size), weights)
+            .map_err(|_| format!("Expected {}x{} layer weights", output_size, input_size))?;
+        if biases.len() != output_size {
+            return Err(format!("Expected {} biases, found {}", output_size, biases.len()));
+        }
+        Ok(Self { weights, biases: Array1::from(biases) })
+    }
+
+    /// Randomly initialized, uniformly within ±1/sqrt(input_size) as PyTorch does.
+    fn random<R: Rng + ?Sized>(input_size: usize, output_size: usize, rng: &mut R) -> Self {
+        let bound = 1.0 / (input_size as f32).sqrt();
+        let weights = Array2::from_shape_simple_fn((output_size, input_size), || rng.gen_range(-bound..bound));
+        let biases = Array1::from_shape_simple_fn(output_size, || rng.gen_range(-bound..bound));
+        Self { weights, biases }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.weights.ncols()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.weights.nrows()
+    }
+
+    fn forward(&self, inputs: &Array1<f32>) -> Array1<f32> {
+        self.weights.dot(inputs) + &self.biases
+    }
+
+    /// `forward` for a batch with one input per row.
+    fn forward_batch(&self, inputs: &Array2<f32>) -> Array2<f32> {
+        inputs.dot(&self.weights.t()) + &self.biases
+    }
+}
+
+/// The policy/value network: ReLU hidden layers, then a linear policy head and a tanh value
+/// head, as trained by `train`.
+#[derive(Debug, Clone)]
+pub struct NeuralNetwork {
+    hidden: Vec<Dense>,
+    policy_head: Dense,
+    value_head: Dense,
+}
+
+impl NeuralNetwork {
+    /// A randomly initialized network, for self-play before any model has been trained.
+    pub fn new(input_size: usize, hidden_sizes: &[usize], policy_size: usize) -> Self {
+        Self::new_with_rng(input_size, hidden_sizes, policy_size, &mut rand::thread_rng())
+    }
+
+    /// `new`, drawing the initial weights from `rng`.
+    pub fn new_with_rng<R: Rng + ?Sized>(input_size: usize, hidden_sizes: &[usize], policy_size: usize, rng: &mut R) -> Self {
+        let mut sizes = vec![input_size];
+        sizes.extend_from_slice(hidden_sizes);
+        let hidden = sizes.windows(2).map(|pair| Dense::random(pair[0], pair[1], rng)).collect();
+        let last = *sizes.last().unwrap_or(&input_size);
+        Self { hidden, policy_head: Dense::random(last, policy_size, rng), value_head: Dense::random(last, 1, rng) }
+    }
+
+    /// A network from its layers, checking that their sizes line up.
+    pub fn from_layers(hidden: Vec<Dense>, policy_head: Dense, value_head: Dense) -> Result<Self, String> {
+        for pair in hidden.windows(2) {
+            if pair[0].output_size() != pair[1].input_size() {
+                return Err(format!(
+                    "Layer sizes don't match: {} outputs feed {} inputs",
+                    pair[0].output_size(), pair[1].input_size()
+                ));
+            }
+        }
+        if let Some(last) = hidden.last() {
+            for head in [&policy_head, &value_head] {
+                if head.input_size() != last.output_size() {
+                    return Err(format!("Head takes {} inputs, but the last hidden layer has {} outputs", head.input_size(), last.output_size()));
+                }
+            }
+        }
+        if value_head.output_size() != 1 {
+            return Err(format!("Value head has {} outputs, expected 1", value_head.output_size()));
+        }
+        Ok(Self { hidden, policy_head, value_head })
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.hidden.first().unwrap_or(&self.policy_head).input_size()
+    }
+
+    pub fn policy_size(&self) -> usize {
+        self.policy_head.output_size()
+    }
+
+    /// The raw policy outputs and the value, in [-1, 1], of `inputs`.
+    pub fn forward(&self, inputs: &[f32]) -> (Vec<f32>, f32) {
+        let features = self.hidden.iter().fold(Array1::from(inputs.to_vec()), |acc, layer| {
+            layer.forward(&acc).mapv(|x| x.max(0.0))
+        });
+        let policy = self.policy_head.forward(&features).to_vec();
+        let value = self.value_head.forward(&features)[0].tanh();
+        (policy, value)
+    }
+
+    /// `forward` for several inputs in one pass, which multiplies whole matrices instead of
+    /// one vector at a time.
+    pub fn forward_batch(&self, inputs: &[Vec<f32>]) -> Vec<(Vec<f32>, f32)> {
+        let flat: Vec<f32> = inputs.iter().flatten().copied().collect();
+        let batch = Array2::from_shape_vec((inputs.len(), self.input_size()), flat)
+            .expect("every input has the network's input size");
+        let features = self.hidden.iter().fold(batch, |acc, layer| layer.forward_batch(&acc).mapv(|x| x.max(0.0)));
+        let policies = self.policy_head.forward_batch(&features);
+        let values = self.value_head.forward_batch(&features);
+        policies.rows().into_iter().zip(values.column(0))
+            .map(|(policy, value)| (policy.to_vec(), value.tanh()))
+            .collect()
+    }
+
+    /// Reads a weights file written by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut decoder = WeightsDecoder::new(Some(bytes.len()));
+        decoder.push(bytes)?;
+        decoder.finish()
+    }
+
+    /// The network in the weights file format that `from_bytes` reads.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let layers: Vec<&Dense> = self.hidden.iter().chain([&self.policy_head, &self.value_head]).collect();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&WEIGHTS_MAGIC);
+        bytes.extend_from_slice(&WEIGHTS_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(layers.len() as u32).to_le_bytes());
+        for layer in layers {
+            bytes.extend_from_slice(&(layer.output_size() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(layer.input_size() as u32).to_le_bytes());
+            for value in layer.weights.iter().chain(layer.biases.iter()) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+/// Reads a weights file handed over in pieces of any size, building each layer as its bytes
+/// arrive, so the file itself is never held in memory. Every field in the format is a 4-byte
+/// word, and a word split between pieces is carried over to the next one.
+pub struct WeightsDecoder {
+    /// The file's size, when known, so an implausible layer size fails before it's allocated.
+    total_len: Option<usize>,
+    bytes_read: usize,
+    partial: [u8; 4],
+    partial_len: usize,
+    next: Field,
+    layer_count: usize,
+    layers: Vec<Dense>,
+}
+
+/// The next field a `WeightsDecoder` expects, holding the layer read so far.
+enum Field {
+    Magic,
+    Version,
+    LayerCount,
+    OutputSize,
+    InputSize { output_size: usize },
+    Weights { output_size: usize, input_size: usize, weights: Vec<f32> },
+    Biases { output_size: usize, input_size: usize, weights: Vec<f32>, biases: Vec<f32> },
+    End,
+}
+
+impl Field {
+    /// The values still to read into the current layer, and how many are wanted.
+    fn floats_wanted(&mut self) -> Option<(&mut Vec<f32>, usize)> {
+        match self {
+            Field::Weights { output_size, input_size, weights } => {
+                let wanted = *output_size * *input_size - weights.len();
+                Some((weights, wanted))
+            }
+            Field::Biases { output_size, biases, .. } => {
+                let wanted = *output_size - biases.len();
+                Some((biases, wanted))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl WeightsDecoder {
+    /// `total_len` is the size of the whole file, if known.
+    pub fn new(total_len: Option<usize>) -> Self {
+        Self { total_len, bytes_read: 0, partial: [0; 4], partial_len: 0, next: Field::Magic, layer_count: 0, layers: Vec::new() }
+    }
+
+    /// Decodes the next piece of the file.
+    pub fn push(&mut self, mut bytes: &[u8]) -> Result<(), String> {
+        while !bytes.is_empty() {
+            if let Field::End = self.next {
+                return Err(format!("{} unexpected bytes after the last layer", bytes.len()));
+            }
+            // Runs of whole values go straight into the layer being read.
+            if self.partial_len == 0 {
+                if let Some((values, wanted)) = self.next.floats_wanted() {
+                    let count = wanted.min(bytes.len() / 4);
+                    if count > 0 {
+                        let (head, rest) = bytes.split_at(count * 4);
+                        values.extend(head.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])));
+                        bytes = rest;
+                        self.bytes_read += head.len();
+                        let field = core::mem::replace(&mut self.next, Field::End);
+                        self.next = self.settle(field)?;
+                        continue;
+                    }
+                }
+            }
+            let take = (4 - self.partial_len).min(bytes.len());
+            self.partial[self.partial_len..self.partial_len + take].copy_from_slice(&bytes[..take]);
+            self.partial_len += take;
+            self.bytes_read += take;
+            bytes = &bytes[take..];
+            if self.partial_len == 4 {
+                self.partial_len = 0;
+                self.read_word(self.partial)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The network, once the whole file has been pushed.
+    pub fn finish(self) -> Result<NeuralNetwork, String> {
+        if !matches!(self.next, Field::End) {
+            return Err("Weights file is truncated".to_string());
+        }
+        let mut layers = self.layers;
+        let value_head = layers.pop().expect("at least two layers");
+        let policy_head = layers.pop().expect("at least two layers");
+        NeuralNetwork::from_layers(layers, policy_head, value_head)
+    }
+
+    fn read_word(&mut self, word: [u8; 4]) -> Result<(), String> {
+        let value = u32::from_le_bytes(word);
+        let field = match core::mem::replace(&mut self.next, Field::End) {
+            Field::Magic if word != WEIGHTS_MAGIC => return Err("Not a weights file (a model exported by train)".to_string()),
+            Field::Magic => Field::Version,
+            Field::Version if value != WEIGHTS_VERSION => return Err(format!("Unsupported weights format version {}", value)),
+            Field::Version => Field::LayerCount,
+            Field::LayerCount if value < 2 => {
+                return Err(format!("Weights file has {} layers, expected at least the two heads", value));
+            }
+            Field::LayerCount => {
+                self.layer_count = value as usize;
+                Field::OutputSize
+            }
+            Field::OutputSize => Field::InputSize { output_size: value as usize },
+            Field::InputSize { output_size } => {
+                let input_size = value as usize;
+                let count = output_size.checked_mul(input_size).ok_or("Weights file layer is too large")?;
+                Field::Weights { output_size, input_size, weights: self.allocate(count)? }
+            }
+            Field::Weights { output_size, input_size, mut weights } => {
+                weights.push(f32::from_le_bytes(word));
+                Field::Weights { output_size, input_size, weights }
+            }
+            Field::Biases { output_size, input_size, weights, mut biases } => {
+                biases.push(f32::from_le_bytes(word));
+                Field::Biases { output_size, input_size, weights, biases }
+            }
+            Field::End => unreachable!("push stops at the end of the file"),
+        };
+        self.next = self.settle(field)?;
+        Ok(())
+    }
+
+    /// Moves past a layer part that is complete, finishing the layer if it was the biases.
+    fn settle(&mut self, field: Field) -> Result<Field, String> {
+        match field {
+            Field::Weights { output_size, input_size, weights } if weights.len() == output_size * input_size => {
+                let biases = self.allocate(output_size)?;
+                self.settle(Field::Biases { output_size, input_size, weights, biases })
+            }
+            Field::Biases { output_size, input_size, weights, biases } if biases.len() == output_size => {
+                self.layers.push(Dense::from_parts(input_size, output_size, weights, biases)?);
+                Ok(if self.layers.len() == self.layer_count { Field::End } else { Field::OutputSize })
+            }
+            field => Ok(field),
+        }
+    }
+
+    /// Room for `count` values, refused if the rest of the file can't hold them.
+    fn allocate(&self, count: usize) -> Result<Vec<f32>, String> {
+        let len = count.checked_mul(4).ok_or("Weights file layer is too large")?;
+        if self.total_len.is_some_and(|total| len > total.saturating_sub(self.bytes_read)) {
+            return Err("Weights file is truncated".to_string());
+        }
+        let mut values = Vec::new();
+        values.try_reserve_exact(count).map_err(|_| "Weights file layer is too large".to_string())?;
+        Ok(values)
+    }
+}
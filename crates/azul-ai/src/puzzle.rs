@@ -0,0 +1,105 @@
+//! Puzzles: positions from self-play in which one move is clearly better than every other,
+//! for a "find the best move" challenge. A puzzle is fixed by its seed, so a daily puzzle can
+//! be shared as a number.
+
+use crate::alpha_beta_ai::{ordered_moves, AlphaBetaAI};
+use crate::heuristic_ai::HeuristicAI;
+use crate::review::{evaluate_move, MoveReview, REVIEW_DEPTH};
+use crate::AIAgent;
+use azul_core::{GamePhase, GameState, Move, MoveError};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Points by which the best move must beat the runner-up.
+pub const PUZZLE_MARGIN: f32 = 3.0;
+/// Positions with fewer legal moves are too easy to count as puzzles.
+const MIN_LEGAL_MOVES: usize = 6;
+/// Games tried for a seed before giving up.
+const MAX_GAMES: u64 = 50;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Puzzle {
+    pub seed: u64,
+    pub state: GameState,
+    pub best_move: Move,
+    /// Points the best move gains over the runner-up, `PUZZLE_MARGIN` or more.
+    pub margin: f32,
+}
+
+/// The verdict on an answer, with the review that explains it.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleAnswer {
+    pub correct: bool,
+    pub review: MoveReview,
+}
+
+impl Puzzle {
+    /// Checks `answer` against the puzzle's best move. Only that move is correct; the review
+    /// says how much any other one gives up.
+    pub fn check(&self, answer: &Move) -> Result<PuzzleAnswer, MoveError> {
+        let review = evaluate_move(&self.state, answer)?;
+        Ok(PuzzleAnswer { correct: *answer == self.best_move, review })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Puzzle {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a puzzle always serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let puzzle: Puzzle = serde_json::from_str(json).map_err(|e| format!("not a puzzle: {}", e))?;
+        puzzle.state.check_invariants().map_err(|e| format!("the puzzle's position is invalid: {}", e))?;
+        if puzzle.state.phase() != GamePhase::Drafting {
+            return Err("the puzzle's position has no move to find".to_string());
+        }
+        puzzle.state.validate_move(&puzzle.best_move).map_err(|e| format!("the puzzle's answer is illegal: {}", e))?;
+        Ok(puzzle)
+    }
+}
+
+/// Plays HeuristicAI self-play games dealt from `seed` (and, if none of its positions will do,
+/// the seeds after it) until a position turns up in which the best move, searched
+/// `REVIEW_DEPTH` plies deep, beats every other move by `PUZZLE_MARGIN` points. Returns `None`
+/// if no game has one.
+pub fn generate_puzzle(num_players: usize, seed: u64) -> Option<Puzzle> {
+    (0..MAX_GAMES).find_map(|game| find_in_game(num_players, seed, seed.wrapping_add(game)))
+}
+
+fn find_in_game(num_players: usize, seed: u64, game_seed: u64) -> Option<Puzzle> {
+    let mut game_state = GameState::new_with_seed(num_players, game_seed);
+    let mut agent = HeuristicAI::new();
+    let mut search = AlphaBetaAI::new(REVIEW_DEPTH);
+    while !game_state.is_game_over() {
+        while game_state.phase() == GamePhase::Drafting {
+            if let Some((best_move, margin)) = clear_best_move(&mut search, &game_state) {
+                return Some(Puzzle { seed, state: game_state, best_move, margin });
+            }
+            let m = agent.get_move(&game_state)?;
+            game_state.apply_move(&m);
+        }
+        game_state.advance(None).expect("the drafting phase is over");
+    }
+    None
+}
+
+/// The best move and its lead over the runner-up, if the lead is at least `PUZZLE_MARGIN`.
+fn clear_best_move(search: &mut AlphaBetaAI, game_state: &GameState) -> Option<(Move, f32)> {
+    // Strongest-looking first, so that a position without a clear best move is rejected early.
+    let moves = ordered_moves(&mut game_state.clone());
+    if moves.len() < MIN_LEGAL_MOVES {
+        return None;
+    }
+    let (best_move, best_value) = search.best_move_value(game_state)?;
+    let mut runner_up = f32::NEG_INFINITY;
+    for m in moves.iter().filter(|&m| *m != best_move) {
+        runner_up = runner_up.max(search.move_value(game_state, m));
+        if best_value - runner_up < PUZZLE_MARGIN {
+            return None;
+        }
+    }
+    Some((best_move, best_value - runner_up))
+}
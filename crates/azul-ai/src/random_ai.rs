@@ -0,0 +1,48 @@
+use crate::{AIAgent, AgentConfig};
+use azul_core::{GameState, Move};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::any::Any;
+
+/// Picks uniformly among the legal moves. The floor every other agent should beat; seed it with
+/// `with_seed` for repeatable benchmark runs.
+pub struct RandomAI {
+    rng: StdRng,
+}
+
+impl RandomAI {
+    pub fn new() -> Self {
+        Self { rng: StdRng::from_entropy() }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Default for RandomAI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AIAgent for RandomAI {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        game_state.get_legal_moves().choose(&mut self.rng).cloned()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "RandomAI"
+    }
+
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(seed) = config.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Builds agents from spec strings such as `"mctsheuristic:2000:cpuct=2.0"`, so that every
+//! front-end accepts the same agent names. Downstream crates can add their own agents (or
+//! replace a built-in one) with `AgentRegistry::register`. With the "serde" feature, a spec can
+//! also be written as an `AgentTable`, e.g. in a TOML file.
+
+use crate::{
+    alpha_beta_ai::AlphaBetaAI,
+    difficulty::Difficulty,
+    flat_mc_ai::FlatMcAI,
+    greedy_ai::GreedyAI,
+    heuristic_ai::HeuristicAI,
+    human_agent::HumanAgent,
+    lookahead_ai::LookaheadAI,
+    max_n_ai::MaxNAI,
+    mcts_heuristic_ai::MctsHeuristicAI,
+    mcts_lib::MctsConfig,
+    random_ai::RandomAI,
+    simple_ai::SimpleAI,
+    AIAgent, AgentConfig,
+};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Search iterations for `mctsheuristic` when the spec doesn't give any.
+pub const DEFAULT_MCTS_HEURISTIC_ITERATIONS: u32 = 2000;
+/// Search iterations for `mctsnn` when the spec doesn't give any.
+pub const DEFAULT_MCTS_NN_ITERATIONS: u32 = 800;
+
+/// A parsed agent spec: a name, then colon-separated positional arguments and `key=value`
+/// options, e.g. `alphabeta:4` or `mctsheuristic:500:cpuct=2.0:endgame=0`. Names are
+/// case-insensitive. Every agent takes a `seed=N` option, which seeds it through
+/// `AIAgent::configure`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentSpec {
+    pub name: String,
+    pub args: Vec<String>,
+    pub options: Vec<(String, String)>,
+}
+
+impl AgentSpec {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_lowercase(), args: Vec::new(), options: Vec::new() }
+    }
+
+    /// Parses the positional argument at `idx`, or returns `None` if the spec doesn't have it.
+    /// `what` names the argument in the error message.
+    pub fn arg<T: FromStr>(&self, idx: usize, what: &str) -> Result<Option<T>, String> {
+        match self.args.get(idx) {
+            Some(arg) => arg.parse().map(Some)
+                .map_err(|_| format!("Invalid {} '{}' in agent spec: {}", what, arg, self)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fails if the spec carries options, for agents that don't take any.
+    pub fn expect_no_options(&self) -> Result<(), String> {
+        match self.options.first() {
+            Some((key, _)) => Err(format!("Agent '{}' takes no option '{}'", self.name, key)),
+            None => Ok(()),
+        }
+    }
+
+    /// The MCTS settings given by the spec's options.
+    pub fn mcts_config(&self) -> Result<MctsConfig, String> {
+        let mut config = MctsConfig::default();
+        for (key, value) in &self.options {
+            config.set(key, value).map_err(|e| format!("{} in agent spec: {}", e, self))?;
+        }
+        Ok(config)
+    }
+}
+
+/// An agent spec as a table of named settings, for agents files (`--players agents.toml`):
+///
+/// ```toml
+/// [[agents]]
+/// type = "mctsnn"
+/// iterations = 400
+/// model = 'C:\models\azul_model_v7.weights'
+/// time = 0.5
+/// options = { cpuct = 2.0, batch = 8 }
+/// ```
+///
+/// Each setting is only accepted by the agents that have it.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentTable {
+    /// The agent name, e.g. "mctsheuristic".
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Search iterations for mctsheuristic and mctsnn, or rollouts per move for flatmc.
+    pub iterations: Option<u32>,
+    /// Search depth in plies for alphabeta, maxn and lookahead.
+    pub depth: Option<u32>,
+    /// The difficulty preset, e.g. "medium".
+    pub level: Option<String>,
+    /// Seconds per move for MCTS agents, which stop searching when it runs out even if they
+    /// have iterations left.
+    pub time: Option<f64>,
+    /// The weights file for mctsnn.
+    pub model: Option<String>,
+    /// The weights JSON file for heuristicai.
+    pub weights: Option<String>,
+    pub seed: Option<u64>,
+    /// Further `key=value` options, such as the MCTS search settings.
+    #[serde(default)]
+    pub options: BTreeMap<String, serde_json::Value>,
+}
+
+#[cfg(feature = "serde")]
+impl AgentTable {
+    /// The equivalent spec. Its string form keeps paths with colons in them intact, since
+    /// heuristicai and mctsnn rejoin their trailing arguments.
+    pub fn to_spec(&self) -> Result<AgentSpec, String> {
+        let mut spec = AgentSpec::new(self.kind.trim());
+        if spec.name.is_empty() {
+            return Err("Missing agent type in agent table".to_string());
+        }
+        let iterations = self.iterations.map(|iterations| iterations.to_string());
+        let depth = self.depth.map(|depth| depth.to_string());
+        let (args, taken): (Vec<Option<String>>, &[&str]) = match spec.name.as_str() {
+            "mctsheuristic" | "flatmc" => (vec![iterations], &["iterations"]),
+            "mctsnn" => {
+                // The model is the second argument, so it needs an iteration count before it.
+                let iterations = iterations.or_else(|| self.model.as_ref().map(|_| DEFAULT_MCTS_NN_ITERATIONS.to_string()));
+                (vec![iterations, self.model.clone()], &["iterations", "model"])
+            }
+            "alphabeta" | "maxn" | "lookahead" => (vec![depth], &["depth"]),
+            "difficulty" => (vec![self.level.clone()], &["level"]),
+            "heuristicai" => (vec![self.weights.clone()], &["weights"]),
+            _ => (Vec::new(), &[]),
+        };
+        let settings = [
+            ("iterations", self.iterations.is_some()),
+            ("depth", self.depth.is_some()),
+            ("level", self.level.is_some()),
+            ("model", self.model.is_some()),
+            ("weights", self.weights.is_some()),
+        ];
+        if let Some((setting, _)) = settings.iter().find(|(setting, set)| *set && !taken.contains(setting)) {
+            return Err(format!("Agent '{}' takes no '{}' setting", spec.name, setting));
+        }
+        spec.args = args.into_iter().map_while(|arg| arg).collect();
+        if let Some(seed) = self.seed {
+            spec.options.push(("seed".to_string(), seed.to_string()));
+        }
+        if let Some(time) = self.time {
+            spec.options.push(("time".to_string(), time.to_string()));
+        }
+        for (key, value) in &self.options {
+            let value = match value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            spec.options.push((key.trim().to_lowercase(), value));
+        }
+        Ok(spec)
+    }
+}
+
+impl FromStr for AgentSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let name = parts.next().unwrap_or("");
+        if name.is_empty() {
+            return Err(format!("Missing agent name in spec: '{}'", s));
+        }
+        let mut spec = Self::new(name);
+        for part in parts {
+            match part.split_once('=') {
+                Some((key, value)) => spec.options.push((key.trim().to_lowercase(), value.trim().to_string())),
+                None => spec.args.push(part.to_string()),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl fmt::Display for AgentSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for arg in &self.args {
+            write!(f, ":{}", arg)?;
+        }
+        for (key, value) in &self.options {
+            write!(f, ":{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds an agent from a spec whose name it was registered under.
+pub type AgentFactory = Box<dyn Fn(&AgentSpec) -> Result<Box<dyn AIAgent>, String> + Send + Sync>;
+
+/// Maps agent names to factories. `AgentRegistry::new` comes with the built-in agents.
+pub struct AgentRegistry {
+    factories: BTreeMap<String, AgentFactory>,
+}
+
+impl AgentRegistry {
+    /// A registry with no agents in it.
+    pub fn empty() -> Self {
+        Self { factories: BTreeMap::new() }
+    }
+
+    /// A registry with the built-in agents: human, random[:seed], simpleai, greedyai,
+    /// heuristicai[:weights.json], difficulty:<level>, alphabeta[:depth], maxn[:depth],
+    /// lookahead[:depth], flatmc[:rollouts], mctsheuristic[:iterations][:key=value...], and, with the "nn" feature,
+    /// mctsnn[:iterations][:model][:key=value...].
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register("human", |spec| {
+            spec.expect_no_options()?;
+            Ok(Box::new(HumanAgent))
+        });
+        registry.register("random", |spec| {
+            spec.expect_no_options()?;
+            Ok(match spec.arg::<u64>(0, "seed")? {
+                Some(seed) => Box::new(RandomAI::with_seed(seed)),
+                None => Box::new(RandomAI::new()),
+            })
+        });
+        registry.register("simpleai", |spec| {
+            spec.expect_no_options()?;
+            Ok(Box::new(SimpleAI))
+        });
+        registry.register("greedyai", |spec| {
+            spec.expect_no_options()?;
+            Ok(Box::new(GreedyAI))
+        });
+        registry.register("heuristicai", create_heuristic_ai);
+        registry.register("difficulty", |spec| {
+            spec.expect_no_options()?;
+            let level = spec.arg::<Difficulty>(0, "difficulty")?
+                .ok_or_else(|| format!("Missing level in agent spec: {} (e.g. difficulty:medium)", spec))?;
+            Ok(level.create_agent())
+        });
+        registry.register("alphabeta", |spec| {
+            spec.expect_no_options()?;
+            Ok(match spec.arg::<u32>(0, "depth")? {
+                Some(depth) => Box::new(AlphaBetaAI::new(depth)),
+                None => Box::new(AlphaBetaAI::default()),
+            })
+        });
+        registry.register("maxn", |spec| {
+            spec.expect_no_options()?;
+            Ok(match spec.arg::<u32>(0, "depth")? {
+                Some(depth) => Box::new(MaxNAI::new(depth)),
+                None => Box::new(MaxNAI::default()),
+            })
+        });
+        registry.register("lookahead", |spec| {
+            spec.expect_no_options()?;
+            Ok(match spec.arg::<u32>(0, "depth")? {
+                Some(depth) => Box::new(LookaheadAI::new(depth)),
+                None => Box::new(LookaheadAI::default()),
+            })
+        });
+        registry.register("flatmc", |spec| {
+            spec.expect_no_options()?;
+            Ok(match spec.arg::<u32>(0, "rollout count")? {
+                Some(rollouts) => Box::new(FlatMcAI::new(rollouts)),
+                None => Box::new(FlatMcAI::default()),
+            })
+        });
+        registry.register("mctsheuristic", |spec| {
+            let iterations = spec.arg::<u32>(0, "iteration count")?.unwrap_or(DEFAULT_MCTS_HEURISTIC_ITERATIONS);
+            Ok(Box::new(MctsHeuristicAI::new(iterations).with_config(spec.mcts_config()?)))
+        });
+        #[cfg(feature = "nn")]
+        registry.register("mctsnn", |spec| {
+            use crate::mcts_nn_ai::MctsNnAI;
+            let iterations = spec.arg::<u32>(0, "iteration count")?.unwrap_or(DEFAULT_MCTS_NN_ITERATIONS);
+            // Rejoined so that a Windows path such as C:\azul.weights survives the split.
+            let model_path = (spec.args.len() > 1).then(|| spec.args[1..].join(":"));
+            Ok(Box::new(MctsNnAI::new(iterations, model_path, None).with_config(spec.mcts_config()?)))
+        });
+        registry
+    }
+
+    /// Registers `factory` under `name`, replacing any agent already registered under it.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&AgentSpec) -> Result<Box<dyn AIAgent>, String> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_lowercase(), Box::new(factory));
+    }
+
+    /// Whether an agent is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(&name.to_lowercase())
+    }
+
+    /// The registered agent names, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    pub fn create(&self, spec: &AgentSpec) -> Result<Box<dyn AIAgent>, String> {
+        let factory = self.factories.get(&spec.name).ok_or_else(|| format!(
+            "Unknown agent type: {} (expected one of: {})",
+            spec, self.names().collect::<Vec<_>>().join(", ")
+        ))?;
+        // The seed option is for every agent, so the factories never see it.
+        let mut rest = spec.clone();
+        let seed = match rest.options.iter().position(|(key, _)| key == "seed") {
+            Some(idx) => {
+                let (_, value) = rest.options.remove(idx);
+                Some(value.parse::<u64>().map_err(|_| format!("Invalid seed '{}' in agent spec: {}", value, spec))?)
+            }
+            None => None,
+        };
+        let mut agent = factory(&rest)?;
+        if seed.is_some() {
+            agent.configure(&AgentConfig { seed, ..AgentConfig::default() });
+        }
+        Ok(agent)
+    }
+
+    /// Parses `spec` and builds the agent it names.
+    pub fn create_from_str(&self, spec: &str) -> Result<Box<dyn AIAgent>, String> {
+        self.create(&spec.parse()?)
+    }
+}
+
+impl Default for AgentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds one of the built-in agents.
+pub fn create_agent(spec: &AgentSpec) -> Result<Box<dyn AIAgent>, String> {
+    AgentRegistry::new().create(spec)
+}
+
+fn create_heuristic_ai(spec: &AgentSpec) -> Result<Box<dyn AIAgent>, String> {
+    spec.expect_no_options()?;
+    if spec.args.is_empty() {
+        return Ok(Box::new(HeuristicAI::new()));
+    }
+    // Rejoined so that a Windows path such as C:\weights.json survives the split.
+    #[cfg(feature = "serde")]
+    {
+        let weights = crate::heuristic_ai::HeuristicWeights::from_json_file(&spec.args.join(":"))?;
+        Ok(Box::new(HeuristicAI::with_weights(weights)))
+    }
+    #[cfg(not(feature = "serde"))]
+    Err(format!("Loading heuristic weights needs the \"serde\" feature: {}", spec))
+}
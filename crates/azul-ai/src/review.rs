@@ -0,0 +1,126 @@
+use crate::alpha_beta_ai::AlphaBetaAI;
+use crate::mcts_heuristic_ai::{MctsHeuristicAI, VALUE_SCALE};
+use crate::mcts_lib::MctsConfig;
+use crate::AIAgent;
+use azul_core::{GamePhase, GameState, Move, MoveError};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Plies searched for each move when reviewing, counting the move itself.
+pub const REVIEW_DEPTH: u32 = 3;
+
+/// How costly a move was, by the points it gave up against the engine's choice.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveQuality {
+    /// Within half a point of the best move.
+    Good,
+    /// Gave up up to 2 points.
+    Inaccuracy,
+    /// Gave up up to 5 points.
+    Mistake,
+    /// Gave up more than 5 points.
+    Blunder,
+}
+
+impl MoveQuality {
+    pub fn from_loss(loss: f32) -> Self {
+        if loss < 0.5 {
+            MoveQuality::Good
+        } else if loss <= 2.0 {
+            MoveQuality::Inaccuracy
+        } else if loss <= 5.0 {
+            MoveQuality::Mistake
+        } else {
+            MoveQuality::Blunder
+        }
+    }
+
+    /// The annotation symbol, as in chess: "", "?!", "?", or "??".
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            MoveQuality::Good => "",
+            MoveQuality::Inaccuracy => "?!",
+            MoveQuality::Mistake => "?",
+            MoveQuality::Blunder => "??",
+        }
+    }
+}
+
+/// A played move compared with the best move of a fixed-depth search. Values are the mover's
+/// margin over the best opponent, in points.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReview {
+    pub played: Move,
+    pub best_move: Move,
+    pub played_value: f32,
+    pub best_value: f32,
+    /// Hundredths of a point given up by playing `played` instead of `best_move`; never negative.
+    pub centipoints: u32,
+    pub quality: MoveQuality,
+}
+
+impl MoveReview {
+    /// The points given up, e.g. 6.0 for a move that cost 600 centipoints.
+    pub fn loss(&self) -> f32 {
+        self.centipoints as f32 / 100.0
+    }
+}
+
+/// Reviews `played` for the player to move in `game_state`: the best move and the played move
+/// are each searched `REVIEW_DEPTH` plies deep, and their values compared.
+pub fn evaluate_move(game_state: &GameState, played: &Move) -> Result<MoveReview, MoveError> {
+    game_state.validate_move(played)?;
+    let mut search = AlphaBetaAI::new(REVIEW_DEPTH);
+    let (best_move, best_value) = search.best_move_value(game_state)
+        .expect("a valid move means there are legal moves");
+    let played_value = if *played == best_move { best_value } else { search.move_value(game_state, played) };
+    let loss = (best_value - played_value).max(0.0);
+    Ok(MoveReview {
+        played: played.clone(),
+        best_move,
+        played_value,
+        best_value,
+        centipoints: (loss * 100.0).round() as u32,
+        quality: MoveQuality::from_loss(loss),
+    })
+}
+
+/// Reviews `played` like `evaluate_move`, but with an `iterations`-long MCTS search instead of
+/// the fixed-depth one, which sees to the end of the game. The best move's value is the
+/// search's value of the position; the played move's is that of a second search from the
+/// position it leads to, or, if it ends the round, the first search's estimate of it.
+pub fn evaluate_move_with_search(game_state: &GameState, played: &Move, iterations: u32) -> Result<MoveReview, MoveError> {
+    game_state.validate_move(played)?;
+    // The endgame solver would skip the search whose values are compared here.
+    let new_engine = || MctsHeuristicAI::new(iterations.max(1)).with_config(MctsConfig { endgame_nodes: 0, ..MctsConfig::default() });
+    let mut engine = new_engine();
+    let best_move = engine.get_move(game_state).expect("a valid move means there are legal moves");
+    let best_value = engine.root_value().unwrap_or(0.0) * VALUE_SCALE;
+
+    let mut next_state = game_state.clone();
+    next_state.apply_move(played);
+    let played_value = if *played == best_move {
+        best_value
+    } else if next_state.phase() == GamePhase::Drafting {
+        // The search backs values up as zero-sum, so the mover's value is minus the next
+        // player's.
+        let mut reply_engine = new_engine();
+        reply_engine.get_move(&next_state);
+        -reply_engine.root_value().unwrap_or(0.0) * VALUE_SCALE
+    } else {
+        engine.analysis(usize::MAX)
+            .and_then(|analysis| analysis.top_moves.into_iter().find(|stats| stats.mv == *played))
+            .map_or(0.0, |stats| stats.q * VALUE_SCALE)
+    };
+    let loss = (best_value - played_value).max(0.0);
+    Ok(MoveReview {
+        played: played.clone(),
+        best_move,
+        played_value,
+        best_value,
+        centipoints: (loss * 100.0).round() as u32,
+        quality: MoveQuality::from_loss(loss),
+    })
+}
@@ -1,4 +1,5 @@
-use crate::{ai::AIAgent, GameState, Move, MoveDestination};
+use crate::AIAgent;
+use azul_core::{GameState, Move, MoveDestination};
 use std::any::Any;
 
 pub struct SimpleAI;
@@ -22,4 +23,8 @@ impl AIAgent for SimpleAI {
     fn as_any(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn name(&self) -> &str {
+        "SimpleAI"
+    }
 }
\ No newline at end of file
@@ -0,0 +1,150 @@
+//! A compact binary file format for `TrainingData`, written and read one sample at a time so
+//! that neither self-play nor training has to hold a whole dataset as JSON.
+//!
+//! Layout (little-endian): the `SAMPLES_MAGIC` bytes and a u32 format version, then one record
+//! per sample: the state input, the MCTS policy and the legal mask as sparse vectors, then the
+//! outcome and the score margin as f32s, with a NaN margin for none. Version 1 files have no
+//! score margin. A sparse vector is its u32 length, a u32 count of nonzero entries, and a
+//! u32 index and f32 value for each of them. Inputs, policies and masks are mostly zeros.
+
+use crate::TrainingData;
+use std::io::{self, Read, Write};
+
+/// The first bytes of every samples file.
+pub const SAMPLES_MAGIC: [u8; 4] = *b"AZTD";
+/// The file extension self-play gives samples files.
+pub const SAMPLES_EXTENSION: &str = "samples";
+const SAMPLES_VERSION: u32 = 2;
+/// The first format version with score margins.
+const SCORE_MARGIN_VERSION: u32 = 2;
+
+/// Writes samples to `W` as they come in. Wrap files in a `BufWriter`.
+pub struct TrainingDataWriter<W: Write> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: Write> TrainingDataWriter<W> {
+    /// Writes the file header.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&SAMPLES_MAGIC)?;
+        inner.write_all(&SAMPLES_VERSION.to_le_bytes())?;
+        Ok(Self { inner, count: 0 })
+    }
+
+    pub fn write(&mut self, sample: &TrainingData) -> io::Result<()> {
+        write_sparse(&mut self.inner, &sample.state_input)?;
+        write_sparse(&mut self.inner, &sample.mcts_policy)?;
+        write_sparse(&mut self.inner, &sample.legal_mask)?;
+        self.inner.write_all(&sample.outcome.to_le_bytes())?;
+        self.inner.write_all(&sample.score_margin.unwrap_or(f32::NAN).to_le_bytes())?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Flushes the samples written so far, so that a reader sees only whole records.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Samples written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Flushes and hands back the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Reads samples from `R` one at a time. Wrap files in a `BufReader`.
+pub struct TrainingDataReader<R: Read> {
+    inner: R,
+    version: u32,
+}
+
+impl<R: Read> TrainingDataReader<R> {
+    /// Reads and checks the file header. Files of any earlier format version can be read.
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        inner.read_exact(&mut magic)?;
+        if magic != SAMPLES_MAGIC {
+            return Err(invalid_data("Not a training samples file".to_string()));
+        }
+        let version = read_u32(&mut inner)?;
+        if version == 0 || version > SAMPLES_VERSION {
+            return Err(invalid_data(format!("Unsupported training samples format version {}", version)));
+        }
+        Ok(Self { inner, version })
+    }
+
+    /// The next sample, or `None` at the end of the file.
+    pub fn read(&mut self) -> io::Result<Option<TrainingData>> {
+        // A clean end of file can only come before a record's first byte.
+        let mut first = [0; 4];
+        match self.inner.read(&mut first[..1])? {
+            0 => return Ok(None),
+            _ => self.inner.read_exact(&mut first[1..])?,
+        }
+        let state_input = read_sparse_with_len(&mut self.inner, u32::from_le_bytes(first))?;
+        let mcts_policy = read_sparse(&mut self.inner)?;
+        let legal_mask = read_sparse(&mut self.inner)?;
+        let outcome = f32::from_bits(read_u32(&mut self.inner)?);
+        let score_margin = if self.version >= SCORE_MARGIN_VERSION {
+            Some(f32::from_bits(read_u32(&mut self.inner)?)).filter(|margin| !margin.is_nan())
+        } else {
+            None
+        };
+        Ok(Some(TrainingData { state_input, mcts_policy, legal_mask, outcome, score_margin }))
+    }
+}
+
+impl<R: Read> Iterator for TrainingDataReader<R> {
+    type Item = io::Result<TrainingData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read().transpose()
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn write_sparse(writer: &mut impl Write, values: &[f32]) -> io::Result<()> {
+    writer.write_all(&(values.len() as u32).to_le_bytes())?;
+    let nonzero = values.iter().filter(|&&v| v != 0.0).count() as u32;
+    writer.write_all(&nonzero.to_le_bytes())?;
+    for (idx, value) in values.iter().enumerate().filter(|(_, &v)| v != 0.0) {
+        writer.write_all(&(idx as u32).to_le_bytes())?;
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_sparse(reader: &mut impl Read) -> io::Result<Vec<f32>> {
+    let len = read_u32(reader)?;
+    read_sparse_with_len(reader, len)
+}
+
+fn read_sparse_with_len(reader: &mut impl Read, len: u32) -> io::Result<Vec<f32>> {
+    let nonzero = read_u32(reader)?;
+    if nonzero > len {
+        return Err(invalid_data(format!("Sparse vector has {} entries but length {}", nonzero, len)));
+    }
+    let mut values = vec![0.0; len as usize];
+    for _ in 0..nonzero {
+        let idx = read_u32(reader)? as usize;
+        let value = f32::from_bits(read_u32(reader)?);
+        *values.get_mut(idx).ok_or_else(|| invalid_data(format!("Sparse index {} out of range {}", idx, len)))? = value;
+    }
+    Ok(values)
+}
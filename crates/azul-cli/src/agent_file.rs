@@ -0,0 +1,47 @@
+//! Agents files for `--players agents.toml`: a TOML list of `AgentTable`s, for agents whose
+//! settings don't fit the colon-separated spec strings, such as a model on a Windows path.
+//!
+//! ```toml
+//! [[agents]]
+//! type = "mctsheuristic"
+//! iterations = 5000
+//! seed = 7
+//! options = { cpuct = 2.0 }
+//!
+//! [[agents]]
+//! type = "heuristicai"
+//! weights = "tuned_weights.json"
+//! ```
+
+use azul_ai::registry::AgentTable;
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AgentFile {
+    agents: Vec<AgentTable>,
+}
+
+/// The agent specs in the agents file at `path`, in order, as spec strings.
+pub fn read_agent_file(path: &str) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let file: AgentFile = toml::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+    file.agents.iter()
+        .map(|table| table.to_spec().map(|spec| spec.to_string()).map_err(|e| format!("{}: {}", path, e)))
+        .collect()
+}
+
+/// `players` with every entry that names a `.toml` file replaced by the agents in it, so that
+/// agents files and spec strings can be mixed.
+pub fn expand_agent_files(players: &[String]) -> Result<Vec<String>, String> {
+    let mut specs = Vec::new();
+    for player in players {
+        if player.ends_with(".toml") {
+            specs.extend(read_agent_file(player)?);
+        } else {
+            specs.push(player.clone());
+        }
+    }
+    Ok(specs)
+}
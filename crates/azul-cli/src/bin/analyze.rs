@@ -0,0 +1,174 @@
+use azul_ai::review::{evaluate_move_with_search, MoveQuality, MoveReview};
+use azul_cli::game_log::{read_game_logs, GameLog};
+use azul_core::GameState;
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Runs an MCTS search on every recorded position of a `headless` game log and annotates each
+/// move with the points it gave up against the search's choice, flagging inaccuracies,
+/// mistakes and blunders per player.
+#[derive(Parser, Debug)]
+#[command(version, about = "Annotate headless game logs with an engine's evaluations", long_about = None)]
+struct Cli {
+    /// A game log: game_logs.jsonl, game_logs.jsonl.gz, or an older game_logs.json.
+    path: PathBuf,
+    /// MCTS iterations per position. Each move that isn't the engine's choice is searched
+    /// a second time from the position it leads to.
+    #[arg(short, long, default_value_t = 2000)]
+    iterations: u32,
+    /// Only analyze this game (numbered from 1, in the log's order).
+    #[arg(short, long)]
+    game: Option<usize>,
+    /// Where to write the annotated games, one JSON line each.
+    #[arg(short, long, default_value = "game_analysis.jsonl")]
+    output: PathBuf,
+    /// Worker threads for analyzing positions in parallel. Defaults to one per core.
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+}
+
+#[derive(Serialize)]
+struct AnnotatedMove {
+    round: usize,
+    /// The move's number within its round, from 1.
+    turn: usize,
+    player_index: usize,
+    review: MoveReview,
+}
+
+/// One player's moves by quality, and the points they gave up on average.
+#[derive(Serialize)]
+struct PlayerSummary {
+    agent: String,
+    moves: u32,
+    inaccuracies: u32,
+    mistakes: u32,
+    blunders: u32,
+    average_loss: f64,
+}
+
+#[derive(Serialize)]
+struct AnnotatedGame {
+    /// The game's number in the log, from 1.
+    game: usize,
+    matchup: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    final_scores: Vec<u32>,
+    moves: Vec<AnnotatedMove>,
+    players: Vec<PlayerSummary>,
+}
+
+impl AnnotatedGame {
+    fn new(game: usize, log: GameLog, moves: Vec<AnnotatedMove>) -> Self {
+        let players = log.matchup.iter().enumerate()
+            .map(|(seat, agent)| {
+                let reviews: Vec<&MoveReview> = moves.iter().filter(|m| m.player_index == seat).map(|m| &m.review).collect();
+                let count = |quality: MoveQuality| reviews.iter().filter(|r| r.quality == quality).count() as u32;
+                PlayerSummary {
+                    agent: agent.clone(),
+                    moves: reviews.len() as u32,
+                    inaccuracies: count(MoveQuality::Inaccuracy),
+                    mistakes: count(MoveQuality::Mistake),
+                    blunders: count(MoveQuality::Blunder),
+                    average_loss: reviews.iter().map(|r| r.loss() as f64).sum::<f64>() / reviews.len().max(1) as f64,
+                }
+            })
+            .collect();
+        Self { game, matchup: log.matchup, seed: log.seed, final_scores: log.final_scores, moves, players }
+    }
+
+    fn print(&self) {
+        println!("\n--- Game {}: {} (final scores {:?}) ---", self.game, self.matchup.join(" vs "), self.final_scores);
+        for (seat, player) in self.players.iter().enumerate() {
+            println!(
+                "  Player {} ({}): {} moves, {} inaccuracies, {} mistakes, {} blunders, {:.2} points lost per move",
+                seat + 1, player.agent, player.moves, player.inaccuracies, player.mistakes, player.blunders, player.average_loss
+            );
+        }
+        for m in self.moves.iter().filter(|m| matches!(m.review.quality, MoveQuality::Mistake | MoveQuality::Blunder)) {
+            let review = &m.review;
+            println!(
+                "    Round {}, turn {}, player {}: {}{} ({:+.1} -> {:+.1}; best was {})",
+                m.round, m.turn, m.player_index + 1, review.played, review.quality.symbol(),
+                review.best_value, review.played_value, review.best_move
+            );
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new().num_threads(threads.get()).build_global().map_err(|e| e.to_string())?;
+    }
+    let logs = read_game_logs(&cli.path).map_err(|e| format!("Could not read {}: {}", cli.path.display(), e))?;
+    let mut games = Vec::new();
+    for (i, log) in logs.enumerate() {
+        let number = i + 1;
+        if cli.game.is_none_or(|game| game == number) {
+            games.push((number, log.map_err(|e| format!("{}, game {}: {}", cli.path.display(), number, e))?));
+        }
+    }
+    if games.is_empty() {
+        return Err(match cli.game {
+            Some(game) => format!("There is no game {} in {}", game, cli.path.display()),
+            None => format!("{} has no games", cli.path.display()),
+        });
+    }
+
+    // Every position of every game, analyzed in parallel.
+    let positions: Vec<(usize, usize, usize)> = games.iter().enumerate()
+        .flat_map(|(g, (_, log))| {
+            log.history.iter().enumerate().flat_map(move |(r, round)| (0..round.turns.len()).map(move |t| (g, r, t)))
+        })
+        .collect();
+    println!("Analyzing {} positions from {} games at {} iterations each...", positions.len(), games.len(), cli.iterations);
+    let start_time = Instant::now();
+    let style = ProgressStyle::with_template("{bar:40} {pos}/{len} positions  {per_sec}  elapsed {elapsed}, ETA {eta}")
+        .expect("the progress bar template is valid");
+    let progress = ProgressBar::new(positions.len() as u64).with_style(style);
+    let reviews: Vec<Result<AnnotatedMove, String>> = positions.par_iter()
+        .map(|&(g, r, t)| {
+            let (number, log) = &games[g];
+            let round = &log.history[r];
+            let turn = &round.turns[t];
+            let state = GameState::from_turn_state(&turn.state_before_move);
+            let review = evaluate_move_with_search(&state, &turn.chosen_move, cli.iterations)
+                .map_err(|e| format!("Game {}, round {}, turn {}: {} is illegal: {}", number, round.round_number, t + 1, turn.chosen_move, e))?;
+            progress.inc(1);
+            Ok(AnnotatedMove { round: round.round_number, turn: t + 1, player_index: turn.player_index, review })
+        })
+        .collect();
+    progress.finish_and_clear();
+
+    let mut reviews = reviews.into_iter();
+    let mut writer = BufWriter::new(File::create(&cli.output).map_err(|e| format!("Could not create {}: {}", cli.output.display(), e))?);
+    for (number, log) in games {
+        let count: usize = log.history.iter().map(|round| round.turns.len()).sum();
+        let moves = reviews.by_ref().take(count).collect::<Result<Vec<_>, _>>()?;
+        let annotated = AnnotatedGame::new(number, log, moves);
+        annotated.print();
+        serde_json::to_writer(&mut writer, &annotated).map_err(|e| e.to_string())?;
+        writeln!(writer).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+    println!(
+        "\nDone in {:.1}s. Annotated games saved to '{}'.",
+        start_time.elapsed().as_secs_f64(), cli.output.display()
+    );
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,141 @@
+use azul_ai::nn::WEIGHTS_EXTENSION;
+use azul_ai::training_io::{TrainingDataReader, TrainingDataWriter, SAMPLES_EXTENSION};
+use azul_ai::TrainingData;
+use azul_cli::self_play_http::{read_request, write_response, Request, MODEL_PATH, MODEL_VERSION_HEADER, SAMPLES_PATH};
+use chrono::prelude::*;
+use clap::Parser;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Serves the newest model to `headless --worker` processes and collects the self-play samples
+/// they upload into the training data directory, so that several machines can generate data
+/// for one training run.
+#[derive(Parser, Debug)]
+#[command(version, about = "Coordinate distributed self-play workers", long_about = None)]
+struct Cli {
+    /// Address to listen on.
+    #[arg(long, default_value = "0.0.0.0:8000")]
+    bind: String,
+    /// Where the newest azul_model_vN.weights is served from.
+    #[arg(long, default_value = "training_models")]
+    models_dir: PathBuf,
+    /// Where uploaded samples are written, one data file per model version.
+    #[arg(long, default_value = "training_data")]
+    data_dir: PathBuf,
+}
+
+/// The newest `azul_model_vN.weights` in `dir`, with its version.
+fn latest_model(dir: &Path) -> Option<(u32, PathBuf)> {
+    fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == WEIGHTS_EXTENSION))
+        .filter_map(|path| {
+            let version = path.file_stem()?.to_str()?.strip_prefix("azul_model_v")?.parse::<u32>().ok()?;
+            Some((version, path))
+        })
+        .max_by_key(|(version, _)| *version)
+}
+
+/// The data file that uploads currently go to.
+struct DataFile {
+    version: u32,
+    path: PathBuf,
+    writer: TrainingDataWriter<BufWriter<File>>,
+}
+
+struct Coordinator {
+    models_dir: PathBuf,
+    data_dir: PathBuf,
+    data_file: Option<DataFile>,
+}
+
+impl Coordinator {
+    /// Appends `samples` recorded with model `version`. Samples from a newer model than the
+    /// current file's start a new file, so that each file is one generation of training data;
+    /// late uploads from an older model go to the current file.
+    fn record(&mut self, version: u32, samples: &[TrainingData]) -> std::io::Result<(usize, PathBuf)> {
+        if self.data_file.as_ref().is_none_or(|file| version > file.version) {
+            if let Some(file) = self.data_file.take() {
+                file.writer.finish()?;
+            }
+            fs::create_dir_all(&self.data_dir)?;
+            let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+            let path = self.data_dir.join(format!("data_{}_v{}.{}", timestamp, version, SAMPLES_EXTENSION));
+            let writer = TrainingDataWriter::new(BufWriter::new(File::create(&path)?))?;
+            println!("Writing samples from model v{} to {:?}.", version, path);
+            self.data_file = Some(DataFile { version, path, writer });
+        }
+        let file = self.data_file.as_mut().expect("a data file was just opened");
+        for sample in samples {
+            file.writer.write(sample)?;
+        }
+        file.writer.flush()?;
+        Ok((file.writer.count(), file.path.clone()))
+    }
+}
+
+fn handle(request: Request, coordinator: &Mutex<Coordinator>, stream: &mut TcpStream) -> std::io::Result<()> {
+    let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_default();
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", MODEL_PATH) => {
+            let models_dir = coordinator.lock().expect("a connection handler panicked").models_dir.clone();
+            match latest_model(&models_dir) {
+                Some((version, path)) => {
+                    let bytes = fs::read(&path)?;
+                    println!("{}: sent model v{}.", peer, version);
+                    write_response(stream, 200, &[(MODEL_VERSION_HEADER, version.to_string())], &bytes)
+                }
+                None => write_response(stream, 204, &[], &[]),
+            }
+        }
+        ("POST", SAMPLES_PATH) => {
+            let version = request.message.model_version().unwrap_or(0);
+            // The whole upload is checked before any of it is recorded.
+            let samples = TrainingDataReader::new(request.message.body.as_slice())
+                .and_then(|reader| reader.collect::<std::io::Result<Vec<TrainingData>>>());
+            let samples = match samples {
+                Ok(samples) => samples,
+                Err(e) => return write_response(stream, 400, &[], format!("Invalid samples: {}", e).as_bytes()),
+            };
+            let (total, path) = coordinator.lock().expect("a connection handler panicked").record(version, &samples)?;
+            println!("{}: recorded {} samples from model v{} ({} in {:?}).", peer, samples.len(), version, total, path);
+            write_response(stream, 200, &[], format!("Recorded {} samples", samples.len()).as_bytes())
+        }
+        (_, MODEL_PATH) | (_, SAMPLES_PATH) => write_response(stream, 405, &[], &[]),
+        _ => write_response(stream, 404, &[], &[]),
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let cli = Cli::parse();
+    let listener = TcpListener::bind(&cli.bind)?;
+    match latest_model(&cli.models_dir) {
+        Some((version, path)) => println!("Serving model v{} ({:?}); newer models are picked up as they appear.", version, path),
+        None => println!("No model in {:?} yet; workers will play with a random brain.", cli.models_dir),
+    }
+    println!("Listening for self-play workers on {}.", cli.bind);
+    let coordinator = Arc::new(Mutex::new(Coordinator { models_dir: cli.models_dir, data_dir: cli.data_dir, data_file: None }));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Connection failed: {}", e);
+                continue;
+            }
+        };
+        let coordinator = Arc::clone(&coordinator);
+        std::thread::spawn(move || {
+            let result = read_request(&stream).and_then(|request| handle(request, &coordinator, &mut stream));
+            if let Err(e) = result {
+                eprintln!("Request failed: {}", e);
+                let _ = write_response(&mut stream, 500, &[], e.to_string().as_bytes());
+            }
+        });
+    }
+    Ok(())
+}
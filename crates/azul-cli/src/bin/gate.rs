@@ -0,0 +1,144 @@
+use azul_ai::{mcts_nn_ai::MctsNnAI, nn::{NeuralNetwork, WEIGHTS_EXTENSION}, AIAgent};
+use azul_core::{GamePhase, GameState, Winner};
+use clap::Parser;
+use rayon::prelude::*;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Plays a freshly trained model against the current release model over seeded games, and
+/// promotes it to release_models/ only if it wins often enough.
+#[derive(Parser, Debug)]
+#[command(version, about = "Promote a trained model only if it beats the release model", long_about = None)]
+struct Cli {
+    /// Weights of the model to evaluate. Defaults to the newest azul_model_vN.weights in
+    /// training_models/.
+    #[arg(long)]
+    candidate: Option<PathBuf>,
+    /// Weights of the current best model, which the candidate replaces if it passes.
+    #[arg(long, default_value = "release_models/azul_alpha.weights")]
+    incumbent: PathBuf,
+    /// Game pairs to play. Each pair plays the same seed twice with the seats swapped.
+    #[arg(short, long, default_value_t = 20)]
+    pairs: u32,
+    /// MCTS iterations per move for both models.
+    #[arg(short, long, default_value_t = 200)]
+    iterations: u32,
+    /// The candidate's score (wins plus half the shared wins, per game) must exceed this.
+    #[arg(short, long, default_value_t = 0.55)]
+    threshold: f64,
+    /// The first game seed; pair k uses seed + k.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Worker threads for playing games in parallel. Defaults to one per core.
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+}
+
+/// The newest `azul_model_vN.weights` in `dir`.
+fn latest_model(dir: &str) -> Option<PathBuf> {
+    fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == WEIGHTS_EXTENSION))
+        .filter_map(|path| {
+            let version = path.file_stem()?.to_str()?.strip_prefix("azul_model_v")?.parse::<u32>().ok()?;
+            Some((version, path))
+        })
+        .max_by_key(|(version, _)| *version)
+        .map(|(_, path)| path)
+}
+
+fn load_network(path: &Path) -> Result<NeuralNetwork, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    NeuralNetwork::from_bytes(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn nn_agent(network: &NeuralNetwork, iterations: u32) -> Result<Box<dyn AIAgent>, String> {
+    let mut agent = MctsNnAI::new(iterations, None, None);
+    agent.set_network(network.clone())?;
+    Ok(Box::new(agent))
+}
+
+/// Plays one 2-player game and returns seat 0's result: 1.0 for a win, 0.5 for a shared
+/// win, 0.0 for a loss.
+fn play_game(mut agents: [Box<dyn AIAgent>; 2], seed: u64) -> f64 {
+    let mut game = GameState::new_with_seed(2, seed);
+    while game.phase() != GamePhase::GameOver {
+        let next_move = match game.phase() {
+            GamePhase::Drafting => match agents[game.current_player_idx].get_move(&game) {
+                Some(m) => Some(m),
+                None => break,
+            },
+            _ => None,
+        };
+        if game.advance(next_move.as_ref()).is_err() {
+            break;
+        }
+    }
+    match game.winner() {
+        Winner::Single(0) => 1.0,
+        Winner::Single(_) => 0.0,
+        Winner::Shared(players) if players.contains(&0) => 0.5,
+        Winner::Shared(_) => 0.0,
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&cli.threshold) {
+        return Err("--threshold must be between 0.0 and 1.0.".to_string());
+    }
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new().num_threads(threads.get()).build_global().map_err(|e| e.to_string())?;
+    }
+    let candidate_path = match cli.candidate {
+        Some(path) => path,
+        None => latest_model("training_models")
+            .ok_or("No trained model found in training_models/. Run train first.")?,
+    };
+    let candidate = load_network(&candidate_path)?;
+
+    if cli.incumbent.exists() {
+        let incumbent = load_network(&cli.incumbent)?;
+        println!(
+            "Playing {} against {}: {} games at {} iterations per move...",
+            candidate_path.display(), cli.incumbent.display(), cli.pairs * 2, cli.iterations
+        );
+        let start_time = Instant::now();
+        let score: f64 = (0..cli.pairs)
+            .into_par_iter()
+            .map(|pair| {
+                let seed = cli.seed + pair as u64;
+                let as_first = play_game([nn_agent(&candidate, cli.iterations)?, nn_agent(&incumbent, cli.iterations)?], seed);
+                let as_second = 1.0 - play_game([nn_agent(&incumbent, cli.iterations)?, nn_agent(&candidate, cli.iterations)?], seed);
+                Ok(as_first + as_second)
+            })
+            .sum::<Result<f64, String>>()?;
+        let rate = score / (cli.pairs * 2).max(1) as f64;
+        println!(
+            "Candidate scored {:.1}/{} ({:.1}%) in {:.1}s; the threshold is {:.1}%.",
+            score, cli.pairs * 2, rate * 100.0, start_time.elapsed().as_secs_f64(), cli.threshold * 100.0
+        );
+        if rate <= cli.threshold {
+            println!("Not promoted. {} stays the release model.", cli.incumbent.display());
+            return Ok(());
+        }
+    } else {
+        println!("No release model at {} yet; promoting without a match.", cli.incumbent.display());
+    }
+
+    if let Some(dir) = cli.incumbent.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::copy(&candidate_path, &cli.incumbent).map_err(|e| e.to_string())?;
+    println!("Promoted {} to {}.", candidate_path.display(), cli.incumbent.display());
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,1680 @@
+use azul_ai::{
+    heuristic_ai::HeuristicAI,
+    mcts_heuristic_ai::MctsHeuristicAI,
+    mcts_lib::MctsConfig,
+    mcts_nn_ai::{legal_policy_mask, MctsNnAI},
+    nn::{NeuralNetwork, WEIGHTS_EXTENSION},
+    registry::{AgentRegistry, AgentSpec, DEFAULT_MCTS_HEURISTIC_ITERATIONS, DEFAULT_MCTS_NN_ITERATIONS},
+    training_io::{TrainingDataWriter, SAMPLES_EXTENSION},
+    rank_outcome, score_margin, AIAgent, AgentConfig, LabeledPosition, TrainingData
+};
+use azul_cli::agent_file::expand_agent_files;
+use azul_cli::cli_human_agent::{play_interactive_game, CliHumanAgent};
+use azul_cli::confidence::{parse_margin, StoppingRule};
+use azul_cli::external_agent::register_external_agent;
+use azul_cli::game_log::{read_game_logs, GameLog, GameLogWriter, GameRound, GameTurn};
+use azul_cli::self_play_http::Client;
+use azul_cli::sprt::{Decision, Sprt};
+use azul_cli::watch::watch_game;
+use azul_cli::tournament::{round_robin_pairings, Crosstable, GameResult, Swiss};
+use azul_core::{GamePhase, GameState, Move, Ranking, ScoringEvent, TileBagSummary, TurnState, Winner};
+use chrono::prelude::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use clap::{Parser, ValueEnum};
+use rand::seq::SliceRandom;
+use rand::distributions::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use rayon::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    /// Agent specs (e.g. mctsheuristic:1000), or agents files (e.g. agents.toml) that list
+    /// agents as tables.
+    #[arg(short, long, num_args = 1.., value_delimiter = ' ', required = true)]
+    players: Vec<String>,
+    #[arg(short, long, default_value_t = 100)]
+    games: u32,
+    #[arg(long)]
+    self_play: bool,
+    #[arg(long, default_value_t = 2)]
+    self_play_players: usize,
+    /// Probability that each self-play seat (other than seat 0) is taken by an opponent from the pool.
+    #[arg(long, default_value_t = 0.0)]
+    pool_rate: f64,
+    /// How many earlier model versions from training_models/ to include in the opponent pool.
+    #[arg(long, default_value_t = 5)]
+    pool_models: usize,
+    /// Extra agent specs to include in the opponent pool (e.g. heuristicai mctsheuristic:500).
+    #[arg(long, num_args = 1.., value_delimiter = ' ')]
+    pool_agents: Vec<String>,
+    /// How pool opponents are picked: uniformly, or prioritized toward the ones the learner
+    /// scores worst against so far in the run.
+    #[arg(long, value_enum, default_value_t = PoolSampling::Uniform)]
+    pool_sampling: PoolSampling,
+    /// Fraction of self-play games that start from a sampled mid-game position instead of the opening.
+    #[arg(long, default_value_t = 0.0)]
+    curriculum_rate: f64,
+    /// A game log (game_logs.jsonl, .jsonl.gz, or an older game_logs.json) from an earlier
+    /// simulation run to sample curriculum positions from.
+    /// Without it, curriculum positions are generated by fast warm-up play.
+    #[arg(long)]
+    curriculum_logs: Option<String>,
+    /// End a self-play game early once an mctsnn player's search value stays below this
+    /// (in [-1, 1]) for --resign-moves of its moves in a row. The player who resigns places
+    /// last. Off unless given.
+    #[arg(long)]
+    resign_threshold: Option<f32>,
+    /// Consecutive low-value moves before a player resigns.
+    #[arg(long, default_value_t = 3)]
+    resign_moves: usize,
+    /// Fraction of self-play games that never resign, to measure how often resigning would
+    /// have been wrong.
+    #[arg(long, default_value_t = 0.1)]
+    resign_playthrough: f64,
+    /// Run as a distributed self-play worker for the coordinator at this URL
+    /// (e.g. http://192.168.1.10:8000): fetch its model, play --games self-play games,
+    /// upload the samples, and repeat.
+    #[arg(long)]
+    worker: Option<String>,
+    /// Stop a worker after this many batches of games instead of running until stopped.
+    #[arg(long)]
+    worker_batches: Option<u32>,
+    /// Label `--games` sampled positions with a deep search by the first `--players` agent
+    /// (e.g. mctsheuristic:20000) instead of playing games.
+    #[arg(long)]
+    label_positions: bool,
+    /// Play one interactive game with the `--players` seats; use `human` for a terminal player.
+    #[arg(long)]
+    play: bool,
+    /// Play one game between the `--players` agents and print every position, move and
+    /// scoring event in color, instead of a simulation.
+    #[arg(long)]
+    watch: bool,
+    /// Milliseconds to pause after each move in `--watch` mode.
+    #[arg(long, default_value_t = 0)]
+    watch_delay: u64,
+    /// Play a 2-player tournament between the `--players` agents instead of a simulation.
+    /// round-robin plays every pairing, in both seat orders, `--games` times each; swiss
+    /// plays `--rounds` rounds of `--games`-game matches between entrants with similar scores.
+    #[arg(long, value_enum)]
+    tournament: Option<TournamentFormat>,
+    /// Rounds of a Swiss tournament. Defaults to log2 of the number of agents, rounded up.
+    #[arg(long)]
+    rounds: Option<u32>,
+    /// Test whether the first of two `--players` agents is stronger than the second with a
+    /// sequential probability ratio test, given as key=value settings: elo0 and elo1 (the
+    /// Elo differences of H0 and H1) and optionally alpha and beta (default 0.05). Stops at a
+    /// decision or after `--games` games.
+    #[arg(long, num_args = 1.., value_delimiter = ' ')]
+    sprt: Vec<String>,
+    /// Measure engine throughput instead of running a simulation: legal move generation,
+    /// apply_move and MCTS iterations per second, and each `--players` agent's rollouts
+    /// (games played to the end with the agent in every seat) per second. Runs on one thread.
+    #[arg(long)]
+    bench: bool,
+    /// Seconds spent on each `--bench` measurement.
+    #[arg(long, default_value_t = 2.0)]
+    bench_seconds: f64,
+    /// Play games in mirrored sets that share a seeded tile sequence, with the seats rotated
+    /// so every agent takes every seat once (swapped, in a 2-player game). Results then
+    /// compare skill rather than tile luck. Applies to simulations, tournaments and the SPRT.
+    #[arg(long)]
+    mirror: bool,
+    /// Seed the tiles of every game, and the random choices of its agents, so that the run
+    /// can be repeated exactly: game k uses seed + k (with --mirror, mirrored set k does, and
+    /// the seed defaults to 0). Applies to simulations, tournaments and the SPRT.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Stop a simulation early, once every agent's win rate is known to within `--margin` at
+    /// this confidence level (e.g. 0.95), instead of always playing `--games` games.
+    #[arg(long)]
+    confidence: Option<f64>,
+    /// The win-rate margin for `--confidence`, as a percentage (2%) or a fraction (0.02).
+    #[arg(long, value_parser = parse_margin, default_value = "2%")]
+    margin: f64,
+    /// Gzip the simulation's game log (game_logs.jsonl.gz).
+    #[arg(long)]
+    gzip_logs: bool,
+    /// Don't save the simulation's game log, only its summary.
+    #[arg(long)]
+    no_logs: bool,
+    /// How the simulation's summary is printed: text, or csv with a row per agent (also saved
+    /// as summary.csv).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Worker threads for playing games in parallel. Defaults to one per core. Agents that
+    /// search in parallel share these threads rather than starting their own.
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum TournamentFormat {
+    RoundRobin,
+    Swiss,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Csv,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum PoolSampling {
+    Uniform,
+    Prioritized,
+}
+
+/// One agent's totals over the games it played, in any seat.
+#[derive(Serialize, Default, Clone, Copy)]
+struct AgentStats {
+    games: u32,
+    wins: u32,
+    total_score: u64,
+    /// The sum of the squared final scores, for the standard deviation.
+    total_score_squared: u64,
+    /// Points lost to the floor line.
+    total_floor_penalty: u64,
+    /// Rounds in the games it played.
+    total_rounds: u64,
+    /// End-of-game bonuses: complete rows, columns and colors.
+    row_bonuses: u64,
+    column_bonuses: u64,
+    color_bonuses: u64,
+    moves: u64,
+    /// Time spent choosing moves.
+    total_think_seconds: f64,
+}
+
+impl AgentStats {
+    /// `total` per game.
+    fn average(&self, total: u64) -> f64 {
+        total as f64 / self.games.max(1) as f64
+    }
+
+    fn win_rate(&self) -> f64 {
+        self.average(self.wins as u64)
+    }
+
+    /// The standard deviation of the final score.
+    fn score_std_dev(&self) -> f64 {
+        let mean = self.average(self.total_score);
+        (self.average(self.total_score_squared) - mean * mean).max(0.0).sqrt()
+    }
+
+    fn floor_penalty_per_round(&self) -> f64 {
+        self.total_floor_penalty as f64 / self.total_rounds.max(1) as f64
+    }
+
+    fn think_seconds_per_move(&self) -> f64 {
+        self.total_think_seconds / self.moves.max(1) as f64
+    }
+}
+
+/// `names` without repeats, in order.
+fn distinct(names: &[String]) -> impl Iterator<Item = &String> {
+    names.iter().enumerate().filter(|&(i, name)| !names[..i].contains(name)).map(|(_, name)| name)
+}
+
+#[derive(Serialize)]
+struct GameStats {
+    agent_wins: HashMap<String, u32>,
+    agents: HashMap<String, AgentStats>,
+    total_games: u32,
+    ties: u32,
+    /// Games that ended when an agent gave no legal move.
+    forfeits: u32,
+    simulation_time_seconds: f64,
+}
+
+impl GameStats {
+    fn new() -> Self {
+        Self {
+            agent_wins: HashMap::new(),
+            agents: HashMap::new(),
+            total_games: 0,
+            ties: 0,
+            forfeits: 0,
+            simulation_time_seconds: 0.0,
+        }
+    }
+
+    fn record_game(&mut self, final_state: &GameState, game_log: &GameLog) {
+        let agent_names = &game_log.matchup;
+        self.total_games += 1;
+        // A forfeited game is lost by the seat that forfeited, whatever the scores say.
+        let winner = match game_log.forfeited_by {
+            Some(seat) => {
+                self.forfeits += 1;
+                let others: Vec<usize> = (0..agent_names.len()).filter(|&p| p != seat).collect();
+                if let [winner_idx] = others[..] { Winner::Single(winner_idx) } else { Winner::Shared(others) }
+            }
+            None => final_state.winner(),
+        };
+        match winner {
+            Winner::Single(winner_idx) => {
+                let winner_name = &agent_names[winner_idx];
+                *self.agent_wins.entry(winner_name.clone()).or_insert(0) += 1;
+                self.agents.entry(winner_name.clone()).or_default().wins += 1;
+            }
+            Winner::Shared(_) => self.ties += 1,
+        }
+        for (seat, name) in agent_names.iter().enumerate() {
+            let agent = self.agents.entry(name.clone()).or_default();
+            let score = final_state.players[seat].score as u64;
+            agent.games += 1;
+            agent.total_score += score;
+            agent.total_score_squared += score * score;
+            agent.total_rounds += game_log.history.len() as u64;
+        }
+        for turn in game_log.history.iter().flat_map(|round| &round.turns) {
+            let agent = self.agents.entry(agent_names[turn.player_index].clone()).or_default();
+            agent.moves += 1;
+            agent.total_think_seconds += turn.think_seconds.unwrap_or(0.0);
+        }
+        for event in game_log.history.iter().flat_map(|round| &round.scoring_events) {
+            match event {
+                ScoringEvent::FloorPenalty { player, penalty, .. } => {
+                    self.agents.entry(agent_names[*player].clone()).or_default().total_floor_penalty += *penalty as u64;
+                }
+                ScoringEvent::EndGameBonus { player, bonuses, .. } => {
+                    let agent = self.agents.entry(agent_names[*player].clone()).or_default();
+                    agent.row_bonuses += bonuses.rows as u64;
+                    agent.column_bonuses += bonuses.columns as u64;
+                    agent.color_bonuses += bonuses.colors as u64;
+                }
+                ScoringEvent::TilePlaced { .. } => {}
+            }
+        }
+    }
+
+    /// A CSV header and a row per agent in `agent_names`, in that order.
+    fn to_csv(&self, agent_names: &[String]) -> String {
+        let mut csv = "agent,games,wins,win_rate,avg_score,score_std_dev,avg_floor_penalty,floor_penalty_per_round,\
+                       avg_rounds,avg_row_bonuses,avg_column_bonuses,avg_color_bonuses,seconds_per_move\n".to_string();
+        for name in distinct(agent_names) {
+            let agent = self.agents.get(name).copied().unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{:.4},{:.2},{:.2},{:.2},{:.3},{:.2},{:.3},{:.3},{:.3},{:.5}\n",
+                csv_field(name), agent.games, agent.wins, agent.win_rate(),
+                agent.average(agent.total_score), agent.score_std_dev(),
+                agent.average(agent.total_floor_penalty), agent.floor_penalty_per_round(),
+                agent.average(agent.total_rounds), agent.average(agent.row_bonuses),
+                agent.average(agent.column_bonuses), agent.average(agent.color_bonuses), agent.think_seconds_per_move()
+            ));
+        }
+        csv
+    }
+
+    /// Prints the totals, then each agent in `agent_names` in that order.
+    fn print_summary(&self, agent_names: &[String]) {
+        println!("\n--- Simulation Complete ---");
+        println!("Total Games: {}", self.total_games);
+        println!("Total Time: {:.2} seconds", self.simulation_time_seconds);
+        println!("Ties: {}", self.ties);
+        if self.forfeits > 0 {
+            println!("Forfeits: {}", self.forfeits);
+        }
+        println!("Wins by Agent:");
+        for name in distinct(agent_names) {
+            let agent = self.agents.get(name).copied().unwrap_or_default();
+            let win_rate = (agent.wins as f64 / self.total_games as f64) * 100.0;
+            println!("  - {}: {} ({:.2}%)", name, agent.wins, win_rate);
+        }
+        println!("Per Agent (per game unless noted):");
+        for name in distinct(agent_names) {
+            let agent = self.agents.get(name).copied().unwrap_or_default();
+            println!("  - {}:", name);
+            println!(
+                "      score {:.1} (std dev {:.1}), floor penalty {:.1} ({:.2} per round), {:.1} rounds",
+                agent.average(agent.total_score), agent.score_std_dev(),
+                agent.average(agent.total_floor_penalty), agent.floor_penalty_per_round(), agent.average(agent.total_rounds)
+            );
+            println!(
+                "      bonuses: {:.2} rows, {:.2} columns, {:.2} colors; {:.4}s per move",
+                agent.average(agent.row_bonuses), agent.average(agent.column_bonuses), agent.average(agent.color_bonuses),
+                agent.think_seconds_per_move()
+            );
+        }
+    }
+}
+
+/// `field` as a CSV field, quoted if it needs to be.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// A progress bar for a run of `games` games, with the rate, the time left, and a message.
+/// It draws to stderr, and only when that is a terminal.
+fn games_progress_bar(games: u32) -> ProgressBar {
+    let style = ProgressStyle::with_template("{bar:40} {pos}/{len} games  {per_sec}  elapsed {elapsed}, ETA {eta}  {msg}")
+        .expect("the progress bar template is valid");
+    ProgressBar::new(games as u64).with_style(style)
+}
+
+fn create_agent(name: &str) -> Box<dyn AIAgent> {
+    let mut registry = AgentRegistry::new();
+    registry.register("human", |spec| {
+        spec.expect_no_options()?;
+        Ok(Box::new(CliHumanAgent::new()))
+    });
+    register_external_agent(&mut registry);
+    registry.create_from_str(name).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn main() -> std::io::Result<()> {
+    let mut cli = Cli::parse();
+    match (expand_agent_files(&cli.players), expand_agent_files(&cli.pool_agents)) {
+        (Ok(players), Ok(pool_agents)) => (cli.players, cli.pool_agents) = (players, pool_agents),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {}", e);
+            return Ok(());
+        }
+    }
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new().num_threads(threads.get()).build_global().map_err(std::io::Error::other)?;
+    }
+    if let Some(url) = cli.worker.clone() {
+        run_worker(cli, &url)?;
+    } else if cli.self_play {
+        run_self_play(cli)?;
+    } else if cli.label_positions {
+        run_label_positions(cli)?;
+    } else if cli.play {
+        run_play(cli);
+    } else if cli.watch {
+        run_watch(cli);
+    } else if cli.bench {
+        run_bench(cli)?;
+    } else if !cli.sprt.is_empty() {
+        run_sprt(cli)?;
+    } else if let Some(format) = cli.tournament {
+        run_tournament(cli, format)?;
+    } else {
+        run_simulations(cli)?;
+    }
+    Ok(())
+}
+
+/// Returns every `azul_model_vN.weights` in `dir`, sorted from newest to oldest version.
+fn list_model_versions(dir: &str) -> std::io::Result<Vec<(u32, PathBuf)>> {
+    let mut versions: Vec<(u32, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == WEIGHTS_EXTENSION))
+        .filter_map(|path| {
+            let version = path.file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|stem| stem.strip_prefix("azul_model_v"))
+                .and_then(|v| v.parse::<u32>().ok())?;
+            Some((version, path))
+        })
+        .collect();
+    versions.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    Ok(versions)
+}
+
+/// The self-play opponent pool: picks an opponent for each pool seat and keeps the learner's
+/// results against each one.
+struct OpponentPool {
+    entries: Vec<PoolEntry>,
+    sampling: PoolSampling,
+}
+
+struct PoolEntry {
+    spec: String,
+    games: u32,
+    /// The learner's points against this opponent: 1 per game it placed ahead, 0.5 per tie.
+    learner_score: f64,
+}
+
+impl PoolEntry {
+    /// The learner's score rate, starting from one imagined drawn game so that a new
+    /// opponent isn't judged on a single result.
+    fn learner_rate(&self) -> f64 {
+        (self.learner_score + 0.5) / (self.games as f64 + 1.0)
+    }
+}
+
+impl OpponentPool {
+    fn new(specs: Vec<String>, sampling: PoolSampling) -> Self {
+        let entries = specs.into_iter().map(|spec| PoolEntry { spec, games: 0, learner_score: 0.0 }).collect();
+        Self { entries, sampling }
+    }
+
+    /// The index of the next opponent. Prioritized sampling weighs each opponent by the
+    /// square of the learner's loss rate against it.
+    fn choose(&self, rng: &mut impl Rng) -> usize {
+        match self.sampling {
+            PoolSampling::Uniform => rng.gen_range(0..self.entries.len()),
+            PoolSampling::Prioritized => {
+                let weights = self.entries.iter().map(|entry| (1.0 - entry.learner_rate()).powi(2));
+                rng.sample(WeightedIndex::new(weights).expect("the pool is not empty"))
+            }
+        }
+    }
+
+    fn spec(&self, idx: usize) -> &str {
+        &self.entries[idx].spec
+    }
+
+    /// Records a finished game between the learner and pool opponent `idx`, given their ranks.
+    fn record(&mut self, idx: usize, learner_rank: usize, opponent_rank: usize) {
+        let entry = &mut self.entries[idx];
+        entry.games += 1;
+        entry.learner_score += match learner_rank.cmp(&opponent_rank) {
+            std::cmp::Ordering::Less => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Greater => 0.0,
+        };
+    }
+
+    fn print_summary(&self) {
+        println!("Learner results against the opponent pool:");
+        for entry in &self.entries {
+            println!(
+                "  - {}: {:.1}/{} ({:.1}%)",
+                entry.spec, entry.learner_score, entry.games,
+                100.0 * entry.learner_score / entry.games.max(1) as f64
+            );
+        }
+    }
+}
+
+fn check_self_play_options(cli: &Cli) -> Result<(), String> {
+    if !(2..=4).contains(&cli.self_play_players) {
+        return Err("Self-play player count must be between 2 and 4.".to_string());
+    }
+    if !(0.0..=1.0).contains(&cli.pool_rate) {
+        return Err("--pool-rate must be between 0.0 and 1.0.".to_string());
+    }
+    if !(0.0..=1.0).contains(&cli.curriculum_rate) {
+        return Err("--curriculum-rate must be between 0.0 and 1.0.".to_string());
+    }
+    if !(0.0..=1.0).contains(&cli.resign_playthrough) {
+        return Err("--resign-playthrough must be between 0.0 and 1.0.".to_string());
+    }
+    Ok(())
+}
+
+fn run_self_play(cli: Cli) -> std::io::Result<()> {
+    let mut agent_config = cli.players[0].clone();
+    if let Err(e) = check_self_play_options(&cli) {
+        eprintln!("Error: {}", e);
+        return Ok(());
+    }
+
+    // --- Auto-find latest model for self-play ---
+    let parts: Vec<String> = agent_config.split(':').map(String::from).collect();
+    let is_nn_agent = parts[0].to_lowercase() == "mctsnn";
+    let mut learner_model: Option<PathBuf> = None;
+    if is_nn_agent && parts.len() < 3 {
+        let training_models_dir = "training_models";
+        fs::create_dir_all(training_models_dir)?;
+        let latest_model = fs::read_dir(training_models_dir)?
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == WEIGHTS_EXTENSION))
+            .max_by_key(|entry| entry.metadata().unwrap().created().unwrap());
+
+        if let Some(entry) = latest_model {
+            let path_str = entry.path().to_string_lossy().to_string();
+            println!("Found latest model for self-play: {}", path_str);
+            learner_model = Some(entry.path());
+            // Append the path to the agent config string
+            agent_config = format!("{}:{}", agent_config, path_str);
+        } else {
+            println!("No existing model found. Starting self-play with a random brain.");
+        }
+    } else if is_nn_agent {
+        learner_model = Some(PathBuf::from(&parts[2]));
+    }
+
+    fs::create_dir_all("training_data")?;
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let data_path = format!("training_data/data_{}.{}", timestamp, SAMPLES_EXTENSION);
+    let file = BufWriter::new(fs::File::create(&data_path)?);
+    play_self_play_games(&cli, &agent_config, learner_model.as_ref(), "training_models", file)?;
+    println!("Done. Data saved to '{}'", data_path);
+    Ok(())
+}
+
+/// Plays `--games` self-play games with `agent_config` as the learner and writes their samples
+/// to `out`, which it hands back at the end. `learner_model` is the learner's weights file, and
+/// the opponent pool draws earlier model versions from `models_dir`.
+fn play_self_play_games<W: Write + Send>(
+    cli: &Cli,
+    agent_config: &str,
+    learner_model: Option<&PathBuf>,
+    models_dir: &str,
+    out: W,
+) -> std::io::Result<W> {
+    let num_games = cli.games;
+    let num_players = cli.self_play_players;
+    let resign_rule = cli.resign_threshold.map(|threshold| ResignRule { threshold, moves: cli.resign_moves.max(1) });
+    let parts: Vec<&str> = agent_config.split(':').collect();
+    let is_nn_agent = parts[0].eq_ignore_ascii_case("mctsnn");
+
+    // --- Opponent pool: earlier model versions plus any explicitly listed agents ---
+    let mut opponent_pool: Vec<String> = cli.pool_agents.clone();
+    if is_nn_agent && cli.pool_models > 0 {
+        let iterations = parts.get(1).copied().unwrap_or("800");
+        let earlier_models = list_model_versions(models_dir)?
+            .into_iter()
+            .filter(|(_, path)| Some(path) != learner_model)
+            .take(cli.pool_models);
+        for (_, path) in earlier_models {
+            opponent_pool.push(format!("mctsnn:{}:{}", iterations, path.to_string_lossy()));
+        }
+    }
+    let pool_rate = if opponent_pool.is_empty() { 0.0 } else { cli.pool_rate };
+    if pool_rate > 0.0 {
+        println!(
+            "Opponent pool ({} seat rate {:.0}%, {:?} sampling):",
+            opponent_pool.len(), pool_rate * 100.0, cli.pool_sampling
+        );
+        for spec in &opponent_pool {
+            println!("  - {}", spec);
+        }
+    } else if cli.pool_rate > 0.0 {
+        println!("Opponent pool is empty. Falling back to pure self-play.");
+    }
+
+    // --- Curriculum: logged positions to start from, if a log file was given ---
+    let logged_positions: Vec<TurnState> = match &cli.curriculum_logs {
+        Some(path) if cli.curriculum_rate > 0.0 => load_logged_positions(path, num_players)?,
+        _ => Vec::new(),
+    };
+    if cli.curriculum_rate > 0.0 {
+        if logged_positions.is_empty() {
+            println!("Curriculum: {:.0}% of games start from generated mid-game positions.", cli.curriculum_rate * 100.0);
+        } else {
+            println!("Curriculum: {:.0}% of games start from {} logged positions.", cli.curriculum_rate * 100.0, logged_positions.len());
+        }
+    }
+
+    // Samples are written as each game finishes, so memory doesn't grow with the run.
+    let writer = Mutex::new(TrainingDataWriter::new(out)?);
+
+    println!("Running {} {}-player self-play games to generate training data...", num_games, num_players);
+    if let Some(rule) = &resign_rule {
+        println!(
+            "Resignation: below {} for {} moves in a row; {:.0}% of games play through.",
+            rule.threshold, rule.moves, cli.resign_playthrough * 100.0
+        );
+    }
+    let resign_stats = Mutex::new(ResignStats::default());
+    let opponent_pool = Mutex::new(OpponentPool::new(opponent_pool, cli.pool_sampling));
+    let start_time = Instant::now();
+    let progress = games_progress_bar(num_games);
+
+    (0..num_games)
+        .into_par_iter()
+        // Each worker keeps its agents between games, keyed by seat and spec, and resets them
+        // instead of building (and, for mctsnn, loading) new ones every game.
+        .try_for_each_init(HashMap::new, |agent_cache: &mut HashMap<(usize, String), Box<dyn AIAgent>>, _| {
+            let mut rng = thread_rng();
+            let start_state = if rng.gen_bool(cli.curriculum_rate) {
+                match logged_positions.choose(&mut rng) {
+                    Some(turn_state) => GameState::from_turn_state(turn_state),
+                    None => generate_curriculum_position(num_players, &mut rng),
+                }
+            } else {
+                GameState::new(num_players)
+            };
+            // Seat 0 is always the learner so every game produces training data. Pool seats
+            // carry their opponent's index in the pool.
+            let seat_configs: Vec<(String, Option<usize>)> = (0..num_players)
+                .map(|seat| {
+                    if seat > 0 && rng.gen_bool(pool_rate) {
+                        let pool = opponent_pool.lock().expect("a self-play worker panicked");
+                        let idx = pool.choose(&mut rng);
+                        (pool.spec(idx).to_string(), Some(idx))
+                    } else {
+                        (agent_config.to_string(), None)
+                    }
+                })
+                .collect();
+            let mut agents: Vec<Box<dyn AIAgent>> = seat_configs.iter().enumerate()
+                .map(|(seat, (config, _))| {
+                    let mut agent = agent_cache.remove(&(seat, config.clone()))
+                        .unwrap_or_else(|| create_agent(config));
+                    agent.reset();
+                    agent
+                })
+                .collect();
+            let learner_seats: Vec<bool> = seat_configs.iter().map(|(_, pool_idx)| pool_idx.is_none()).collect();
+            let play_through = rng.gen_bool(cli.resign_playthrough);
+            let game = run_one_self_play_game(start_state, &mut agents, &learner_seats, resign_rule.as_ref(), play_through);
+            if let Some(resignation) = &game.resignation {
+                resign_stats.lock().expect("a self-play worker panicked").record(resignation);
+            }
+            let rank_of = |seat: usize| game.rankings.iter().find(|r| r.player == seat).map_or(usize::MAX, |r| r.rank);
+            for (seat, (_, pool_idx)) in seat_configs.iter().enumerate() {
+                if let Some(idx) = pool_idx {
+                    opponent_pool.lock().expect("a self-play worker panicked").record(*idx, rank_of(0), rank_of(seat));
+                }
+            }
+            for ((seat, (config, _)), agent) in seat_configs.into_iter().enumerate().zip(agents) {
+                agent_cache.insert((seat, config), agent);
+            }
+            let mut writer = writer.lock().expect("a self-play worker panicked");
+            game.samples.iter().try_for_each(|sample| writer.write(sample))?;
+            progress.set_message(format!("{} samples", writer.count()));
+            progress.inc(1);
+            Ok::<(), std::io::Error>(())
+        })?;
+    progress.finish_and_clear();
+
+    let writer = writer.into_inner().expect("a self-play worker panicked");
+    let sample_count = writer.count();
+    let out = writer.finish()?;
+    let duration = start_time.elapsed();
+    println!("\n--- Self-Play Complete ---");
+    println!("Generated {} training samples in {:.2} seconds.", sample_count, duration.as_secs_f64());
+    if resign_rule.is_some() {
+        resign_stats.into_inner().expect("a self-play worker panicked").print_summary();
+    }
+    if pool_rate > 0.0 {
+        opponent_pool.into_inner().expect("a self-play worker panicked").print_summary();
+    }
+    Ok(out)
+}
+
+// Where a worker keeps the models it fetches; they also make up its opponent pool.
+const WORKER_MODELS_DIR: &str = "worker_models";
+const WORKER_RETRIES: u32 = 6;
+const WORKER_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Runs `attempt` until it succeeds, up to `WORKER_RETRIES` times, so that a coordinator
+/// restart doesn't stop the worker.
+fn with_retries<T>(what: &str, mut attempt: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    let mut failures = 0;
+    loop {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(e) if failures + 1 < WORKER_RETRIES => {
+                failures += 1;
+                eprintln!("{} failed ({}); retrying in {}s.", what, e, WORKER_RETRY_DELAY.as_secs());
+                std::thread::sleep(WORKER_RETRY_DELAY);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Distributed self-play: repeatedly fetches the coordinator's current model, plays `--games`
+/// self-play games with it, and uploads the samples, until `--worker-batches` batches are done
+/// (or forever).
+fn run_worker(cli: Cli, url: &str) -> std::io::Result<()> {
+    if let Err(e) = check_self_play_options(&cli) {
+        eprintln!("Error: {}", e);
+        return Ok(());
+    }
+    let learner = cli.players[0].clone();
+    if !learner.split(':').next().is_some_and(|name| name.eq_ignore_ascii_case("mctsnn")) || learner.split(':').count() > 2 {
+        eprintln!("Error: A worker's player must be mctsnn or mctsnn:<iterations>; the model comes from the coordinator.");
+        return Ok(());
+    }
+    let client = match Client::new(url) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(());
+        }
+    };
+    fs::create_dir_all(WORKER_MODELS_DIR)?;
+
+    let mut batch = 0;
+    while cli.worker_batches.is_none_or(|batches| batch < batches) {
+        batch += 1;
+        let (version, agent_config, learner_model) = match with_retries("Fetching the model", || client.fetch_model())? {
+            Some((version, bytes)) => {
+                if let Err(e) = NeuralNetwork::from_bytes(&bytes) {
+                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("The coordinator's model v{}: {}", version, e)));
+                }
+                let path = PathBuf::from(WORKER_MODELS_DIR).join(format!("azul_model_v{}.{}", version, WEIGHTS_EXTENSION));
+                if !path.exists() {
+                    fs::write(&path, &bytes)?;
+                }
+                let agent_config = format!("{}:{}", learner, path.to_string_lossy());
+                (version, agent_config, Some(path))
+            }
+            None => (0, learner.clone(), None),
+        };
+        if version == 0 {
+            println!("Batch {}: the coordinator has no model yet; playing with a random brain.", batch);
+        } else {
+            println!("Batch {}: playing with model v{}.", batch, version);
+        }
+        let samples = play_self_play_games(&cli, &agent_config, learner_model.as_ref(), WORKER_MODELS_DIR, Vec::new())?;
+        with_retries("Uploading samples", || client.upload_samples(version, &samples))?;
+        println!("Uploaded {} bytes of samples to {}.", samples.len(), url);
+    }
+    Ok(())
+}
+
+/// Loads every recorded pre-move position with the given player count from a game log file.
+fn load_logged_positions(path: &str, num_players: usize) -> std::io::Result<Vec<TurnState>> {
+    let mut positions = Vec::new();
+    for log in read_game_logs(Path::new(path))? {
+        let log = log?;
+        if log.matchup.len() == num_players {
+            positions.extend(log.history.into_iter().flat_map(|round| round.turns).map(|turn| turn.state_before_move));
+        }
+    }
+    Ok(positions)
+}
+
+/// Produces a mid-game or endgame position by playing a random number of rounds with a
+/// fast, slightly noisy heuristic player, then stopping at a random point in the drafting phase.
+fn generate_curriculum_position(num_players: usize, rng: &mut impl Rng) -> GameState {
+    const RANDOM_MOVE_RATE: f64 = 0.25;
+    loop {
+        let mut game = GameState::new(num_players);
+        let mut warm_up_agent = HeuristicAI::new();
+        let rounds_to_play = rng.gen_range(1..=5);
+        let mut rounds_played = 0;
+
+        while rounds_played < rounds_to_play && game.phase() != GamePhase::GameOver {
+            if game.phase() == GamePhase::Tiling {
+                game.advance(None).expect("the drafting phase is over");
+                rounds_played += 1;
+                continue;
+            }
+            let legal_moves = game.get_legal_moves();
+            let next_move = if rng.gen_bool(RANDOM_MOVE_RATE) {
+                legal_moves.choose(rng).cloned()
+            } else {
+                warm_up_agent.get_move(&game)
+            };
+            match next_move {
+                Some(m) => { game.advance(Some(&m)).expect("warm-up moves are legal"); }
+                None => break,
+            }
+        }
+        if game.phase() != GamePhase::Drafting {
+            // The warm-up finished the game; try again rather than return a terminal position.
+            continue;
+        }
+
+        // Stop somewhere inside the next drafting phase, so tiling decisions are covered too.
+        let extra_moves = rng.gen_range(0..num_players * 3);
+        for _ in 0..extra_moves {
+            let legal_moves = game.get_legal_moves();
+            if legal_moves.len() <= 1 { break; }
+            let m = legal_moves.choose(rng).unwrap().clone();
+            game.apply_move(&m);
+            if game.phase() != GamePhase::Drafting { break; }
+        }
+        if game.phase() == GamePhase::Drafting {
+            return game;
+        }
+    }
+}
+
+/// When a self-play player gives up: after `moves` moves in a row with a search value below
+/// `threshold`.
+struct ResignRule {
+    threshold: f32,
+    moves: usize,
+}
+
+/// A resignation in a self-play game, or one that would have happened in a play-through game.
+struct Resignation {
+    played_through: bool,
+    /// In a play-through game, whether the player would have been wrong to resign: it didn't
+    /// finish last after all.
+    false_positive: bool,
+}
+
+#[derive(Default)]
+struct ResignStats {
+    resigned: u32,
+    played_through: u32,
+    false_positives: u32,
+}
+
+impl ResignStats {
+    fn record(&mut self, resignation: &Resignation) {
+        if resignation.played_through {
+            self.played_through += 1;
+            if resignation.false_positive {
+                self.false_positives += 1;
+            }
+        } else {
+            self.resigned += 1;
+        }
+    }
+
+    fn print_summary(&self) {
+        println!("Games ended by resignation: {}", self.resigned);
+        if self.played_through > 0 {
+            println!(
+                "Play-through games that would have resigned: {}, of which the player didn't finish last: {} ({:.1}%)",
+                self.played_through, self.false_positives,
+                100.0 * self.false_positives as f64 / self.played_through as f64
+            );
+        }
+    }
+}
+
+/// The rankings of a game that `resigner` gave up: it places last, and the others keep their
+/// order by the current scores.
+fn resigned_rankings(game: &GameState, resigner: usize) -> Vec<Ranking> {
+    let mut rankings: Vec<Ranking> = game.rankings().into_iter().filter(|r| r.player != resigner).collect();
+    for idx in 0..rankings.len() {
+        let tied_with_previous = idx > 0
+            && (rankings[idx].score, rankings[idx].complete_rows) == (rankings[idx - 1].score, rankings[idx - 1].complete_rows);
+        rankings[idx].rank = if tied_with_previous { rankings[idx - 1].rank } else { idx + 1 };
+    }
+    let board = &game.players[resigner];
+    rankings.push(Ranking { player: resigner, rank: game.players.len(), score: board.score, complete_rows: board.count_complete_rows() });
+    rankings
+}
+
+/// A finished self-play game.
+struct SelfPlayGame {
+    samples: Vec<TrainingData>,
+    rankings: Vec<Ranking>,
+    resignation: Option<Resignation>,
+}
+
+/// Plays one game from `start_state` and records training samples for the seats flagged in
+/// `learner_seats`. Pool opponents still play normally, but their positions are not recorded.
+/// With a `resign_rule`, an mctsnn player can end the game early by resigning, unless
+/// `play_through` is set; then the game is finished anyway and the would-be resignation is
+/// only reported.
+fn run_one_self_play_game(
+    start_state: GameState,
+    agents: &mut [Box<dyn AIAgent>],
+    learner_seats: &[bool],
+    resign_rule: Option<&ResignRule>,
+    play_through: bool,
+) -> SelfPlayGame {
+    let mut game = start_state;
+    // Each sample with the seat it was recorded for; outcomes and margins are filled in at the end.
+    let mut history: Vec<(TrainingData, usize)> = Vec::new();
+    let mut low_value_moves = vec![0; agents.len()];
+    let mut resigner: Option<usize> = None;
+
+    loop {
+        match game.phase() {
+            GamePhase::Drafting => {}
+            GamePhase::Tiling => {
+                game.advance(None).expect("the drafting phase is over");
+                continue;
+            }
+            GamePhase::GameOver => break,
+        }
+        let player_idx = game.current_player_idx;
+        let agent = &mut agents[player_idx];
+        let state_input_opt = agent.as_any().downcast_ref::<MctsNnAI>().and_then(|a| a.state_to_input(&game));
+
+        let Some(the_move) = agent.get_move(&game) else { break };
+        if learner_seats[player_idx] {
+            if let Some(mcts_agent) = agent.as_any().downcast_ref::<MctsNnAI>() {
+                if let (Some(state_input), Some(mcts_policy)) = (state_input_opt, mcts_agent.get_mcts_policy()) {
+                    let legal_mask = legal_policy_mask(&game);
+                    history.push((TrainingData { state_input, mcts_policy, legal_mask, outcome: 0.0, score_margin: None }, player_idx));
+                }
+            }
+        }
+        if let Some(rule) = resign_rule.filter(|_| resigner.is_none()) {
+            let value = agent.as_any().downcast_ref::<MctsNnAI>().and_then(|a| a.root_value());
+            match value {
+                Some(value) if value < rule.threshold => low_value_moves[player_idx] += 1,
+                _ => low_value_moves[player_idx] = 0,
+            }
+            if low_value_moves[player_idx] >= rule.moves {
+                resigner = Some(player_idx);
+                if !play_through {
+                    break;
+                }
+            }
+        }
+        if game.advance(Some(&the_move)).is_err() { break; }
+    }
+
+    let (rankings, resignation) = match resigner {
+        Some(player) if !play_through => (resigned_rankings(&game, player), Some(Resignation { played_through: false, false_positive: false })),
+        Some(player) => {
+            let rankings = game.rankings();
+            let false_positive = rank_outcome(&rankings, player) > -1.0;
+            (rankings, Some(Resignation { played_through: true, false_positive }))
+        }
+        None => (game.rankings(), None),
+    };
+    // A resigned game's scores aren't final, so its samples get no score margin.
+    let finished = resignation.as_ref().is_none_or(|r| r.played_through);
+    let samples = history.into_iter()
+        .map(|(mut sample, player_idx)| {
+            sample.outcome = rank_outcome(&rankings, player_idx);
+            sample.score_margin = finished.then(|| score_margin(&rankings, player_idx));
+            sample
+        })
+        .collect();
+    SelfPlayGame { samples, rankings, resignation }
+}
+
+fn run_play(cli: Cli) {
+    let num_players = cli.players.len();
+    if !(2..=4).contains(&num_players) {
+        eprintln!("Error: Play mode needs between 2 and 4 players.");
+        return;
+    }
+    let mut agents: Vec<Box<dyn AIAgent>> = cli.players.iter().map(|name| create_agent(name)).collect();
+    let mut game = GameState::new(num_players);
+    play_interactive_game(&mut game, &mut agents, &cli.players);
+}
+
+fn run_watch(cli: Cli) {
+    let num_players = cli.players.len();
+    if !(2..=4).contains(&num_players) {
+        eprintln!("Error: Watch mode needs between 2 and 4 players.");
+        return;
+    }
+    let mut agents = create_agents(&cli.players, cli.seed);
+    let mut game = start_state(num_players, cli.seed);
+    watch_game(&mut game, &mut agents, &cli.players, Duration::from_millis(cli.watch_delay));
+}
+
+fn search_value(agent: &mut Box<dyn AIAgent>) -> Option<f32> {
+    if let Some(mcts_agent) = agent.as_any().downcast_ref::<MctsHeuristicAI>() {
+        return mcts_agent.root_value();
+    }
+    agent.as_any().downcast_ref::<MctsNnAI>().and_then(|a| a.root_value())
+}
+
+fn run_label_positions(cli: Cli) -> std::io::Result<()> {
+    let num_positions = cli.games;
+    let agent_config = cli.players[0].clone();
+    let num_players = cli.self_play_players;
+
+    if !(2..=4).contains(&num_players) {
+        eprintln!("Error: Player count must be between 2 and 4.");
+        return Ok(());
+    }
+    let spec: AgentSpec = match agent_config.parse() {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(());
+        }
+    };
+    let default_iterations = match spec.name.as_str() {
+        "mctsheuristic" => DEFAULT_MCTS_HEURISTIC_ITERATIONS,
+        "mctsnn" => DEFAULT_MCTS_NN_ITERATIONS,
+        _ => {
+            eprintln!("Error: Labeling needs an MCTS agent (mctsheuristic or mctsnn), got '{}'.", agent_config);
+            return Ok(());
+        }
+    };
+    let search_iterations = spec.arg::<u32>(0, "iteration count").ok().flatten().unwrap_or(default_iterations);
+
+    let logged_positions: Vec<TurnState> = match &cli.curriculum_logs {
+        Some(path) => load_logged_positions(path, num_players)?,
+        None => Vec::new(),
+    };
+
+    println!("Labeling {} {}-player positions with {}...", num_positions, num_players, agent_config);
+    let start_time = Instant::now();
+
+    let labeled: Vec<LabeledPosition> = (0..num_positions)
+        .into_par_iter()
+        .filter_map(|_| {
+            let mut rng = thread_rng();
+            let state = match logged_positions.choose(&mut rng) {
+                Some(turn_state) => GameState::from_turn_state(turn_state),
+                None => generate_curriculum_position(num_players, &mut rng),
+            };
+            let mut agent = create_agent(&agent_config);
+            let best_move = agent.get_move(&state)?;
+            let value = search_value(&mut agent)?;
+            Some(LabeledPosition { state, best_move, value, search_iterations })
+        })
+        .collect();
+
+    let duration = start_time.elapsed();
+    println!("Labeled {} positions in {:.2} seconds.", labeled.len(), duration.as_secs_f64());
+
+    fs::create_dir_all("eval_positions")?;
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let data_path = format!("eval_positions/positions_{}.json", timestamp);
+    let data_file = fs::File::create(&data_path)?;
+    serde_json::to_writer_pretty(data_file, &labeled)?;
+    println!("Done. Dataset saved to '{}'", data_path);
+    Ok(())
+}
+
+/// The fewest games between checks of a simulation's `--confidence` stopping rule.
+const CONFIDENCE_MIN_BATCH: u32 = 50;
+
+fn run_simulations(cli: Cli) -> std::io::Result<()> {
+    let seeds = GameSeeds::new(&cli);
+    let mut num_games = cli.games;
+    let agent_config = cli.players;
+    let seats = agent_config.len() as u32;
+    if cli.mirror && !num_games.is_multiple_of(seats) {
+        num_games = num_games.div_ceil(seats) * seats;
+        println!("Rounding up to {} games, a whole number of mirrored sets of {}.", num_games, seats);
+    }
+    let stopping_rule = match cli.confidence.map(|confidence| StoppingRule::new(confidence, cli.margin)).transpose() {
+        Ok(rule) => rule,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(());
+        }
+    };
+    match &stopping_rule {
+        Some(rule) => println!(
+            "Running up to {} {}-player games in parallel, until every win rate is within {:.1}% at {:.0}% confidence...",
+            num_games, agent_config.len(), rule.margin * 100.0, rule.confidence * 100.0
+        ),
+        None => println!("Running {} {}-player games in parallel...", num_games, agent_config.len()),
+    }
+    let start_time = Instant::now();
+
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let output_dir = format!("stats/{}", timestamp);
+    fs::create_dir_all(&output_dir)?;
+    // Each game's log is written as soon as it finishes, so memory doesn't grow with the run.
+    let log_writer = if cli.no_logs {
+        None
+    } else {
+        let logs_path = format!("{}/game_logs.jsonl{}", output_dir, if cli.gzip_logs { ".gz" } else { "" });
+        Some(Mutex::new(GameLogWriter::create(Path::new(&logs_path), cli.gzip_logs)?))
+    };
+    let mut stats = GameStats::new();
+    for name in &agent_config {
+        stats.agent_wins.entry(name.clone()).or_insert(0);
+    }
+    let stats = Mutex::new(stats);
+
+    let progress = games_progress_bar(num_games);
+    let play_games = |games: std::ops::Range<u32>| games.into_par_iter().try_for_each(|i| {
+        let mut current_matchup = agent_config.clone();
+        let len = current_matchup.len();
+        if len > 0 { current_matchup.rotate_left(i as usize % len); }
+        // A mirrored set is every rotation of the seats on one seed.
+        let seed = seeds.game_seed(i as u64, seats as u64);
+        let agents = create_agents(&current_matchup, seed);
+        let (final_state, game_log) = run_game(start_state(agents.len(), seed), agents, current_matchup, seed);
+        stats.lock().expect("a simulation worker panicked").record_game(&final_state, &game_log);
+        if let Some(log_writer) = &log_writer {
+            log_writer.lock().expect("a simulation worker panicked").write(&game_log)?;
+        }
+        progress.inc(1);
+        Ok::<(), std::io::Error>(())
+    });
+    // The widest win-rate interval when the rule was last checked.
+    let mut widest = None;
+    match &stopping_rule {
+        None => play_games(0..num_games)?,
+        Some(rule) => {
+            // Checked between batches of whole mirrored sets, so the stop never splits a set.
+            let batch = (rayon::current_num_threads() as u32 * 2).max(CONFIDENCE_MIN_BATCH).div_ceil(seats) * seats;
+            let mut played = 0;
+            while played < num_games {
+                let end = (played + batch).min(num_games);
+                play_games(played..end)?;
+                played = end;
+                let stats = stats.lock().expect("a simulation worker panicked");
+                let half_width = distinct(&agent_config)
+                    .map(|name| stats.agents.get(name).copied().unwrap_or_default())
+                    .map(|agent| rule.half_width(agent.wins, agent.games))
+                    .fold(0.0, f64::max);
+                widest = Some(half_width);
+                if half_width <= rule.margin {
+                    break;
+                }
+            }
+        }
+    }
+    progress.finish_and_clear();
+    if let (Some(rule), Some(widest)) = (&stopping_rule, widest) {
+        let played = stats.lock().expect("a simulation worker panicked").total_games;
+        if widest <= rule.margin {
+            println!(
+                "Stopped after {} games: every win rate is within {:.1}% (widest ±{:.1}%) at {:.0}% confidence.",
+                played, rule.margin * 100.0, widest * 100.0, rule.confidence * 100.0
+            );
+        } else {
+            println!(
+                "Played all {} games without reaching the margin: the widest win-rate interval is still ±{:.1}%.",
+                played, widest * 100.0
+            );
+        }
+    }
+    if let Some(log_writer) = log_writer {
+        log_writer.into_inner().expect("a simulation worker panicked").finish()?;
+    }
+
+    let mut stats = stats.into_inner().expect("a simulation worker panicked");
+    stats.simulation_time_seconds = start_time.elapsed().as_secs_f64();
+    match cli.format {
+        OutputFormat::Text => stats.print_summary(&agent_config),
+        OutputFormat::Csv => {
+            let csv = stats.to_csv(&agent_config);
+            print!("{}", csv);
+            fs::write(format!("{}/summary.csv", output_dir), csv)?;
+        }
+    }
+    let stats_path = format!("{}/summary_stats.json", output_dir);
+    let stats_file = fs::File::create(&stats_path)?;
+    serde_json::to_writer_pretty(stats_file, &stats)?;
+    println!("Done. Results saved in '{}' directory.", output_dir);
+    Ok(())
+}
+
+/// A new game, on a seeded tile sequence if `seed` is given.
+fn start_state(num_players: usize, seed: Option<u64>) -> GameState {
+    match seed {
+        Some(seed) => GameState::new_with_seed(num_players, seed),
+        None => GameState::new(num_players),
+    }
+}
+
+/// The seeds of a run's games, from --seed and --mirror.
+struct GameSeeds {
+    first: Option<u64>,
+    mirror: bool,
+}
+
+impl GameSeeds {
+    fn new(cli: &Cli) -> Self {
+        let first = if cli.mirror { Some(cli.seed.unwrap_or(0)) } else { cli.seed };
+        Self { first, mirror: cli.mirror }
+    }
+
+    /// The seed of the run's `game`th game, where mirrored sets are `set_size` games each, or
+    /// `None` for an unseeded run.
+    fn game_seed(&self, game: u64, set_size: u64) -> Option<u64> {
+        let index = if self.mirror { game / set_size } else { game };
+        self.first.map(|first| first.wrapping_add(index))
+    }
+}
+
+/// The agents for a game, each seeded from the game's seed (and its seat) if it has one.
+fn create_agents(names: &[String], seed: Option<u64>) -> Vec<Box<dyn AIAgent>> {
+    let mut rng = seed.map(StdRng::seed_from_u64);
+    names.iter()
+        .map(|name| {
+            let mut agent = create_agent(name);
+            if let Some(rng) = &mut rng {
+                agent.configure(&AgentConfig { seed: Some(rng.gen()), ..AgentConfig::default() });
+            }
+            agent
+        })
+        .collect()
+}
+
+/// Plays a game, on a seeded tile sequence if `seed` is given, between the `first` and
+/// `second` of `entrants`.
+fn play_tournament_game(entrants: &[String], first: usize, second: usize, seed: Option<u64>) -> GameResult {
+    let mut game = start_state(2, seed);
+    let matchup = [entrants[first].clone(), entrants[second].clone()];
+    let mut agents = create_agents(&matchup, seed);
+    while game.phase() != GamePhase::GameOver {
+        let next_move = match game.phase() {
+            GamePhase::Drafting => {
+                let seat = game.current_player_idx;
+                match agents[seat].get_move(&game).filter(|m| game.validate_move(m).is_ok()) {
+                    Some(m) => Some(m),
+                    None => {
+                        report_forfeit(&matchup, seat, seed);
+                        return GameResult { first, second, points: if seat == 0 { 0.0 } else { 1.0 }, margin: 0 };
+                    }
+                }
+            }
+            _ => None,
+        };
+        game.advance(next_move.as_ref()).expect("the move was validated");
+    }
+    let points = match game.winner() {
+        Winner::Single(0) => 1.0,
+        Winner::Single(_) => 0.0,
+        Winner::Shared(players) if players.contains(&0) => 0.5,
+        Winner::Shared(_) => 0.0,
+    };
+    let margin = game.players[0].score as i32 - game.players[1].score as i32;
+    GameResult { first, second, points, margin }
+}
+
+/// Logs that the agent in `seat` of `matchup` gave no move or an illegal one, forfeiting.
+fn report_forfeit(matchup: &[String], seat: usize, seed: Option<u64>) {
+    let seed = seed.map(|seed| format!(" (seed {})", seed)).unwrap_or_default();
+    eprintln!("{} in seat {} forfeits{}: it gave no legal move.", matchup[seat], seat, seed);
+}
+
+/// A tournament's results as saved to tournament.json.
+#[derive(Serialize)]
+struct TournamentReport {
+    crosstable: Crosstable,
+    /// Elo ratings relative to an average of 0, in entrant order.
+    ratings: Vec<f64>,
+    /// Swiss match points, in entrant order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    match_points: Option<Vec<f64>>,
+}
+
+/// Plays the games of `jobs`, each the first and second seat's entrants and the game's seed.
+fn play_tournament_games(entrants: &[String], jobs: Vec<(usize, usize, Option<u64>)>) -> Vec<GameResult> {
+    jobs.into_par_iter()
+        .map(|(first, second, seed)| play_tournament_game(entrants, first, second, seed))
+        .collect()
+}
+
+/// Plays the rounds of a Swiss tournament into `crosstable`: `games_per_match` games per
+/// pairing, alternating who takes the first seat, each pair of games on the same seed when
+/// mirrored.
+fn run_swiss_rounds(
+    entrants: &[String],
+    rounds: u32,
+    games_per_match: usize,
+    seeds: &GameSeeds,
+    crosstable: &mut Crosstable,
+) -> Swiss {
+    let mut swiss = Swiss::new(entrants.len());
+    let mut pairs_played = 0;
+    for round in 1..=rounds {
+        let (pairings, bye) = swiss.pair_round(crosstable);
+        let mut jobs = Vec::new();
+        for &(a, b) in &pairings {
+            for game in (0..games_per_match).step_by(2) {
+                jobs.push((a, b, seeds.game_seed(2 * pairs_played, 2)));
+                if game + 1 < games_per_match {
+                    jobs.push((b, a, seeds.game_seed(2 * pairs_played + 1, 2)));
+                }
+                pairs_played += 1;
+            }
+        }
+        let results = play_tournament_games(entrants, jobs);
+        for result in &results {
+            crosstable.record(result);
+        }
+        println!("Round {}:", round);
+        for &(a, b) in &pairings {
+            let a_points: f64 = results.iter()
+                .filter_map(|r| match (r.first, r.second) {
+                    pair if pair == (a, b) => Some(r.points),
+                    pair if pair == (b, a) => Some(1.0 - r.points),
+                    _ => None,
+                })
+                .sum();
+            let b_points = games_per_match as f64 - a_points;
+            swiss.record_match(a, b, a_points, b_points);
+            println!("  {} {} - {} {}", entrants[a], a_points, b_points, entrants[b]);
+        }
+        if let Some(idx) = bye {
+            println!("  {} has a bye", entrants[idx]);
+        }
+    }
+    swiss
+}
+
+fn run_tournament(cli: Cli, format: TournamentFormat) -> std::io::Result<()> {
+    let seeds = GameSeeds::new(&cli);
+    let entrants = cli.players;
+    if entrants.len() < 2 {
+        eprintln!("Error: A tournament needs at least 2 --players agents.");
+        return Ok(());
+    }
+    let start_time = Instant::now();
+    let mut crosstable = Crosstable::new(entrants.clone());
+    let mut swiss = None;
+    match format {
+        TournamentFormat::RoundRobin => {
+            let mut jobs = Vec::new();
+            for (a, b) in round_robin_pairings(entrants.len()) {
+                for _ in 0..cli.games {
+                    let game = jobs.len() as u64;
+                    jobs.push((a, b, seeds.game_seed(game, 2)));
+                    jobs.push((b, a, seeds.game_seed(game + 1, 2)));
+                }
+            }
+            println!(
+                "Running a round-robin tournament between {} agents: {} games ({} per pairing and seat order)...",
+                entrants.len(), jobs.len(), cli.games
+            );
+            for result in &play_tournament_games(&entrants, jobs) {
+                crosstable.record(result);
+            }
+            crosstable.print();
+        }
+        TournamentFormat::Swiss => {
+            // Enough rounds for an undefeated entrant to be the only one left.
+            let rounds = cli.rounds.unwrap_or((entrants.len() as f64).log2().ceil() as u32).max(1);
+            println!(
+                "Running a {}-round Swiss tournament between {} agents ({} games per match)...",
+                rounds, entrants.len(), cli.games
+            );
+            swiss = Some(run_swiss_rounds(&entrants, rounds, cli.games as usize, &seeds, &mut crosstable));
+        }
+    }
+
+    let ratings = crosstable.ratings();
+    let order = match &swiss {
+        Some(swiss) => swiss.standings(&crosstable),
+        None => crosstable.standings(),
+    };
+    println!("\n--- Ratings (Elo, relative to the field's average) ---");
+    for (place, &idx) in order.iter().enumerate() {
+        let total = crosstable.total(idx);
+        let match_points = swiss.as_ref().map(|s| format!("{} match points, ", s.match_points[idx])).unwrap_or_default();
+        println!(
+            "  {:>2}. {:<30} {:+7.0}  ({}{} games, {:.1}%, {:+.1} per game)",
+            place + 1, entrants[idx], ratings[idx], match_points, total.games,
+            total.score_rate().unwrap_or(0.0) * 100.0, total.average_margin().unwrap_or(0.0)
+        );
+    }
+    let games: u32 = (0..entrants.len()).map(|idx| crosstable.total(idx).games).sum::<u32>() / 2;
+    println!("\nPlayed {} games in {:.2} seconds.", games, start_time.elapsed().as_secs_f64());
+
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let output_dir = format!("stats/{}", timestamp);
+    fs::create_dir_all(&output_dir)?;
+    let report_path = format!("{}/tournament.json", output_dir);
+    let report = TournamentReport { crosstable, ratings, match_points: swiss.map(|s| s.match_points) };
+    serde_json::to_writer_pretty(fs::File::create(&report_path)?, &report)?;
+    println!("Done. Results saved to '{}'.", report_path);
+    Ok(())
+}
+
+/// Random games whose drafting positions `--bench` measures on.
+const BENCH_GAMES: usize = 20;
+/// Iterations per search in the `--bench` MCTS measurement.
+const BENCH_MCTS_ITERATIONS: u32 = 500;
+
+/// Every drafting position of `BENCH_GAMES` random 2-player games, the same ones for a given seed.
+fn bench_positions(seed: u64) -> Vec<GameState> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut positions = Vec::new();
+    for _ in 0..BENCH_GAMES {
+        let mut game = GameState::new_with_seed(2, rng.gen());
+        while !game.is_game_over() {
+            let player_move = match game.phase() {
+                GamePhase::Drafting => {
+                    positions.push(game.clone());
+                    game.get_legal_moves().choose(&mut rng).cloned()
+                }
+                _ => None,
+            };
+            if game.advance(player_move.as_ref()).is_err() {
+                break;
+            }
+        }
+    }
+    positions
+}
+
+/// Runs `step` until `seconds` have passed (at least once) and returns the units of work it
+/// reported per second.
+fn measure_rate(seconds: f64, mut step: impl FnMut() -> u64) -> f64 {
+    let start = Instant::now();
+    let mut units = 0;
+    while units == 0 || start.elapsed().as_secs_f64() < seconds {
+        units += step();
+    }
+    units as f64 / start.elapsed().as_secs_f64()
+}
+
+/// One agent's `--bench` results.
+struct AgentBench {
+    rollouts_per_second: f64,
+    moves_per_second: f64,
+    /// Search iterations per second, for agents that report them.
+    iterations_per_second: Option<f64>,
+}
+
+/// Plays games to the end from `positions` in turn, with `agent` in every seat.
+fn bench_agent(agent: &mut dyn AIAgent, positions: &[GameState], seconds: f64) -> AgentBench {
+    let (mut moves, mut iterations, mut searched) = (0u64, 0u64, false);
+    let mut next = positions.iter().cycle();
+    let start = Instant::now();
+    let rollouts = measure_rate(seconds, || {
+        let mut game = next.next().expect("there are bench positions").clone();
+        agent.reset();
+        while !game.is_game_over() {
+            let player_move = match game.phase() {
+                GamePhase::Drafting => {
+                    let Some(player_move) = agent.get_move(&game) else { break };
+                    moves += 1;
+                    if let Some(search) = agent.search_stats() {
+                        iterations += search.iterations as u64;
+                        searched |= search.iterations > 0;
+                    }
+                    Some(player_move)
+                }
+                _ => None,
+            };
+            if game.advance(player_move.as_ref()).is_err() {
+                break;
+            }
+        }
+        1
+    });
+    let elapsed = start.elapsed().as_secs_f64();
+    AgentBench {
+        rollouts_per_second: rollouts,
+        moves_per_second: moves as f64 / elapsed,
+        iterations_per_second: searched.then(|| iterations as f64 / elapsed),
+    }
+}
+
+fn run_bench(cli: Cli) -> std::io::Result<()> {
+    let seconds = cli.bench_seconds;
+    if seconds.is_nan() || seconds <= 0.0 {
+        eprintln!("Error: --bench-seconds must be positive.");
+        return Ok(());
+    }
+    let positions = bench_positions(cli.seed.unwrap_or(0));
+    let with_moves: Vec<(&GameState, Vec<Move>)> =
+        positions.iter().map(|position| (position, position.get_legal_moves())).collect();
+    let moves_per_position = with_moves.iter().map(|(_, moves)| moves.len()).sum::<usize>() as f64 / positions.len() as f64;
+    println!(
+        "Benchmarking on {} positions from {} random 2-player games ({:.1} legal moves each), {:.1}s per measurement...",
+        positions.len(), BENCH_GAMES, moves_per_position, seconds
+    );
+
+    let mut results: Vec<(String, f64)> = Vec::new();
+    let legal_moves = measure_rate(seconds, || {
+        for position in &positions {
+            std::hint::black_box(position.get_legal_moves());
+        }
+        positions.len() as u64
+    });
+    results.push(("legal move generations".to_string(), legal_moves));
+    let clones = measure_rate(seconds, || {
+        for position in &positions {
+            std::hint::black_box(position.clone());
+        }
+        positions.len() as u64
+    });
+    results.push(("state clones".to_string(), clones));
+    // Each move is applied to a fresh clone, so this rate includes the clone.
+    let applies = measure_rate(seconds, || {
+        let mut count = 0;
+        for (position, moves) in &with_moves {
+            for player_move in moves {
+                let mut state = (*position).clone();
+                state.apply_move(player_move);
+                std::hint::black_box(&state);
+                count += 1;
+            }
+        }
+        count
+    });
+    results.push(("apply_move calls (with a clone)".to_string(), applies));
+    // Without the endgame solver, every search runs all of its iterations.
+    let mut engine = MctsHeuristicAI::new(BENCH_MCTS_ITERATIONS)
+        .with_config(MctsConfig { endgame_nodes: 0, ..MctsConfig::default() });
+    engine.configure(&AgentConfig { seed: Some(cli.seed.unwrap_or(0)), ..AgentConfig::default() });
+    let mut next = positions.iter().cycle();
+    let mcts = measure_rate(seconds, || {
+        engine.reset();
+        engine.get_move(next.next().expect("there are bench positions"));
+        engine.search_stats().map_or(0, |search| search.iterations as u64)
+    });
+    results.push((format!("MCTS iterations (mctsheuristic:{})", BENCH_MCTS_ITERATIONS), mcts));
+
+    let mut agents = Vec::new();
+    for name in &cli.players {
+        let mut agent = create_agent(name);
+        if let Some(seed) = cli.seed {
+            agent.configure(&AgentConfig { seed: Some(seed), ..AgentConfig::default() });
+        }
+        agents.push((name, bench_agent(agent.as_mut(), &positions, seconds)));
+    }
+
+    match cli.format {
+        OutputFormat::Text => {
+            println!("\n--- Engine (per second) ---");
+            for (what, rate) in &results {
+                println!("  {:<40} {:>14.0}", what, rate);
+            }
+            println!("\n--- Agents (per second, every seat played by the agent) ---");
+            for (name, bench) in &agents {
+                let iterations = bench.iterations_per_second.map(|rate| format!(", {:.0} search iterations", rate)).unwrap_or_default();
+                println!(
+                    "  {}: {:.2} rollouts, {:.1} moves{}",
+                    name, bench.rollouts_per_second, bench.moves_per_second, iterations
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            println!("measurement,per_second");
+            for (what, rate) in &results {
+                println!("{},{:.1}", csv_field(what), rate);
+            }
+            for (name, bench) in &agents {
+                println!("{},{:.3}", csv_field(&format!("{} rollouts", name)), bench.rollouts_per_second);
+                println!("{},{:.3}", csv_field(&format!("{} moves", name)), bench.moves_per_second);
+                if let Some(rate) = bench.iterations_per_second {
+                    println!("{},{:.1}", csv_field(&format!("{} search iterations", name)), rate);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The fewest games between reports of the SPRT's progress.
+const SPRT_MIN_BATCH: usize = 50;
+
+fn run_sprt(cli: Cli) -> std::io::Result<()> {
+    if cli.players.len() != 2 {
+        eprintln!("Error: The SPRT needs exactly 2 --players agents: the one under test, then the baseline.");
+        return Ok(());
+    }
+    let mut sprt = match Sprt::parse(&cli.sprt) {
+        Ok(sprt) => sprt,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(());
+        }
+    };
+    let seeds = GameSeeds::new(&cli);
+    let (lower, upper) = sprt.bounds();
+    println!(
+        "SPRT of {} against {}: H0 elo {}, H1 elo {} (alpha {}, beta {}), LLR bounds [{:.2}, {:.2}], at most {} games.",
+        cli.players[0], cli.players[1], sprt.elo0, sprt.elo1, sprt.alpha, sprt.beta, lower, upper, cli.games
+    );
+    let start_time = Instant::now();
+    // Games are played a batch at a time in parallel, and counted in order until a decision.
+    let batch_size = (rayon::current_num_threads() * 2).max(SPRT_MIN_BATCH);
+    let mut decision = None;
+    while decision.is_none() && sprt.games() < cli.games {
+        let played = sprt.games() as usize;
+        let batch = batch_size.min((cli.games - sprt.games()) as usize);
+        // Each pair of games swaps the seats, on the same seed when mirrored.
+        let jobs: Vec<(usize, usize, Option<u64>)> = (played..played + batch)
+            .map(|game| {
+                let seed = seeds.game_seed(game as u64, 2);
+                if game % 2 == 0 { (0, 1, seed) } else { (1, 0, seed) }
+            })
+            .collect();
+        for result in play_tournament_games(&cli.players, jobs) {
+            sprt.record(if result.first == 0 { result.points } else { 1.0 - result.points });
+            decision = sprt.decision();
+            if decision.is_some() {
+                break;
+            }
+        }
+        println!(
+            "  {:>6} games: W-D-L {}-{}-{}, score {:.1}%, Elo {:+.1}, LLR {:+.2}",
+            sprt.games(), sprt.wins, sprt.draws, sprt.losses,
+            sprt.score().unwrap_or(0.5) * 100.0, sprt.elo_estimate().unwrap_or(0.0), sprt.llr()
+        );
+    }
+    println!("\nPlayed {} games in {:.2} seconds.", sprt.games(), start_time.elapsed().as_secs_f64());
+    match decision {
+        Some(Decision::H1) => println!("H1 accepted: {} is at least {} Elo stronger than {}.", cli.players[0], sprt.elo1, cli.players[1]),
+        Some(Decision::H0) => println!("H0 accepted: {} is no more than {} Elo stronger than {}.", cli.players[0], sprt.elo0, cli.players[1]),
+        None => println!("No decision after {} games; raise --games to keep testing.", sprt.games()),
+    }
+    Ok(())
+}
+
+fn run_game(mut game: GameState, mut agents: Vec<Box<dyn AIAgent>>, matchup: Vec<String>, seed: Option<u64>) -> (GameState, GameLog) {
+    let mut round_history: Vec<GameRound> = Vec::new();
+    let mut round_counter = 1;
+    let mut forfeited_by = None;
+
+    while !game.is_game_over() {
+        let tile_bag_at_start = TileBagSummary::from_vec(&game.tile_bag);
+        let mut turns_this_round: Vec<GameTurn> = Vec::new();
+        while game.phase() == GamePhase::Drafting {
+            let state_before_move = TurnState::from(&game);
+            let agent = &mut agents[game.current_player_idx];
+            let think_start = Instant::now();
+            let Some(ai_move) = agent.get_move(&game).filter(|m| game.validate_move(m).is_ok()) else {
+                report_forfeit(&matchup, state_before_move.current_player_idx, seed);
+                forfeited_by = Some(state_before_move.current_player_idx);
+                break;
+            };
+            let think_seconds = think_start.elapsed().as_secs_f64();
+            game.advance(Some(&ai_move)).expect("the move was validated");
+            turns_this_round.push(GameTurn {
+                player_index: state_before_move.current_player_idx,
+                state_before_move,
+                chosen_move: ai_move,
+                think_seconds: Some(think_seconds),
+                search: agent.search_stats(),
+            });
+        }
+        if forfeited_by.is_some() {
+            break;
+        }
+        let scoring_events = game.advance(None).expect("the drafting phase is over");
+        round_history.push(GameRound {
+            round_number: round_counter,
+            tile_bag_at_start_of_round: tile_bag_at_start,
+            turns: turns_this_round,
+            scoring_events,
+        });
+        round_counter += 1;
+    }
+    let log = GameLog {
+        matchup,
+        seed,
+        history: round_history,
+        final_scores: game.players.iter().map(|p| p.score).collect(),
+        forfeited_by,
+    };
+    (game, log)
+}
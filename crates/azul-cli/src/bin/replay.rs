@@ -0,0 +1,87 @@
+use azul_cli::cli_human_agent::{describe_move, describe_scoring_event};
+use azul_cli::game_log::{read_game_logs, verify_game_log, GameLog};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Replays the games in a `headless` game log through the engine and checks that every move
+/// is legal and every score comes out as recorded, which catches engine nondeterminism and log
+/// format drift. It can also print a game move by move.
+#[derive(Parser, Debug)]
+#[command(version, about = "Verify and replay headless game logs", long_about = None)]
+struct Cli {
+    /// A game log: game_logs.jsonl, game_logs.jsonl.gz, or an older game_logs.json.
+    path: PathBuf,
+    /// Print this game (numbered from 1, in the log's order) move by move.
+    #[arg(short, long)]
+    game: Option<usize>,
+}
+
+fn print_game(number: usize, log: &GameLog) {
+    print!("\n--- Game {}: {}", number, log.matchup.join(" vs "));
+    match log.seed {
+        Some(seed) => println!(" (seed {}) ---", seed),
+        None => println!(" ---"),
+    }
+    for round in &log.history {
+        println!("\nRound {}", round.round_number);
+        if let Some(first) = round.turns.first() {
+            let scores: Vec<String> = first.state_before_move.players.iter().map(|p| p.score.to_string()).collect();
+            println!("  Scores: {}", scores.join(", "));
+        }
+        for turn in &round.turns {
+            let mut effort = Vec::new();
+            if let Some(seconds) = turn.think_seconds {
+                effort.push(format!("{:.3}s", seconds));
+            }
+            if let Some(search) = turn.search {
+                if search.iterations > 0 {
+                    effort.push(format!("{} iterations", search.iterations));
+                }
+                effort.push(format!("{} nodes", search.nodes));
+            }
+            let effort = if effort.is_empty() { String::new() } else { format!(" ({})", effort.join(", ")) };
+            println!(
+                "  Player {} ({}): {} [{}]{}",
+                turn.player_index + 1, log.matchup[turn.player_index], describe_move(&turn.chosen_move), turn.chosen_move, effort
+            );
+        }
+        for event in &round.scoring_events {
+            println!("    {}", describe_scoring_event(event));
+        }
+    }
+    let scores: Vec<String> = log.final_scores.iter().map(|score| score.to_string()).collect();
+    println!("\nFinal scores: {}", scores.join(", "));
+}
+
+fn run(cli: Cli) -> Result<bool, String> {
+    let logs = read_game_logs(&cli.path).map_err(|e| format!("Could not read {}: {}", cli.path.display(), e))?;
+    let (mut games, mut mismatched) = (0, 0);
+    for (i, log) in logs.enumerate() {
+        let number = i + 1;
+        let log = log.map_err(|e| format!("{}, game {}: {}", cli.path.display(), number, e))?;
+        games += 1;
+        if let Err(e) = verify_game_log(&log) {
+            mismatched += 1;
+            println!("Game {} ({}): {}", number, log.matchup.join(" vs "), e);
+        }
+        if cli.game == Some(number) {
+            print_game(number, &log);
+        }
+    }
+    if cli.game.is_some_and(|number| number == 0 || number > games) {
+        return Err(format!("There is no game {}; the log has {} games", cli.game.unwrap_or(0), games));
+    }
+    println!("\nReplayed {} games: {} matched the log, {} didn't.", games, games - mismatched, mismatched);
+    Ok(mismatched == 0)
+}
+
+fn main() {
+    match run(Cli::parse()) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
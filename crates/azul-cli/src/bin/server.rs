@@ -0,0 +1,425 @@
+use azul_ai::analysis::{evaluate_position, PositionEvaluation};
+use azul_core::{GamePhase, GameState, Move, Observation, Ranking, ScoringEvent};
+use clap::Parser;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{error::ProtocolError, Message};
+
+/// Hosts live games over WebSocket. Clients send and receive JSON text messages, each an
+/// object with a `type`:
+///
+///   join {game, name?, players?, token?}  takes the first free seat of `game` (created with
+///                                         `players` seats, default 2), or with the `token`
+///                                         from an earlier `seated`, takes that seat back
+///   spectate {game}                       watches `game` without a seat
+///   move {move}                           plays a move in notation (`F3:B->L2`) on your turn
+///
+/// The server answers `seated {game, seat, token}` or `spectating {game}`, then a `state`
+/// with the table, the seats and every move so far, which is also what a reconnecting client
+/// resyncs from. The game starts once every seat is taken. After that, each move is broadcast
+/// as `moved {player, move, scoring}` (the scoring events, if it ended the round), followed by
+/// a fresh `state`, then `turn {player}` or `game_over {rankings}`. Seats coming and going are
+/// announced as `joined`, `disconnected` and `reconnected`. Spectators also get
+/// `evaluation {ply, evaluation}` from a background search after each move.
+#[derive(Parser, Debug)]
+#[command(version, about = "Host live Azul games for players and spectators over WebSocket", long_about = None)]
+struct Cli {
+    /// Address to listen on.
+    #[arg(long, default_value = "0.0.0.0:8080")]
+    bind: String,
+    /// MCTS iterations for the evaluations sent to spectators; 0 sends none.
+    #[arg(long, default_value_t = 1000)]
+    eval_iterations: u32,
+}
+
+/// How long a connection waits for a message before it sends what is queued for it.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// --- Messages ---
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Join { game: String, name: Option<String>, players: Option<usize>, token: Option<String> },
+    Spectate { game: String },
+    Move {
+        #[serde(rename = "move")]
+        notation: String,
+    },
+}
+
+#[derive(Clone, Serialize)]
+struct PlayedMove {
+    player: usize,
+    #[serde(rename = "move")]
+    notation: String,
+}
+
+#[derive(Serialize)]
+struct SeatInfo {
+    name: Option<String>,
+    connected: bool,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    Seated { game: &'a str, seat: usize, token: &'a str },
+    Spectating { game: &'a str },
+    /// Spectators get seat 0's observation; every seat sees the same table.
+    State { observation: Observation, seats: Vec<SeatInfo>, started: bool, moves: &'a [PlayedMove] },
+    Joined { seat: usize, name: Option<&'a str> },
+    Disconnected { seat: usize },
+    Reconnected { seat: usize },
+    Turn { player: usize },
+    Moved {
+        player: usize,
+        #[serde(rename = "move")]
+        notation: &'a str,
+        scoring: &'a [ScoringEvent],
+    },
+    GameOver { rankings: Vec<Ranking> },
+    Evaluation { ply: usize, evaluation: &'a PositionEvaluation },
+    Error { message: &'a str },
+}
+
+impl ServerMessage<'_> {
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("server messages always serialize")
+    }
+}
+
+// --- Games ---
+
+/// A connection's outgoing queue, with the id that tells its connection apart.
+#[derive(Clone)]
+struct Client {
+    id: u64,
+    outbox: Sender<String>,
+}
+
+impl Client {
+    fn send(&self, message: &ServerMessage) {
+        // A closed connection is cleaned up by its own thread.
+        let _ = self.outbox.send(message.to_json());
+    }
+}
+
+struct Seat {
+    name: Option<String>,
+    /// Proves a reconnecting client's claim to the seat.
+    token: String,
+    client: Option<Client>,
+}
+
+struct Room {
+    state: GameState,
+    seats: Vec<Seat>,
+    spectators: Vec<Client>,
+    moves: Vec<PlayedMove>,
+    /// Whether a spectator evaluation is running.
+    evaluating: bool,
+}
+
+impl Room {
+    fn started(&self) -> bool {
+        self.seats.len() == self.state.players.len()
+    }
+
+    fn clients(&self) -> impl Iterator<Item = &Client> {
+        self.seats.iter().filter_map(|seat| seat.client.as_ref()).chain(&self.spectators)
+    }
+
+    fn broadcast(&self, message: &ServerMessage) {
+        let json = message.to_json();
+        for client in self.clients() {
+            let _ = client.outbox.send(json.clone());
+        }
+    }
+
+    /// Sends `client` the whole game as `seat` (or, for a spectator, seat 0) sees it.
+    fn send_state(&self, client: &Client, seat: Option<usize>) {
+        let seats = self.seats.iter()
+            .map(|seat| SeatInfo { name: seat.name.clone(), connected: seat.client.is_some() })
+            .collect();
+        client.send(&ServerMessage::State {
+            observation: self.state.observation_for(seat.unwrap_or(0)),
+            seats,
+            started: self.started(),
+            moves: &self.moves,
+        });
+    }
+
+    fn send_states(&self) {
+        for (idx, seat) in self.seats.iter().enumerate() {
+            if let Some(client) = &seat.client {
+                self.send_state(client, Some(idx));
+            }
+        }
+        for spectator in &self.spectators {
+            self.send_state(spectator, None);
+        }
+    }
+
+    /// The next thing to wait for: a move from the player to move, or nothing at all.
+    fn announce_turn(&self) {
+        if self.state.is_game_over() {
+            self.broadcast(&ServerMessage::GameOver { rankings: self.state.rankings() });
+        } else if self.started() {
+            self.broadcast(&ServerMessage::Turn { player: self.state.current_player_idx });
+        }
+    }
+
+    /// Plays `notation` for `seat`, finishing the round if it ends the drafting.
+    fn play(&mut self, seat: usize, notation: &str) -> Result<(), String> {
+        if !self.started() {
+            return Err("the game starts once every seat is taken".to_string());
+        }
+        if self.state.phase() != GamePhase::Drafting {
+            return Err("the game is over".to_string());
+        }
+        if seat != self.state.current_player_idx {
+            return Err(format!("it is seat {}'s turn", self.state.current_player_idx));
+        }
+        let m = Move::from_notation(notation)?;
+        self.state.validate_move(&m).map_err(|e| format!("{} is illegal: {}", m, e))?;
+        self.state.apply_move(&m);
+        let scoring = if self.state.phase() == GamePhase::Tiling {
+            self.state.advance(None).map_err(|e| e.to_string())?
+        } else {
+            Vec::new()
+        };
+        let notation = m.to_notation();
+        self.moves.push(PlayedMove { player: seat, notation: notation.clone() });
+        self.broadcast(&ServerMessage::Moved { player: seat, notation: &notation, scoring: &scoring });
+        self.send_states();
+        self.announce_turn();
+        Ok(())
+    }
+}
+
+struct Lobby {
+    rooms: HashMap<String, Room>,
+    eval_iterations: u32,
+}
+
+type SharedLobby = Arc<Mutex<Lobby>>;
+
+/// Where a connection sits, once it has joined or is watching a game.
+#[derive(Clone)]
+enum Membership {
+    Seat { game: String, seat: usize },
+    Spectator { game: String },
+}
+
+fn new_token() -> String {
+    format!("{:016x}", rand::thread_rng().gen::<u64>())
+}
+
+fn join(lobby: &mut Lobby, client: &Client, game: &str, name: Option<String>, players: Option<usize>, token: Option<String>) -> Result<Membership, String> {
+    let num_players = players.unwrap_or(2);
+    if azul_core::factory_count(num_players).is_none() {
+        return Err(format!("Azul is for 2 to 4 players, not {}", num_players));
+    }
+    let room = lobby.rooms.entry(game.to_string()).or_insert_with(|| Room {
+        state: GameState::new(num_players),
+        seats: Vec::new(),
+        spectators: Vec::new(),
+        moves: Vec::new(),
+        evaluating: false,
+    });
+    if players.is_some_and(|players| players != room.state.players.len()) {
+        return Err(format!("game '{}' is for {} players", game, room.state.players.len()));
+    }
+
+    if let Some(token) = token {
+        let seat = room.seats.iter().position(|seat| seat.token == token)
+            .ok_or_else(|| format!("that token holds no seat in game '{}'", game))?;
+        room.seats[seat].client = Some(client.clone());
+        client.send(&ServerMessage::Seated { game, seat, token: &room.seats[seat].token });
+        room.broadcast(&ServerMessage::Reconnected { seat });
+        room.send_state(client, Some(seat));
+        if room.started() {
+            client.send(&ServerMessage::Turn { player: room.state.current_player_idx });
+        }
+        return Ok(Membership::Seat { game: game.to_string(), seat });
+    }
+
+    if room.started() {
+        return Err(format!("game '{}' is full; spectate it instead", game));
+    }
+    let seat = room.seats.len();
+    room.seats.push(Seat { name: name.clone(), token: new_token(), client: Some(client.clone()) });
+    client.send(&ServerMessage::Seated { game, seat, token: &room.seats[seat].token });
+    room.broadcast(&ServerMessage::Joined { seat, name: name.as_deref() });
+    if room.started() {
+        room.send_states();
+        room.announce_turn();
+    } else {
+        room.send_state(client, Some(seat));
+    }
+    Ok(Membership::Seat { game: game.to_string(), seat })
+}
+
+fn spectate(lobby: &mut Lobby, client: &Client, game: &str) -> Result<Membership, String> {
+    let room = lobby.rooms.get_mut(game).ok_or_else(|| format!("there is no game '{}'", game))?;
+    room.spectators.push(client.clone());
+    client.send(&ServerMessage::Spectating { game });
+    room.send_state(client, None);
+    Ok(Membership::Spectator { game: game.to_string() })
+}
+
+/// Starts a background evaluation of `game`'s position for its spectators, unless one is
+/// running; when that one finishes, it starts the next if the game has moved on.
+fn evaluate_for_spectators(lobby: &SharedLobby, game: &str) {
+    let mut guard = lobby.lock().expect("a connection handler panicked");
+    let iterations = guard.eval_iterations;
+    let Some(room) = guard.rooms.get_mut(game) else { return };
+    if iterations == 0 || room.evaluating || room.spectators.is_empty() || !room.started() {
+        return;
+    }
+    room.evaluating = true;
+    let (state, ply) = (room.state.clone(), room.moves.len());
+    let (lobby, game) = (Arc::clone(lobby), game.to_string());
+    thread::spawn(move || {
+        let evaluation = evaluate_position(&state, iterations);
+        let moved_on = {
+            let mut guard = lobby.lock().expect("a connection handler panicked");
+            let Some(room) = guard.rooms.get_mut(&game) else { return };
+            room.evaluating = false;
+            if room.moves.len() == ply {
+                for spectator in &room.spectators {
+                    spectator.send(&ServerMessage::Evaluation { ply, evaluation: &evaluation });
+                }
+            }
+            room.moves.len() != ply
+        };
+        if moved_on {
+            evaluate_for_spectators(&lobby, &game);
+        }
+    });
+}
+
+fn handle_message(lobby: &SharedLobby, client: &Client, membership: &mut Option<Membership>, text: &str) -> Result<(), String> {
+    let message: ClientMessage = serde_json::from_str(text).map_err(|e| format!("unreadable message: {}", e))?;
+    let game = {
+        let mut guard = lobby.lock().expect("a connection handler panicked");
+        match message {
+            ClientMessage::Join { .. } | ClientMessage::Spectate { .. } if membership.is_some() => {
+                return Err("this connection is already in a game".to_string());
+            }
+            ClientMessage::Join { game, name, players, token } => {
+                *membership = Some(join(&mut guard, client, &game, name, players, token)?);
+                game
+            }
+            ClientMessage::Spectate { game } => {
+                *membership = Some(spectate(&mut guard, client, &game)?);
+                game
+            }
+            ClientMessage::Move { notation } => {
+                let Some(Membership::Seat { game, seat }) = membership.clone() else {
+                    return Err("only seated players can move".to_string());
+                };
+                let room = guard.rooms.get_mut(&game).expect("a seated player's game exists");
+                room.play(seat, &notation)?;
+                game
+            }
+        }
+    };
+    evaluate_for_spectators(lobby, &game);
+    Ok(())
+}
+
+/// Takes a closed connection out of its game, and drops a finished game nobody is in.
+fn leave(lobby: &SharedLobby, client: &Client, membership: Option<Membership>) {
+    let mut guard = lobby.lock().expect("a connection handler panicked");
+    let game = match membership {
+        Some(Membership::Seat { game, seat }) => {
+            let Some(room) = guard.rooms.get_mut(&game) else { return };
+            // A newer connection may have taken the seat back already.
+            if room.seats[seat].client.as_ref().is_some_and(|held| held.id == client.id) {
+                room.seats[seat].client = None;
+                room.broadcast(&ServerMessage::Disconnected { seat });
+            }
+            game
+        }
+        Some(Membership::Spectator { game }) => {
+            let Some(room) = guard.rooms.get_mut(&game) else { return };
+            room.spectators.retain(|spectator| spectator.id != client.id);
+            game
+        }
+        None => return,
+    };
+    if guard.rooms.get(&game).is_some_and(|room| room.state.is_game_over() && room.clients().next().is_none()) {
+        guard.rooms.remove(&game);
+    }
+}
+
+fn serve(stream: TcpStream, lobby: SharedLobby, id: u64) -> Result<(), String> {
+    let mut socket = tungstenite::accept(stream).map_err(|e| format!("WebSocket handshake failed: {}", e))?;
+    socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)).map_err(|e| e.to_string())?;
+    let (outbox, inbox) = mpsc::channel();
+    let client = Client { id, outbox };
+    let mut membership = None;
+    let result = 'connection: loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                if let Err(message) = handle_message(&lobby, &client, &mut membership, &text) {
+                    client.send(&ServerMessage::Error { message: &message });
+                }
+            }
+            // Browsers often just drop the connection when a tab closes.
+            Ok(Message::Close(_))
+            | Err(tungstenite::Error::ConnectionClosed)
+            | Err(tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => break Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {}
+            Err(e) => break Err(e.to_string()),
+        }
+        for json in inbox.try_iter() {
+            if let Err(e) = socket.write(Message::Text(json)) {
+                break 'connection Err(e.to_string());
+            }
+        }
+        // Also sends the pongs that reading queued.
+        if let Err(e) = socket.flush() {
+            break Err(e.to_string());
+        }
+    };
+    leave(&lobby, &client, membership);
+    result
+}
+
+fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+    let listener = TcpListener::bind(&cli.bind)?;
+    println!("Hosting games on ws://{}.", cli.bind);
+    let lobby = Arc::new(Mutex::new(Lobby { rooms: HashMap::new(), eval_iterations: cli.eval_iterations }));
+    let next_id = AtomicU64::new(0);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Connection failed: {}", e);
+                continue;
+            }
+        };
+        let lobby = Arc::clone(&lobby);
+        let id = next_id.fetch_add(1, Ordering::Relaxed);
+        thread::spawn(move || {
+            if let Err(e) = serve(stream, lobby, id) {
+                eprintln!("Connection {} failed: {}", id, e);
+            }
+        });
+    }
+    Ok(())
+}
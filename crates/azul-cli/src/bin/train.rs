@@ -0,0 +1,780 @@
+use azul_ai::mcts_nn_ai::{shuffle_factories, INPUT_SIZE, POLICY_SIZE};
+use azul_ai::nn::{Dense, NeuralNetwork, WEIGHTS_EXTENSION};
+use azul_ai::training_io::{TrainingDataReader, SAMPLES_EXTENSION};
+use azul_ai::TrainingData;
+use clap::{Parser, ValueEnum};
+use rand::seq::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::borrow::{Borrow, Cow};
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tch::{nn, Device, Kind, Tensor};
+
+/// Fine-tunes the latest model on recent self-play data. Every setting can also come from a
+/// TOML file passed with --config; flags given on the command line override it.
+#[derive(Parser, Debug)]
+#[command(version, about = "Train the mctsnn network on self-play data", long_about = None)]
+struct Cli {
+    /// A TOML file of settings, with the same names as the flags in snake_case
+    /// (e.g. `learning_rate = 3e-4`).
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Passes over the replay buffer. [default: 10]
+    #[arg(long)]
+    epochs: Option<usize>,
+    /// Samples per optimizer step. [default: 64]
+    #[arg(long)]
+    batch_size: Option<usize>,
+    /// Adam learning rate. [default: 1e-4]
+    #[arg(long)]
+    learning_rate: Option<f64>,
+    /// Width of both hidden layers. Fine-tuning needs the size the loaded model was trained
+    /// with. [default: 256]
+    #[arg(long)]
+    hidden_size: Option<i64>,
+    /// Where self-play data is read from. [default: training_data]
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+    /// Where models are fine-tuned from and saved to. [default: training_models]
+    #[arg(long)]
+    models_dir: Option<PathBuf>,
+    /// How many of the newest self-play data files (generations) to train on. [default: 5]
+    #[arg(long)]
+    replay_window: Option<usize>,
+    /// Sampling weight of each generation relative to the next newer one: every epoch draws
+    /// all of the newest generation, this fraction of the one before it, and so on.
+    /// [default: 0.5]
+    #[arg(long)]
+    replay_decay: Option<f32>,
+    /// Where to train: cpu, cuda, cuda:N, mps, or auto (the first CUDA GPU, then Apple's MPS,
+    /// then the CPU). An unavailable GPU falls back to the CPU. [default: auto]
+    #[arg(long)]
+    device: Option<String>,
+    /// Shuffle the order of each training sample's factories, a different way every epoch,
+    /// to make more of the data. [default: true]
+    #[arg(long)]
+    augment: Option<bool>,
+    /// The fraction of each data file held out to validate on instead of training on.
+    /// [default: 0.1]
+    #[arg(long)]
+    validation_split: Option<f32>,
+    /// Save a checkpoint (weights and optimizer state) every this many epochs. The last epoch
+    /// always gets one. [default: 1]
+    #[arg(long)]
+    checkpoint_every: Option<usize>,
+    /// Weight of the auxiliary score-margin loss, which trains an extra head to predict the
+    /// final score difference. 0 turns it off. [default: 0.5]
+    #[arg(long)]
+    margin_weight: Option<f64>,
+    /// Which checkpoint becomes the new model: the last epoch's, or the one with the lowest
+    /// validation loss. [default: last]
+    #[arg(long, value_enum)]
+    select: Option<Selection>,
+    /// Continue the interrupted run for the next model version from its latest checkpoint,
+    /// instead of starting over from the latest model.
+    #[arg(long)]
+    resume: bool,
+}
+
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Selection {
+    Last,
+    Best,
+}
+
+/// The settings of a training run, from the --config file and the command line.
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct TrainConfig {
+    epochs: usize,
+    batch_size: usize,
+    learning_rate: f64,
+    hidden_size: i64,
+    data_dir: PathBuf,
+    models_dir: PathBuf,
+    replay_window: usize,
+    replay_decay: f32,
+    device: String,
+    augment: bool,
+    validation_split: f32,
+    checkpoint_every: usize,
+    margin_weight: f64,
+    select: Selection,
+}
+
+impl Default for TrainConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 10,
+            batch_size: 64,
+            learning_rate: 1e-4,
+            hidden_size: 256,
+            data_dir: PathBuf::from("training_data"),
+            models_dir: PathBuf::from("training_models"),
+            replay_window: 5,
+            replay_decay: 0.5,
+            device: "auto".to_string(),
+            augment: true,
+            validation_split: 0.1,
+            checkpoint_every: 1,
+            margin_weight: 0.5,
+            select: Selection::Last,
+        }
+    }
+}
+
+impl TrainConfig {
+    /// The --config file's settings (or the defaults), overridden by any flags given.
+    fn from_cli(cli: &Cli) -> anyhow::Result<Self> {
+        let mut config = match &cli.config {
+            Some(path) => {
+                let text = fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("Could not read {}: {}", path.display(), e))?;
+                toml::from_str(&text).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?
+            }
+            None => Self::default(),
+        };
+        if let Some(epochs) = cli.epochs { config.epochs = epochs; }
+        if let Some(batch_size) = cli.batch_size { config.batch_size = batch_size; }
+        if let Some(learning_rate) = cli.learning_rate { config.learning_rate = learning_rate; }
+        if let Some(hidden_size) = cli.hidden_size { config.hidden_size = hidden_size; }
+        if let Some(data_dir) = &cli.data_dir { config.data_dir = data_dir.clone(); }
+        if let Some(models_dir) = &cli.models_dir { config.models_dir = models_dir.clone(); }
+        if let Some(replay_window) = cli.replay_window { config.replay_window = replay_window; }
+        if let Some(replay_decay) = cli.replay_decay { config.replay_decay = replay_decay; }
+        if let Some(device) = &cli.device { config.device = device.clone(); }
+        if let Some(augment) = cli.augment { config.augment = augment; }
+        if let Some(validation_split) = cli.validation_split { config.validation_split = validation_split; }
+        if let Some(checkpoint_every) = cli.checkpoint_every { config.checkpoint_every = checkpoint_every; }
+        if let Some(margin_weight) = cli.margin_weight { config.margin_weight = margin_weight; }
+        if let Some(select) = cli.select { config.select = select; }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.epochs == 0 {
+            anyhow::bail!("epochs must be at least 1.");
+        }
+        if self.batch_size == 0 {
+            anyhow::bail!("batch_size must be at least 1.");
+        }
+        if self.learning_rate.is_nan() || self.learning_rate <= 0.0 {
+            anyhow::bail!("learning_rate must be positive.");
+        }
+        if self.hidden_size <= 0 {
+            anyhow::bail!("hidden_size must be at least 1.");
+        }
+        if self.replay_window == 0 {
+            anyhow::bail!("replay_window must be at least 1.");
+        }
+        if !(0.0..=1.0).contains(&self.replay_decay) {
+            anyhow::bail!("replay_decay must be between 0.0 and 1.0.");
+        }
+        if !(0.0..1.0).contains(&self.validation_split) {
+            anyhow::bail!("validation_split must be at least 0.0 and less than 1.0.");
+        }
+        if self.checkpoint_every == 0 {
+            anyhow::bail!("checkpoint_every must be at least 1.");
+        }
+        if self.margin_weight.is_nan() || self.margin_weight < 0.0 {
+            anyhow::bail!("margin_weight must be at least 0.0.");
+        }
+        Ok(())
+    }
+}
+
+/// The device `spec` names, falling back to the CPU if it isn't available here.
+fn select_device(spec: &str) -> anyhow::Result<Device> {
+    let cuda_count = if tch::Cuda::is_available() { tch::Cuda::device_count() as usize } else { 0 };
+    let requested = match spec.trim().to_ascii_lowercase().as_str() {
+        "auto" => {
+            return Ok(if cuda_count > 0 {
+                Device::Cuda(0)
+            } else if tch::utils::has_mps() {
+                Device::Mps
+            } else {
+                Device::Cpu
+            });
+        }
+        "cpu" => return Ok(Device::Cpu),
+        "mps" => Device::Mps,
+        "cuda" => Device::Cuda(0),
+        other => match other.strip_prefix("cuda:").and_then(|idx| idx.parse::<usize>().ok()) {
+            Some(idx) => Device::Cuda(idx),
+            None => anyhow::bail!("Unknown device '{}'. Use cpu, cuda, cuda:N, mps, or auto.", spec),
+        },
+    };
+    let available = match requested {
+        Device::Cuda(idx) => idx < cuda_count,
+        Device::Mps => tch::utils::has_mps(),
+        _ => true,
+    };
+    if available {
+        Ok(requested)
+    } else {
+        println!(
+            "Device '{}' is not available ({} CUDA devices found); falling back to the CPU.",
+            spec, cuda_count
+        );
+        Ok(Device::Cpu)
+    }
+}
+
+/// One self-play data file in the replay buffer.
+struct Generation {
+    samples: Vec<TrainingData>,
+    /// Held out from training, to measure how well the network generalizes.
+    validation: Vec<TrainingData>,
+    /// The fraction of `samples` drawn each epoch.
+    weight: f32,
+}
+
+impl Generation {
+    fn samples_per_epoch(&self) -> usize {
+        (self.weight * self.samples.len() as f32).round() as usize
+    }
+}
+
+// Seeds the validation split, so that a file splits the same way in every run.
+const VALIDATION_SEED: u64 = 0;
+
+/// The newest `window` data files in `dir`, newest first, weighted by `decay` per generation of
+/// age, with a `validation_split` fraction of each held out.
+fn load_replay_buffer(dir: &Path, window: usize, decay: f32, validation_split: f32) -> anyhow::Result<Vec<Generation>> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == SAMPLES_EXTENSION || ext == "json"))
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.metadata().unwrap().created().unwrap()));
+
+    let mut generations = Vec::new();
+    for (age, entry) in entries.into_iter().take(window).enumerate() {
+        let path = entry.path();
+        let reader = BufReader::new(File::open(&path)?);
+        // Data from before the binary format was added is JSON.
+        let all_samples: Vec<TrainingData> = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_reader(reader)?
+        } else {
+            TrainingDataReader::new(reader)?.collect::<std::io::Result<_>>()?
+        };
+        // Data recorded with an older input encoding or policy layout can't train this network.
+        let loaded = all_samples.len();
+        let samples: Vec<TrainingData> = all_samples.into_iter()
+            .filter(|d| d.state_input.len() == INPUT_SIZE && d.mcts_policy.len() == POLICY_SIZE)
+            .collect();
+        let usable = samples.len();
+        let mut split_rng = StdRng::seed_from_u64(VALIDATION_SEED);
+        let (validation, samples): (Vec<TrainingData>, Vec<TrainingData>) = samples.into_iter()
+            .partition(|_| split_rng.gen::<f32>() < validation_split);
+        let weight = decay.powi(age as i32);
+        println!(
+            "Loaded {} samples from {:?} (weight {:.2}, {} held out for validation).",
+            usable, path, weight, validation.len()
+        );
+        if usable < loaded {
+            println!("  Skipped {} samples recorded for a different network layout.", loaded - usable);
+        }
+        generations.push(Generation { samples, validation, weight });
+    }
+    Ok(generations)
+}
+
+/// One epoch's training samples: a fresh random subset of each generation, sized by its
+/// weight, all shuffled together. With `augment`, each sample's factories are shuffled too,
+/// differently every epoch.
+fn sample_epoch(generations: &[Generation], augment: bool) -> Vec<Cow<'_, TrainingData>> {
+    let mut rng = thread_rng();
+    let mut epoch: Vec<Cow<TrainingData>> = Vec::new();
+    for generation in generations {
+        for sample in generation.samples.choose_multiple(&mut rng, generation.samples_per_epoch()) {
+            epoch.push(if augment { Cow::Owned(shuffle_factories(sample, &mut rng)) } else { Cow::Borrowed(sample) });
+        }
+    }
+    epoch.shuffle(&mut rng);
+    epoch
+}
+
+#[derive(Debug)]
+struct Net {
+    fc1: nn::Linear,
+    fc2: nn::Linear,
+    policy_head: nn::Linear,
+    value_head: nn::Linear,
+    /// Predicts the final score margin. It only shapes the shared layers during training and
+    /// isn't exported.
+    margin_head: nn::Linear,
+}
+
+impl Net {
+    fn new(vs: &nn::Path, hidden_size: i64) -> Self {
+        let fc1 = nn::linear(vs / "fc1", INPUT_SIZE as i64, hidden_size, Default::default());
+        let fc2 = nn::linear(vs / "fc2", hidden_size, hidden_size, Default::default());
+        let policy_head = nn::linear(vs / "policy_head", hidden_size, POLICY_SIZE as i64, Default::default());
+        let value_head = nn::linear(vs / "value_head", hidden_size, 1, Default::default());
+        let margin_head = nn::linear(vs / "margin_head", hidden_size, 1, Default::default());
+        Self { fc1, fc2, policy_head, value_head, margin_head }
+    }
+
+    /// The policy logits, the value and the score margin.
+    fn forward(&self, xs: &Tensor) -> (Tensor, Tensor, Tensor) {
+        let xs = xs.apply(&self.fc1).relu().apply(&self.fc2).relu();
+        let policy = xs.apply(&self.policy_head);
+        let value = xs.apply(&self.value_head).tanh();
+        let margin = xs.apply(&self.margin_head).tanh();
+        (policy, value, margin)
+    }
+
+    /// The same network for plain-Rust inference, which is what MctsNnAI and the web app load.
+    fn export(&self) -> anyhow::Result<NeuralNetwork> {
+        let hidden = vec![export_layer(&self.fc1)?, export_layer(&self.fc2)?];
+        NeuralNetwork::from_layers(hidden, export_layer(&self.policy_head)?, export_layer(&self.value_head)?)
+            .map_err(anyhow::Error::msg)
+    }
+}
+
+fn export_layer(layer: &nn::Linear) -> anyhow::Result<Dense> {
+    let [output_size, input_size] = layer.ws.size()[..] else {
+        anyhow::bail!("Expected 2-D layer weights, found shape {:?}", layer.ws.size());
+    };
+    let flat: &[i64] = &[-1];
+    let weights = Vec::<f32>::try_from(&layer.ws.detach().to_device(Device::Cpu).reshape(flat))?;
+    let biases = match &layer.bs {
+        Some(bs) => Vec::<f32>::try_from(&bs.detach().to_device(Device::Cpu))?,
+        None => vec![0.0; output_size as usize],
+    };
+    Dense::from_parts(input_size as usize, output_size as usize, weights, biases).map_err(anyhow::Error::msg)
+}
+
+// Adam's hyperparameters besides the learning rate, PyTorch's defaults.
+const ADAM_BETA1: f64 = 0.9;
+const ADAM_BETA2: f64 = 0.999;
+const ADAM_EPSILON: f64 = 1e-8;
+
+/// The Adam optimizer, written out here because tch can't save its own optimizers' state:
+/// the moment estimates live in a `VarStore` of their own, which checkpoints save alongside
+/// the weights.
+struct Adam {
+    learning_rate: f64,
+    params: Vec<Tensor>,
+    first_moments: Vec<Tensor>,
+    second_moments: Vec<Tensor>,
+    /// Steps taken so far, as a scalar so that it is saved with the moments.
+    steps: Tensor,
+    state: nn::VarStore,
+}
+
+impl Adam {
+    fn new(vs: &nn::VarStore, learning_rate: f64) -> Self {
+        let mut variables: Vec<(String, Tensor)> = vs.variables().into_iter().collect();
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        let state = nn::VarStore::new(vs.device());
+        let root = state.root();
+        let mut params = Vec::new();
+        let mut first_moments = Vec::new();
+        let mut second_moments = Vec::new();
+        for (name, param) in variables {
+            // Variable names can't contain the path separator.
+            let name = name.replace('.', "_");
+            first_moments.push(root.zeros_no_train(&format!("m_{}", name), &param.size()));
+            second_moments.push(root.zeros_no_train(&format!("v_{}", name), &param.size()));
+            params.push(param);
+        }
+        let steps = root.zeros_no_train("steps", &[]);
+        Self { learning_rate, params, first_moments, second_moments, steps, state }
+    }
+
+    fn zero_grad(&mut self) {
+        for param in &mut self.params {
+            param.zero_grad();
+        }
+    }
+
+    fn step(&mut self) {
+        tch::no_grad(|| {
+            let steps = self.steps.double_value(&[]) + 1.0;
+            self.steps.fill_(steps);
+            let first_correction = 1.0 - ADAM_BETA1.powf(steps);
+            let second_correction = 1.0 - ADAM_BETA2.powf(steps);
+            let moments = self.first_moments.iter_mut().zip(self.second_moments.iter_mut());
+            for (param, (m, v)) in self.params.iter_mut().zip(moments) {
+                let grad = param.grad();
+                if !grad.defined() {
+                    continue;
+                }
+                m.copy_(&(&*m * ADAM_BETA1 + &grad * (1.0 - ADAM_BETA1)));
+                v.copy_(&(&*v * ADAM_BETA2 + &grad * &grad * (1.0 - ADAM_BETA2)));
+                let update = (&*m / first_correction) / ((&*v / second_correction).sqrt() + ADAM_EPSILON) * self.learning_rate;
+                param.g_sub_(&update);
+            }
+        });
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        Ok(self.state.save(path)?)
+    }
+
+    fn load(&mut self, path: &Path) -> anyhow::Result<()> {
+        Ok(self.state.load(path)?)
+    }
+}
+
+/// What has been saved of a run so far, kept as progress.json next to its checkpoints.
+#[derive(Serialize, Deserialize, Default, Debug)]
+struct RunProgress {
+    epochs_done: usize,
+    checkpoints: Vec<CheckpointInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CheckpointInfo {
+    epoch: usize,
+    /// The epoch's policy plus value loss on the validation samples, or on the training
+    /// samples if none are held out.
+    loss: f64,
+}
+
+/// The checkpoints of the run that trains model version `version`.
+struct CheckpointDir {
+    dir: PathBuf,
+}
+
+impl CheckpointDir {
+    fn new(models_dir: &Path, version: u32) -> Self {
+        Self { dir: models_dir.join("checkpoints").join(format!("azul_model_v{}", version)) }
+    }
+
+    fn weights_path(&self, epoch: usize) -> PathBuf {
+        self.dir.join(format!("epoch_{}.ot", epoch))
+    }
+
+    fn optimizer_path(&self, epoch: usize) -> PathBuf {
+        self.dir.join(format!("epoch_{}.adam.ot", epoch))
+    }
+
+    fn progress_path(&self) -> PathBuf {
+        self.dir.join("progress.json")
+    }
+
+    /// Opens metrics.csv, which gets a row per epoch: appended to when resuming, started over
+    /// otherwise.
+    fn open_metrics(&self, resume: bool) -> anyhow::Result<File> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join("metrics.csv");
+        let is_new = !resume || !path.exists();
+        let mut file = fs::OpenOptions::new().create(true).append(!is_new).write(true).truncate(is_new).open(&path)?;
+        if is_new {
+            writeln!(file, "epoch,{},{}", EpochStats::csv_header("train"), EpochStats::csv_header("validation"))?;
+        }
+        println!("Writing per-epoch metrics to {:?}.", path);
+        Ok(file)
+    }
+
+    fn load_progress(&self) -> anyhow::Result<Option<RunProgress>> {
+        match File::open(self.progress_path()) {
+            Ok(file) => Ok(Some(serde_json::from_reader(BufReader::new(file))?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, vs: &nn::VarStore, opt: &Adam, progress: &mut RunProgress, epoch: usize, loss: f64) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        vs.save(self.weights_path(epoch))?;
+        opt.save(&self.optimizer_path(epoch))?;
+        progress.epochs_done = epoch;
+        progress.checkpoints.push(CheckpointInfo { epoch, loss });
+        // Written last, so that it never lists a checkpoint that wasn't saved completely.
+        serde_json::to_writer_pretty(File::create(self.progress_path())?, progress)?;
+        Ok(())
+    }
+}
+
+// The policy dimension of a batch of network outputs.
+const POLICY_DIM: &[i64] = &[1];
+// Added to the logits of illegal moves, so that they get no probability but no NaNs either.
+const ILLEGAL_LOGIT_PENALTY: f64 = 1e9;
+
+/// The sample's legal-move mask; samples recorded without one count every move as legal.
+fn legal_mask(sample: &TrainingData) -> Vec<f32> {
+    if sample.legal_mask.len() == POLICY_SIZE {
+        sample.legal_mask.clone()
+    } else {
+        vec![1.0; POLICY_SIZE]
+    }
+}
+
+fn masked_log_softmax(logits: &Tensor, mask: &Tensor) -> Tensor {
+    (logits + (mask - 1.0) * ILLEGAL_LOGIT_PENALTY).log_softmax(-1, Kind::Float)
+}
+
+/// A batch of samples as tensors on the training device.
+struct Batch {
+    states: Tensor,
+    policies: Tensor,
+    outcomes: Tensor,
+    masks: Tensor,
+    /// The score margins, 0.0 for samples without one.
+    margins: Tensor,
+    /// 1.0 for the samples with a score margin.
+    has_margin: Tensor,
+}
+
+/// A batch's losses, each the mean over the batch.
+struct Losses {
+    /// The network's log-probabilities over the legal moves.
+    log_probs: Tensor,
+    /// Cross-entropy against the visit distribution.
+    policy: Tensor,
+    /// Squared error against the outcome.
+    value: Tensor,
+    /// Squared error against the score margin, over the samples that have one.
+    margin: Tensor,
+}
+
+impl Batch {
+    fn new<T: Borrow<TrainingData>>(samples: &[T], device: Device) -> Self {
+        let samples: Vec<&TrainingData> = samples.iter().map(Borrow::borrow).collect();
+        let stack = |rows: Vec<Tensor>| Tensor::stack(&rows, 0).to_device(device);
+        Self {
+            states: stack(samples.iter().map(|d| Tensor::from_slice(&d.state_input)).collect()),
+            policies: stack(samples.iter().map(|d| Tensor::from_slice(&d.mcts_policy)).collect()),
+            outcomes: stack(samples.iter().map(|d| Tensor::from_slice(&[d.outcome])).collect()),
+            masks: stack(samples.iter().map(|d| Tensor::from_slice(&legal_mask(d))).collect()),
+            margins: stack(samples.iter().map(|d| Tensor::from_slice(&[d.score_margin.unwrap_or(0.0)])).collect()),
+            has_margin: stack(samples.iter().map(|d| Tensor::from_slice(&[d.score_margin.map_or(0.0f32, |_| 1.0)])).collect()),
+        }
+    }
+
+    fn losses(&self, net: &Net) -> Losses {
+        let (policy_logits, value_pred, margin_pred) = net.forward(&self.states);
+        let log_probs = masked_log_softmax(&policy_logits, &self.masks);
+        let policy = -(&self.policies * &log_probs).sum_dim_intlist(POLICY_DIM, false, Kind::Float).mean(Kind::Float);
+        let value = value_pred.mse_loss(&self.outcomes, tch::Reduction::Mean);
+        let margin_errors = (margin_pred - &self.margins) * &self.has_margin;
+        let margin = (&margin_errors * &margin_errors).sum(Kind::Float) / self.has_margin.sum(Kind::Float).clamp_min(1.0);
+        Losses { log_probs, policy, value, margin }
+    }
+}
+
+/// The network's losses and policy quality on `samples`, without training on them.
+fn validate(net: &Net, samples: &[&TrainingData], batch_size: usize, device: Device) -> EpochStats {
+    let mut stats = EpochStats::default();
+    tch::no_grad(|| {
+        for chunk in samples.chunks(batch_size) {
+            let batch = Batch::new(chunk, device);
+            stats.add(&batch, &batch.losses(net));
+        }
+    });
+    stats
+}
+
+/// Losses and policy quality over an epoch, averaged per sample.
+#[derive(Default)]
+struct EpochStats {
+    samples: f64,
+    policy_loss: f64,
+    value_loss: f64,
+    /// Samples with a score margin, which the margin loss is averaged over.
+    margin_samples: f64,
+    margin_loss: f64,
+    /// Samples whose most likely move is the most visited one.
+    matches: f64,
+    kl: f64,
+}
+
+impl EpochStats {
+    /// The mean policy plus value loss. The margin loss is left out, since the margin head
+    /// isn't part of the exported model.
+    fn loss(&self) -> f64 {
+        (self.policy_loss + self.value_loss) / self.samples.max(1.0)
+    }
+
+    fn csv_header(set: &str) -> String {
+        ["policy_loss", "value_mse", "margin_mse", "policy_accuracy", "kl"].map(|metric| format!("{}_{}", set, metric)).join(",")
+    }
+
+    /// The means in `csv_header`'s order, or empty fields if there were no samples.
+    fn csv_fields(&self) -> String {
+        if self.samples == 0.0 {
+            return ",,,,".to_string();
+        }
+        let n = self.samples;
+        let margin = if self.margin_samples > 0.0 { (self.margin_loss / self.margin_samples).to_string() } else { String::new() };
+        format!("{},{},{},{},{}", self.policy_loss / n, self.value_loss / n, margin, self.matches / n, self.kl / n)
+    }
+
+    fn add(&mut self, batch: &Batch, losses: &Losses) {
+        let target = &batch.policies;
+        let size = target.size()[0] as f64;
+        self.samples += size;
+        self.policy_loss += losses.policy.double_value(&[]) * size;
+        self.value_loss += losses.value.double_value(&[]) * size;
+        let with_margin = batch.has_margin.sum(Kind::Float).double_value(&[]);
+        self.margin_samples += with_margin;
+        self.margin_loss += losses.margin.double_value(&[]) * with_margin;
+        self.matches += losses.log_probs.argmax(1, false).eq_tensor(&target.argmax(1, false))
+            .to_kind(Kind::Float).sum(Kind::Float).double_value(&[]);
+        // KL(target || prediction): the cross-entropy minus the target's own entropy.
+        let target_entropy = -(target * (target + 1e-12).log()).sum_dim_intlist(POLICY_DIM, false, Kind::Float).mean(Kind::Float);
+        self.kl += (&losses.policy - target_entropy).double_value(&[]) * size;
+    }
+}
+
+impl std::fmt::Display for EpochStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n = self.samples.max(1.0);
+        write!(
+            f,
+            "policy loss {:.4}, value MSE {:.4}, policy accuracy {:.1}%, KL {:.4}",
+            self.policy_loss / n, self.value_loss / n, 100.0 * self.matches / n, self.kl / n
+        )?;
+        if self.margin_samples > 0.0 {
+            write!(f, ", margin MSE {:.4}", self.margin_loss / self.margin_samples)?;
+        }
+        Ok(())
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let config = TrainConfig::from_cli(&cli)?;
+    let device = select_device(&config.device)?;
+
+    // --- 1. Load Data ---
+    let data_dir = &config.data_dir;
+    fs::create_dir_all(data_dir)?;
+
+    let generations = load_replay_buffer(data_dir, config.replay_window, config.replay_decay, config.validation_split)?;
+    let samples_per_epoch: usize = generations.iter().map(Generation::samples_per_epoch).sum();
+    let validation: Vec<&TrainingData> = generations.iter().flat_map(|g| &g.validation).collect();
+    println!(
+        "Replay buffer: {} generations, {} samples per epoch, {} validation samples.",
+        generations.len(), samples_per_epoch, validation.len()
+    );
+
+    if samples_per_epoch == 0 {
+        println!("No training data found in {:?}. Run headless in --self-play mode to generate data.", data_dir);
+        return Ok(());
+    }
+
+    // --- 2. Set up Model and Optimizer ---
+    println!("Training on {:?}.", device);
+    let mut vs = nn::VarStore::new(device);
+    let net = Net::new(&vs.root(), config.hidden_size);
+
+    // --- MODIFIED SECTION: Fine-tuning Logic ---
+    let training_models_dir = &config.models_dir;
+    fs::create_dir_all(training_models_dir)?;
+
+    let latest_model = fs::read_dir(training_models_dir)?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "ot"))
+        .max_by_key(|entry| entry.metadata().unwrap().created().unwrap());
+
+    let mut next_version = 1;
+    if let Some(entry) = &latest_model {
+        if let Some(stem) = entry.path().file_stem().and_then(|s| s.to_str()) {
+            if let Some(version_str) = stem.strip_prefix("azul_model_v") {
+                if let Ok(version) = version_str.parse::<u32>() {
+                    next_version = version + 1;
+                }
+            }
+        }
+    }
+    let mut opt = Adam::new(&vs, config.learning_rate);
+    let checkpoints = CheckpointDir::new(training_models_dir, next_version);
+
+    let mut progress = RunProgress::default();
+    if cli.resume {
+        progress = checkpoints.load_progress()?
+            .filter(|progress| !progress.checkpoints.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("No checkpoints to resume in {:?}.", checkpoints.dir))?;
+        let epoch = progress.epochs_done;
+        println!("Resuming the training of v{} after epoch {}.", next_version, epoch);
+        vs.load(checkpoints.weights_path(epoch))?;
+        opt.load(&checkpoints.optimizer_path(epoch))?;
+    } else {
+        if checkpoints.progress_path().exists() {
+            println!("Starting over; the checkpoints in {:?} will be overwritten. Pass --resume to continue them.", checkpoints.dir);
+        }
+        if let Some(entry) = &latest_model {
+            let path = entry.path();
+            println!("Loading model for fine-tuning: {:?}", path);
+            // Models from before a head was added don't have it; it starts out untrained.
+            let missing = vs.load_partial(&path)?;
+            if !missing.is_empty() {
+                println!("  Initialized {} new variables: {}", missing.len(), missing.join(", "));
+            }
+        } else {
+            println!("No existing model found in {:?}. Training a new model (v1) from scratch.", training_models_dir);
+        }
+    }
+    // --- END MODIFIED SECTION ---
+
+    // --- 3. Training Loop ---
+    let mut metrics = checkpoints.open_metrics(cli.resume)?;
+    let epochs = config.epochs;
+    let batch_size = config.batch_size;
+    println!(
+        "Starting training for {} epochs (batch size {}, learning rate {}, hidden size {})...",
+        epochs, batch_size, config.learning_rate, config.hidden_size
+    );
+
+    for epoch in progress.epochs_done + 1..=epochs {
+        let mut stats = EpochStats::default();
+        let data = sample_epoch(&generations, config.augment);
+        for chunk in data.chunks(batch_size) {
+            let batch = Batch::new(chunk, vs.device());
+            let losses = batch.losses(&net);
+            let total_loss = &losses.value + &losses.policy + &losses.margin * config.margin_weight;
+
+            opt.zero_grad();
+            total_loss.backward();
+            opt.step();
+
+            tch::no_grad(|| stats.add(&batch, &losses));
+        }
+        let validation_stats = validate(&net, &validation, batch_size, vs.device());
+        println!("Epoch {} complete.\n  Training:   {}", epoch, stats);
+        let loss = if validation.is_empty() {
+            stats.loss()
+        } else {
+            println!("  Validation: {}", validation_stats);
+            validation_stats.loss()
+        };
+        writeln!(metrics, "{},{},{}", epoch, stats.csv_fields(), validation_stats.csv_fields())?;
+        metrics.flush()?;
+        if epoch % config.checkpoint_every == 0 || epoch == epochs {
+            checkpoints.save(&vs, &opt, &mut progress, epoch, loss)?;
+        }
+    }
+
+    if config.select == Selection::Best {
+        let best = progress.checkpoints.iter()
+            .min_by(|a, b| a.loss.total_cmp(&b.loss))
+            .ok_or_else(|| anyhow::anyhow!("No checkpoints to select from."))?;
+        println!("Selected the checkpoint of epoch {} (loss {:.4}).", best.epoch, best.loss);
+        if best.epoch != progress.epochs_done {
+            vs.load(checkpoints.weights_path(best.epoch))?;
+        }
+    }
+
+    // --- 4. Save Model ---
+    let new_training_model_path = training_models_dir.join(format!("azul_model_v{}.ot", next_version));
+    let new_weights_path = training_models_dir.join(format!("azul_model_v{}.{}", next_version, WEIGHTS_EXTENSION));
+
+    // Save the new versioned model for continued training, and its weights for playing.
+    vs.save(&new_training_model_path)?;
+    let weights = net.export()?.to_bytes();
+    fs::write(&new_weights_path, &weights)?;
+    println!("Training complete. New version saved to {:?} (weights: {:?})", new_training_model_path, new_weights_path);
+    println!("Run the gate binary to promote it to release_models/ if it beats the current release model.");
+
+    Ok(())
+}
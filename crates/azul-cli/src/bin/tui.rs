@@ -0,0 +1,452 @@
+use azul_ai::mcts_heuristic_ai::{MctsHeuristicAI, VALUE_SCALE};
+use azul_ai::mcts_lib::MctsConfig;
+use azul_ai::registry::{AgentRegistry, AgentSpec};
+use azul_ai::AIAgent;
+use azul_cli::cli_human_agent::{describe_move, describe_scoring_event};
+use azul_cli::external_agent::register_external_agent;
+use azul_core::{GamePhase, GameState, Move, MoveDestination, MoveSource, PlayerBoard, Tile, Winner, ALL_COLORS, WALL_LAYOUT};
+use clap::Parser;
+use ratatui::crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use ratatui::crossterm::execute;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Plays Azul in a full-screen terminal UI: pick moves with the keyboard or the mouse against
+/// any registered agents, with an evaluation bar from a background MCTS analysis.
+#[derive(Parser, Debug)]
+#[command(version, about = "Play Azul in a terminal UI", long_about = None)]
+struct Cli {
+    /// Agent for each seat, in order; human seats are played from the keyboard and mouse.
+    #[arg(short, long, num_args = 1.., value_delimiter = ' ', default_values_t = ["human".to_string(), "difficulty:medium".to_string()])]
+    players: Vec<String>,
+    /// MCTS iterations behind the evaluation bar, run in the background after every move; 0
+    /// turns it off.
+    #[arg(long, default_value_t = 3000)]
+    analysis_iterations: u32,
+}
+
+/// Width of one tile on screen.
+const TILE_WIDTH: u16 = 3;
+/// The label in front of each factory's tiles, e.g. "F1 ".
+const FACTORY_LABEL_WIDTH: u16 = 4;
+
+fn tile_style(tile: Tile) -> Style {
+    let (bg, fg) = match tile {
+        Tile::Blue => (Color::Blue, Color::White),
+        Tile::Yellow => (Color::Yellow, Color::Black),
+        Tile::Red => (Color::Red, Color::White),
+        Tile::Black => (Color::DarkGray, Color::White),
+        Tile::White => (Color::White, Color::Black),
+    };
+    Style::default().bg(bg).fg(fg).add_modifier(Modifier::BOLD)
+}
+
+fn tile_letter(tile: Tile) -> char {
+    match tile {
+        Tile::Blue => 'B',
+        Tile::Yellow => 'Y',
+        Tile::Red => 'R',
+        Tile::Black => 'K',
+        Tile::White => 'W',
+    }
+}
+
+fn tile_span(tile: Tile, selected: bool) -> Span<'static> {
+    let style = if selected { tile_style(tile).add_modifier(Modifier::UNDERLINED | Modifier::REVERSED) } else { tile_style(tile) };
+    Span::styled(format!(" {} ", tile_letter(tile)), style)
+}
+
+/// The rows of a player board: pattern lines beside the wall, then the floor line.
+fn board_lines(board: &PlayerBoard, highlight: Option<&MoveDestination>) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for (row, line) in board.pattern_lines.iter().enumerate() {
+        let capacity = row + 1;
+        let targeted = highlight == Some(&MoveDestination::PatternLine(row));
+        let empty_style = if targeted { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() };
+        let mut spans = vec![Span::raw("   ".repeat(5 - capacity))];
+        spans.extend((line.len()..capacity).map(|_| Span::styled(" _ ", empty_style)));
+        spans.extend(line.iter().map(|&tile| tile_span(tile, false)));
+        spans.push(Span::raw(" │ "));
+        for (col, slot) in board.wall[row].iter().enumerate() {
+            spans.push(match slot {
+                Some(tile) => tile_span(*tile, false),
+                None => Span::styled(
+                    format!(" {} ", tile_letter(WALL_LAYOUT[row][col]).to_ascii_lowercase()),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            });
+        }
+        lines.push(Line::from(spans));
+    }
+    let targeted = highlight == Some(&MoveDestination::Floor);
+    let mut floor = vec![Span::styled("Floor:", if targeted { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() })];
+    floor.push(Span::raw(" "));
+    if board.has_first_player_marker {
+        floor.push(Span::styled("[1]", Style::default().add_modifier(Modifier::BOLD)));
+    }
+    floor.extend(board.floor_line.iter().map(|&tile| tile_span(tile, false)));
+    floor.push(Span::raw(format!("  (-{})", board.floor_penalty())));
+    lines.push(Line::from(floor));
+    lines
+}
+
+/// The latest evaluation: the player to move's expected margin over the best opponent, in
+/// points.
+struct Evaluation {
+    player: usize,
+    points: f32,
+}
+
+struct App {
+    game: GameState,
+    seat_names: Vec<String>,
+    /// `None` for a human seat.
+    agents: Vec<Option<Box<dyn AIAgent>>>,
+    /// The move being built: a source, then a color, then a destination.
+    source: Option<MoveSource>,
+    tile: Option<Tile>,
+    /// Which of the moves matching the selection Left/Right has picked.
+    cursor: usize,
+    /// Where each factory's line (then the center's) was drawn, for mouse clicks.
+    factories_area: Rect,
+    /// Where each human seat's board was drawn.
+    board_areas: Vec<Rect>,
+    log: Vec<String>,
+    analysis_iterations: u32,
+    evaluation: Option<Evaluation>,
+    analysis: Option<Receiver<Evaluation>>,
+    round: usize,
+    quit: bool,
+}
+
+impl App {
+    fn is_human_turn(&self) -> bool {
+        !self.game.is_game_over() && self.agents[self.game.current_player_idx].is_none()
+    }
+
+    /// The legal moves that match the selection so far.
+    fn candidates(&self) -> Vec<Move> {
+        self.game.get_legal_moves().into_iter()
+            .filter(|m| self.source.as_ref().is_none_or(|source| &m.source == source))
+            .filter(|m| self.tile.is_none_or(|tile| m.tile == tile))
+            .collect()
+    }
+
+    fn proposed(&self) -> Option<Move> {
+        let candidates = self.candidates();
+        (!candidates.is_empty()).then(|| candidates[self.cursor % candidates.len()].clone())
+    }
+
+    fn clear_selection(&mut self) {
+        self.source = None;
+        self.tile = None;
+        self.cursor = 0;
+    }
+
+    /// Starts analyzing the current position in the background, replacing any older analysis.
+    fn start_analysis(&mut self) {
+        self.analysis = None;
+        if self.analysis_iterations == 0 || self.game.phase() != GamePhase::Drafting {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let (state, iterations) = (self.game.clone(), self.analysis_iterations);
+        thread::spawn(move || {
+            // The endgame solver gives no search value to show.
+            let mut engine = MctsHeuristicAI::new(iterations).with_config(MctsConfig { endgame_nodes: 0, ..MctsConfig::default() });
+            engine.get_move(&state);
+            if let Some(value) = engine.root_value() {
+                // The receiver is gone if the position changed meanwhile.
+                sender.send(Evaluation { player: state.current_player_idx, points: value * VALUE_SCALE }).ok();
+            }
+        });
+        self.analysis = Some(receiver);
+    }
+
+    fn play(&mut self, chosen: Move) {
+        let player = self.game.current_player_idx;
+        if let Err(e) = self.game.advance(Some(&chosen)) {
+            self.log.push(format!("Player {} can't play {}: {}", player + 1, chosen, e));
+            return;
+        }
+        self.log.push(format!("Player {} ({}): {}", player + 1, self.seat_names[player], describe_move(&chosen)));
+        self.clear_selection();
+        if self.game.phase() == GamePhase::Tiling {
+            self.log.push(format!("--- Round {} scoring ---", self.round));
+            let events = self.game.advance(None).expect("the drafting phase is over");
+            self.log.extend(events.iter().map(describe_scoring_event));
+            self.round += 1;
+            if self.game.is_game_over() {
+                self.log.push(match self.game.winner() {
+                    Winner::Single(winner) => format!("Game over: Player {} ({}) wins!", winner + 1, self.seat_names[winner]),
+                    Winner::Shared(winners) => {
+                        let names: Vec<String> = winners.iter().map(|i| format!("Player {}", i + 1)).collect();
+                        format!("Game over: shared victory between {}.", names.join(" and "))
+                    }
+                });
+            } else {
+                self.log.push(format!("--- Round {} ---", self.round));
+            }
+        }
+        self.start_analysis();
+    }
+
+    /// Plays the selected move if it is legal and complete with `destination`.
+    fn choose_destination(&mut self, destination: MoveDestination) {
+        let chosen = self.candidates().into_iter().find(|m| m.destination == destination && self.tile.is_some());
+        match chosen {
+            Some(chosen) => self.play(chosen),
+            None => self.log.push("Pick a source and a color that can go there first.".to_string()),
+        }
+    }
+
+    fn choose_source(&mut self, source: MoveSource) {
+        if self.game.get_legal_moves().iter().any(|m| m.source == source) {
+            self.source = Some(source);
+            self.tile = None;
+            self.cursor = 0;
+        }
+    }
+
+    fn choose_tile(&mut self, tile: Tile) {
+        if self.source.is_some() && self.candidates().iter().any(|m| m.tile == tile) {
+            self.tile = Some(tile);
+            self.cursor = 0;
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') => self.quit = true,
+            _ if !self.is_human_turn() => {}
+            KeyCode::Esc => self.clear_selection(),
+            KeyCode::Char('c') => self.choose_source(MoveSource::Center),
+            KeyCode::Char('f') => self.choose_destination(MoveDestination::Floor),
+            KeyCode::Char(digit @ '1'..='9') => {
+                let idx = digit as usize - '1' as usize;
+                if self.tile.is_some() {
+                    self.choose_destination(MoveDestination::PatternLine(idx));
+                } else if idx < self.game.factories.len() {
+                    self.choose_source(MoveSource::Factory(idx));
+                }
+            }
+            KeyCode::Char(letter) => {
+                if let Some(&tile) = ALL_COLORS.iter().find(|&&tile| tile_letter(tile) == letter.to_ascii_uppercase()) {
+                    self.choose_tile(tile);
+                }
+            }
+            KeyCode::Right | KeyCode::Tab => self.cursor += 1,
+            KeyCode::Left | KeyCode::BackTab => {
+                let count = self.candidates().len().max(1);
+                self.cursor = (self.cursor + count - 1) % count;
+            }
+            KeyCode::Enter => {
+                if let Some(chosen) = self.proposed() {
+                    self.play(chosen);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_click(&mut self, column: u16, row: u16) {
+        if !self.is_human_turn() {
+            return;
+        }
+        let inside = |area: Rect| column > area.x && column < area.right() - 1 && row > area.y && row < area.bottom() - 1;
+        let factories = self.factories_area;
+        if inside(factories) {
+            let line = (row - factories.y - 1) as usize;
+            let (source, tiles) = if line < self.game.factories.len() {
+                (MoveSource::Factory(line), self.game.factories[line].clone())
+            } else if line == self.game.factories.len() {
+                (MoveSource::Center, self.game.center.clone())
+            } else {
+                return;
+            };
+            let offset = column - factories.x - 1;
+            if offset >= FACTORY_LABEL_WIDTH {
+                if let Some(&tile) = tiles.get(((offset - FACTORY_LABEL_WIDTH) / TILE_WIDTH) as usize) {
+                    self.choose_source(source);
+                    self.choose_tile(tile);
+                }
+            }
+            return;
+        }
+        let player = self.game.current_player_idx;
+        let board = self.board_areas[player];
+        if inside(board) {
+            match (row - board.y - 1) as usize {
+                line @ 0..=4 => self.choose_destination(MoveDestination::PatternLine(line)),
+                5 => self.choose_destination(MoveDestination::Floor),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [top, boards, bottom] = Layout::vertical([
+            Constraint::Length(self.game.factories.len() as u16 + 3),
+            Constraint::Length(8),
+            Constraint::Min(6),
+        ]).areas(frame.area());
+        let [factories_area, evaluation_area] = Layout::horizontal([Constraint::Min(40), Constraint::Length(30)]).areas(top);
+
+        let proposed = if self.is_human_turn() { self.proposed() } else { None };
+        let mut lines: Vec<Line> = Vec::new();
+        let sources = self.game.factories.iter().enumerate()
+            .map(|(i, tiles)| (MoveSource::Factory(i), format!("F{:<2} ", i + 1), tiles))
+            .chain(std::iter::once((MoveSource::Center, "C   ".to_string(), &self.game.center)));
+        for (source, label, tiles) in sources {
+            let picked = self.source.as_ref() == Some(&source);
+            let mut spans = vec![Span::styled(label, if picked { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() })];
+            spans.extend(tiles.iter().map(|&tile| tile_span(tile, picked && self.tile == Some(tile))));
+            if source == MoveSource::Center && self.game.first_player_marker_in_center {
+                spans.push(Span::styled(" [1]", Style::default().add_modifier(Modifier::BOLD)));
+            }
+            lines.push(Line::from(spans));
+        }
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Factories ")), factories_area);
+        self.factories_area = factories_area;
+
+        let (ratio, label) = match &self.evaluation {
+            Some(evaluation) => (
+                ((evaluation.points / VALUE_SCALE).clamp(-1.0, 1.0) as f64 + 1.0) / 2.0,
+                format!("Player {} {:+.1}", evaluation.player + 1, evaluation.points),
+            ),
+            None => (0.5, if self.analysis.is_some() { "analyzing...".to_string() } else { "-".to_string() }),
+        };
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(" Evaluation "))
+            .gauge_style(Style::default().fg(Color::Green).bg(Color::Red))
+            .ratio(ratio)
+            .label(label);
+        frame.render_widget(gauge, evaluation_area);
+
+        let board_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, self.game.players.len() as u32); self.game.players.len()])
+            .split(boards);
+        self.board_areas = board_areas.to_vec();
+        for (i, board) in self.game.players.iter().enumerate() {
+            let to_move = i == self.game.current_player_idx && !self.game.is_game_over();
+            let highlight = if to_move { proposed.as_ref().map(|m| &m.destination) } else { None };
+            let title = format!(" Player {} ({}): {} {}", i + 1, self.seat_names[i], board.score, if to_move { "◀ " } else { "" });
+            let style = if to_move { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() };
+            let block = Block::default().borders(Borders::ALL).title(Span::styled(title, style));
+            frame.render_widget(Paragraph::new(board_lines(board, highlight)).block(block), board_areas[i]);
+        }
+
+        let [status_area, log_area] = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(bottom);
+        let status = if self.game.is_game_over() {
+            vec![Line::from("The game is over. Press q to quit.")]
+        } else if self.is_human_turn() {
+            let mut status = vec![
+                Line::from("1-9/c: factory or center   b y r k w: color   1-5/f: line or floor"),
+                Line::from("←/→: cycle moves   Enter: play   Esc: clear   q: quit   (or click a tile, then a line)"),
+                Line::from(""),
+            ];
+            if let Some(m) = &proposed {
+                status.push(Line::from(vec![Span::raw("Move: "), Span::styled(format!("{} [{}]", describe_move(m), m), Style::default().add_modifier(Modifier::BOLD))]));
+            }
+            status
+        } else {
+            let player = self.game.current_player_idx;
+            vec![Line::from(format!("Player {} ({}) is thinking…", player + 1, self.seat_names[player]))]
+        };
+        frame.render_widget(Paragraph::new(status).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title(" Move ")), status_area);
+
+        let visible = log_area.height.saturating_sub(2) as usize;
+        let log: Vec<Line> = self.log.iter().skip(self.log.len().saturating_sub(visible)).map(|line| Line::from(line.as_str())).collect();
+        frame.render_widget(Paragraph::new(log).block(Block::default().borders(Borders::ALL).title(" Log ")), log_area);
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    app.start_analysis();
+    while !app.quit {
+        if let Some(evaluation) = app.analysis.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            app.evaluation = Some(evaluation);
+            app.analysis = None;
+        }
+        terminal.draw(|frame| app.draw(frame))?;
+        if !app.game.is_game_over() && !app.is_human_turn() {
+            // Engines think on this thread, after the frame saying so has been drawn.
+            let player = app.game.current_player_idx;
+            let agent = app.agents[player].as_mut().expect("an engine seat has an agent");
+            match agent.get_move(&app.game) {
+                Some(chosen) => app.play(chosen),
+                None => {
+                    app.log.push(format!("Player {} has no move. Stopping the game.", player + 1));
+                    app.agents[player] = None;
+                }
+            }
+            continue;
+        }
+        if event::poll(Duration::from_millis(100))? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key.code),
+                Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => app.handle_click(mouse.column, mouse.row),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if !(2..=4).contains(&cli.players.len()) {
+        eprintln!("Error: The number of players must be between 2 and 4.");
+        std::process::exit(1);
+    }
+    let mut registry = AgentRegistry::new();
+    register_external_agent(&mut registry);
+    let mut agents: Vec<Option<Box<dyn AIAgent>>> = Vec::new();
+    for spec in &cli.players {
+        let parsed = spec.parse::<AgentSpec>().and_then(|parsed| match parsed.name.as_str() {
+            "human" => Ok(None),
+            _ => registry.create(&parsed).map(Some),
+        });
+        match parsed {
+            Ok(agent) => agents.push(agent),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut app = App {
+        game: GameState::new(cli.players.len()),
+        board_areas: vec![Rect::default(); cli.players.len()],
+        seat_names: cli.players,
+        agents,
+        source: None,
+        tile: None,
+        cursor: 0,
+        factories_area: Rect::default(),
+        log: vec!["--- Round 1 ---".to_string()],
+        analysis_iterations: cli.analysis_iterations,
+        evaluation: None,
+        analysis: None,
+        round: 1,
+        quit: false,
+    };
+    let mut terminal = ratatui::init();
+    execute!(io::stdout(), EnableMouseCapture).ok();
+    let result = run(&mut terminal, &mut app);
+    execute!(io::stdout(), DisableMouseCapture).ok();
+    ratatui::restore();
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
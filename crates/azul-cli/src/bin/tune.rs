@@ -0,0 +1,166 @@
+use azul_ai::{
+    heuristic_ai::{HeuristicAI, HeuristicWeights},
+    AIAgent,
+};
+use azul_core::{GamePhase, GameState};
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::num::NonZeroUsize;
+use std::thread;
+use std::time::Instant;
+
+/// Tunes HeuristicAI's weights with SPSA (simultaneous perturbation stochastic approximation).
+/// Each iteration perturbs every weight at once in a random direction, plays the weights nudged
+/// one way against the weights nudged the other way, and steps toward whichever side won by
+/// more points.
+#[derive(Parser, Debug)]
+#[command(version, about = "Tune HeuristicAI weights by self-play", long_about = None)]
+struct Cli {
+    /// Number of SPSA iterations.
+    #[arg(short, long, default_value_t = 100)]
+    iterations: u32,
+    /// Game pairs per iteration. Each pair plays the same seed twice with the seats swapped.
+    #[arg(short, long, default_value_t = 20)]
+    pairs: u32,
+    /// Weights to start from (JSON). Defaults to the built-in weights.
+    #[arg(long)]
+    start: Option<String>,
+    /// Where to write the tuned weights.
+    #[arg(short, long, default_value = "tuned_weights.json")]
+    output: String,
+    /// Seed for the perturbations and the game seeds, so a run can be repeated.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Initial step size, relative to each starting weight.
+    #[arg(long, default_value_t = 0.5)]
+    step: f32,
+    /// Initial perturbation size, relative to each starting weight.
+    #[arg(long, default_value_t = 0.2)]
+    perturbation: f32,
+    /// Play the current weights against the starting weights every this many iterations.
+    #[arg(long, default_value_t = 10)]
+    eval_every: u32,
+    /// Threads for playing games in parallel. Defaults to one per core.
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+}
+
+// Standard SPSA gain schedules: a_k = a / (k + 1 + A)^0.602, c_k = c / (k + 1)^0.101.
+const STEP_DECAY: f32 = 0.602;
+const PERTURBATION_DECAY: f32 = 0.101;
+const STABILITY_OFFSET: f32 = 10.0;
+/// Score margins are divided by this to keep the update on the scale of the weights.
+const MARGIN_SCALE: f32 = 100.0;
+
+fn weights_to_vec(w: &HeuristicWeights) -> Vec<f32> {
+    vec![w.floor_tile_penalty, w.tile_placed, w.line_completion, w.adjacency, w.column_progress]
+}
+
+fn vec_to_weights(v: &[f32]) -> HeuristicWeights {
+    HeuristicWeights {
+        floor_tile_penalty: v[0],
+        tile_placed: v[1],
+        line_completion: v[2],
+        adjacency: v[3],
+        column_progress: v[4],
+    }
+}
+
+/// Plays one 2-player game and returns seat 0's score minus seat 1's.
+fn play_game(seat_weights: [&HeuristicWeights; 2], seed: u64) -> f32 {
+    let mut agents = seat_weights.map(|w| HeuristicAI::with_weights(w.clone()));
+    let mut game = GameState::new_with_seed(2, seed);
+    while game.phase() != GamePhase::GameOver {
+        let next_move = match game.phase() {
+            GamePhase::Drafting => match agents[game.current_player_idx].get_move(&game) {
+                Some(m) => Some(m),
+                None => break,
+            },
+            _ => None,
+        };
+        if game.advance(next_move.as_ref()).is_err() {
+            break;
+        }
+    }
+    game.players[0].score as f32 - game.players[1].score as f32
+}
+
+/// The average margin of `a` over `b` across `seeds`, each played from both seats, spread over
+/// `threads` threads.
+fn match_margin(a: &HeuristicWeights, b: &HeuristicWeights, seeds: &[u64], threads: usize) -> f32 {
+    let chunk_size = seeds.len().div_ceil(threads).max(1);
+    let total: f32 = thread::scope(|scope| {
+        let handles: Vec<_> = seeds.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || {
+                chunk.iter().map(|&seed| play_game([a, b], seed) - play_game([b, a], seed)).sum::<f32>()
+            }))
+            .collect();
+        handles.into_iter().map(|h| h.join().expect("a game thread panicked")).sum()
+    });
+    total / (2 * seeds.len()) as f32
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let start = match &cli.start {
+        Some(path) => HeuristicWeights::from_json_file(path).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }),
+        None => HeuristicWeights::default(),
+    };
+    let start_vec = weights_to_vec(&start);
+    // Steps are taken relative to the starting weights, which differ in scale.
+    let scales: Vec<f32> = start_vec.iter().map(|w| w.abs().max(1.0)).collect();
+    let mut theta = start_vec.clone();
+    let mut rng = StdRng::seed_from_u64(cli.seed);
+    let threads = cli.threads.or_else(|| thread::available_parallelism().ok()).map_or(1, NonZeroUsize::get);
+    let start_time = Instant::now();
+
+    println!("Tuning from {:?}", start);
+    for k in 0..cli.iterations {
+        let a_k = cli.step / (k as f32 + 1.0 + STABILITY_OFFSET).powf(STEP_DECAY);
+        let c_k = cli.perturbation / (k as f32 + 1.0).powf(PERTURBATION_DECAY);
+        let delta: Vec<f32> = (0..theta.len()).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+
+        let shifted = |sign: f32| -> HeuristicWeights {
+            let v: Vec<f32> = theta.iter().zip(&delta).zip(&scales)
+                .map(|((t, d), s)| t + sign * c_k * d * s)
+                .collect();
+            vec_to_weights(&v)
+        };
+        let plus = shifted(1.0);
+        let minus = shifted(-1.0);
+        let seeds: Vec<u64> = (0..cli.pairs).map(|_| rng.gen()).collect();
+        let margin = match_margin(&plus, &minus, &seeds, threads);
+
+        for ((t, d), s) in theta.iter_mut().zip(&delta).zip(&scales) {
+            let gradient = margin / MARGIN_SCALE / (2.0 * c_k * d);
+            *t += a_k * gradient * s;
+        }
+        println!("Iteration {:>4}: margin {:+6.2}  weights {:?}", k + 1, margin, theta);
+
+        if cli.eval_every > 0 && (k + 1) % cli.eval_every == 0 {
+            let current = vec_to_weights(&theta);
+            let eval_seeds: Vec<u64> = (0..cli.pairs * 2).map(|_| rng.gen()).collect();
+            let vs_start = match_margin(&current, &start, &eval_seeds, threads);
+            println!("  vs starting weights: {:+.2} points per game", vs_start);
+            if let Err(e) = current.save_json_file(&cli.output) {
+                eprintln!("Error: {}", e);
+            }
+        }
+    }
+
+    let tuned = vec_to_weights(&theta);
+    match tuned.save_json_file(&cli.output) {
+        Ok(()) => println!(
+            "\nTuned weights written to {} after {:.1}s: {:?}",
+            cli.output, start_time.elapsed().as_secs_f64(), tuned
+        ),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
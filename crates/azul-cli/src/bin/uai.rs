@@ -0,0 +1,367 @@
+use azul_ai::{
+    mcts_heuristic_ai::{MctsHeuristicAI, VALUE_SCALE},
+    mcts_lib::MctsConfig,
+    registry::{AgentRegistry, DEFAULT_MCTS_HEURISTIC_ITERATIONS},
+    AIAgent, AgentConfig,
+};
+use azul_core::{GamePhase, GameState, Move, Observation};
+use clap::Parser;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Speaks the UAI protocol on stdin/stdout, a line protocol modeled on chess's UCI, so that
+/// GUIs, match managers and other engines can drive this one:
+///
+///   uai                                   -> id lines, option lines, uaiok
+///   isready                               -> readyok
+///   setoption name <name> value <value>   Agent, Iterations, or an MCTS option such as cpuct
+///   newgame
+///   position startpos [players <n>] [seed <s>] [moves <move>...]
+///   position state <GameState JSON> [moves <move>...]
+///   position observation <Observation JSON> [moves <move>...]
+///   go [movetime <ms>] [iterations <n>] [infinite]
+///                                         -> info lines, then bestmove <move>
+///   stop                                  ends a search early
+///   d                                     prints the position
+///   quit
+///
+/// Moves are in notation (`F3:B->L2`, `C:W->floor`). A move that ends the drafting round also
+/// tiles the walls and deals the next round, from the game's seed if it has one; without a
+/// seed, the sides can't agree on the deal, so send `position state` each round instead.
+/// `position observation` takes what `GameState::observation_for` shows a player, with the bag
+/// as counts, for opponents that mustn't learn the coming draws.
+#[derive(Parser, Debug)]
+#[command(version, about = "Play Azul over the UAI text protocol", long_about = None)]
+struct Cli {
+    /// The engine, as an agent spec (e.g. mctsheuristic:5000:cpuct=2.0 or alphabeta:3). Only
+    /// mctsheuristic reports its evaluation and principal variation in info lines.
+    #[arg(short, long, default_value = "mctsheuristic")]
+    agent: String,
+}
+
+/// Simulations between checks of the clock and the stop flag.
+const SEARCH_SLICE: u32 = 100;
+/// How often a running search reports.
+const INFO_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What `go` may spend: a fixed number of simulations, a time, or until `stop`.
+#[derive(Debug, Clone, Copy, Default)]
+struct GoLimits {
+    movetime: Option<Duration>,
+    iterations: Option<u32>,
+    infinite: bool,
+}
+
+/// The commands the engine thread runs. `stop` and `isready` are answered by the reader.
+enum Command {
+    SetOption { name: String, value: String },
+    NewGame,
+    Position(GameState),
+    Go(GoLimits),
+    Display,
+}
+
+fn emit(line: &str) {
+    let mut stdout = io::stdout().lock();
+    // A GUI that has gone away can't be told anything more.
+    let _ = writeln!(stdout, "{}", line);
+    let _ = stdout.flush();
+}
+
+// --- Parsing ---
+
+fn parse_position(args: &str) -> Result<GameState, String> {
+    let (setup, moves) = match args.split_once(" moves") {
+        Some((setup, moves)) => (setup.trim(), moves.split_whitespace().collect::<Vec<_>>()),
+        None => (args.trim(), Vec::new()),
+    };
+    let mut game_state = if let Some(json) = setup.strip_prefix("state") {
+        let game_state: GameState = serde_json::from_str(json.trim()).map_err(|e| format!("not a game state: {}", e))?;
+        game_state.check_invariants().map_err(|e| format!("invalid game state: {}", e))?;
+        game_state
+    } else if let Some(json) = setup.strip_prefix("observation") {
+        let observation: Observation = serde_json::from_str(json.trim()).map_err(|e| format!("not an observation: {}", e))?;
+        let game_state = GameState::from_observation(&observation);
+        game_state.check_invariants().map_err(|e| format!("invalid observation: {}", e))?;
+        game_state
+    } else if let Some(options) = setup.strip_prefix("startpos") {
+        let (mut players, mut seed) = (2, None);
+        let mut words = options.split_whitespace();
+        while let Some(word) = words.next() {
+            let value = words.next().ok_or_else(|| format!("'{}' needs a value", word))?;
+            match word {
+                "players" => players = value.parse().map_err(|_| format!("invalid player count '{}'", value))?,
+                "seed" => seed = Some(value.parse().map_err(|_| format!("invalid seed '{}'", value))?),
+                _ => return Err(format!("unknown startpos option '{}'", word)),
+            }
+        }
+        if azul_core::factory_count(players).is_none() {
+            return Err(format!("Azul is for 2 to 4 players, not {}", players));
+        }
+        match seed {
+            Some(seed) => GameState::new_with_seed(players, seed),
+            None => GameState::new(players),
+        }
+    } else {
+        return Err(format!("expected 'startpos', 'state' or 'observation', got '{}'", setup));
+    };
+    for notation in moves {
+        let m = Move::from_notation(notation)?;
+        game_state.advance(Some(&m)).map_err(|e| format!("{} is illegal: {}", m, e))?;
+        if game_state.phase() == GamePhase::Tiling {
+            game_state.advance(None).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(game_state)
+}
+
+fn parse_go(args: &str) -> Result<GoLimits, String> {
+    let mut limits = GoLimits::default();
+    let mut words = args.split_whitespace();
+    while let Some(word) = words.next() {
+        let mut value = || words.next().ok_or_else(|| format!("'{}' needs a value", word));
+        match word {
+            "movetime" => {
+                let ms = value()?;
+                limits.movetime = Some(Duration::from_millis(ms.parse().map_err(|_| format!("invalid movetime '{}'", ms))?));
+            }
+            "iterations" => {
+                let n = value()?;
+                limits.iterations = Some(n.parse().map_err(|_| format!("invalid iterations '{}'", n))?);
+            }
+            "infinite" => limits.infinite = true,
+            _ => return Err(format!("unknown go option '{}'", word)),
+        }
+    }
+    Ok(limits)
+}
+
+fn parse_setoption(args: &str) -> Result<(String, String), String> {
+    let rest = args.trim().strip_prefix("name").ok_or("expected 'setoption name <name> value <value>'")?;
+    let (name, value) = rest.split_once(" value ").ok_or("expected 'setoption name <name> value <value>'")?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
+}
+
+// --- Engine ---
+
+struct Engine {
+    registry: AgentRegistry,
+    agent: Box<dyn AIAgent>,
+    iterations: u32,
+    mcts: MctsConfig,
+    game_state: GameState,
+    /// Set by the reader on `stop`, and cleared by it as it queues each `go`, so that a `stop`
+    /// arriving before the engine starts the search still ends it.
+    stop: Arc<AtomicBool>,
+}
+
+impl Engine {
+    fn new(spec: &str, stop: Arc<AtomicBool>) -> Result<Self, String> {
+        let registry = AgentRegistry::new();
+        let agent = registry.create_from_str(spec)?;
+        Ok(Self {
+            registry,
+            agent,
+            iterations: DEFAULT_MCTS_HEURISTIC_ITERATIONS,
+            mcts: MctsConfig::default(),
+            game_state: GameState::new(2),
+            stop,
+        })
+    }
+
+    fn run(mut self, commands: Receiver<Command>) {
+        for command in commands {
+            let result = match command {
+                Command::SetOption { name, value } => self.set_option(&name, &value),
+                Command::NewGame => {
+                    self.agent.reset();
+                    Ok(())
+                }
+                Command::Position(game_state) => {
+                    self.game_state = game_state;
+                    Ok(())
+                }
+                Command::Go(limits) => {
+                    self.go(limits);
+                    Ok(())
+                }
+                Command::Display => {
+                    emit(&self.game_state.to_string());
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                emit(&format!("info string {}", e));
+            }
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name.to_lowercase().as_str() {
+            "agent" => {
+                self.agent = self.registry.create_from_str(value)?;
+                // Settings made with setoption outlive the agent they were made on.
+                self.agent.configure(&AgentConfig { iterations: Some(self.iterations), mcts: Some(self.mcts.clone()), ..AgentConfig::default() });
+            }
+            "iterations" => {
+                self.iterations = value.parse().map_err(|_| format!("invalid iterations '{}'", value))?;
+                self.agent.configure(&AgentConfig { iterations: Some(self.iterations), ..AgentConfig::default() });
+            }
+            key => {
+                self.mcts.set(key, value)?;
+                self.agent.configure(&AgentConfig { mcts: Some(self.mcts.clone()), ..AgentConfig::default() });
+            }
+        }
+        Ok(())
+    }
+
+    fn go(&mut self, limits: GoLimits) {
+        if self.game_state.phase() != GamePhase::Drafting {
+            emit("bestmove (none)");
+            return;
+        }
+        let start = Instant::now();
+        let deadline = limits.movetime.map(|movetime| start + movetime);
+        let target = match limits {
+            GoLimits { iterations: Some(n), .. } => n,
+            GoLimits { movetime: Some(_), .. } | GoLimits { infinite: true, .. } => u32::MAX,
+            _ => self.iterations,
+        };
+        self.agent.configure(&AgentConfig { iterations: Some(target), ..AgentConfig::default() });
+
+        let best = match self.agent.begin_search(&self.game_state) {
+            Some(mut progress) => {
+                let mut last_info = start;
+                // `infinite` keeps going until stop, even past its target. The first slice
+                // always runs, so an immediate stop still has a move to report.
+                while !progress.is_done() || (limits.infinite && progress.target > 0) {
+                    let out_of_time = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+                    if progress.iterations > 0 && (self.stop.load(Ordering::SeqCst) || out_of_time) {
+                        break;
+                    }
+                    let Some(next) = self.agent.search_step(SEARCH_SLICE) else { break };
+                    if next.iterations == progress.iterations && next.is_done() {
+                        // Nothing left to run: wait for stop instead of spinning.
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                    progress = next;
+                    if last_info.elapsed() >= INFO_INTERVAL {
+                        last_info = Instant::now();
+                        self.report(progress.iterations, start);
+                    }
+                }
+                let best = self.agent.end_search();
+                self.report(progress.iterations, start);
+                best
+            }
+            None => self.agent.get_move(&self.game_state),
+        };
+        self.agent.configure(&AgentConfig { iterations: Some(self.iterations), ..AgentConfig::default() });
+        match best {
+            Some(m) => emit(&format!("bestmove {}", m)),
+            None => emit("bestmove (none)"),
+        }
+    }
+
+    /// An info line for the search so far: depth is the principal variation's length, and the
+    /// score is the mover's expected margin over the best opponent, in hundredths of a point.
+    fn report(&mut self, iterations: u32, start: Instant) {
+        let elapsed = start.elapsed();
+        let mut line = format!(
+            "info iterations {} time {} ips {}",
+            iterations,
+            elapsed.as_millis(),
+            (iterations as f64 / elapsed.as_secs_f64().max(1e-3)) as u64,
+        );
+        if let Some(engine) = self.agent.as_any().downcast_ref::<MctsHeuristicAI>() {
+            if let Some(analysis) = engine.analysis(1) {
+                line += &format!(" depth {} nodes {}", analysis.principal_variation.len(), analysis.visits);
+                if let Some(value) = engine.root_value() {
+                    line += &format!(" score cp {}", (value * VALUE_SCALE * 100.0).round() as i64);
+                }
+                if !analysis.principal_variation.is_empty() {
+                    let pv: Vec<String> = analysis.principal_variation.iter().map(Move::to_notation).collect();
+                    line += &format!(" pv {}", pv.join(" "));
+                }
+            }
+        }
+        emit(&line);
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (commands, received) = mpsc::channel();
+    // Agents aren't Send, so the engine is built on the thread that runs it.
+    let (ready, started) = mpsc::channel();
+    let engine_stop = Arc::clone(&stop);
+    let agent_spec = cli.agent.clone();
+    let engine = thread::spawn(move || match Engine::new(&agent_spec, engine_stop) {
+        Ok(engine) => {
+            let _ = ready.send(Ok(()));
+            engine.run(received);
+        }
+        Err(e) => {
+            let _ = ready.send(Err(e));
+        }
+    });
+    if let Ok(Err(e)) = started.recv() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let line = line.trim();
+        let (word, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let command = match word {
+            "" => continue,
+            "uai" => {
+                emit(&format!("id name Azul_Engine {}", env!("CARGO_PKG_VERSION")));
+                emit("id author the Azul_Engine authors");
+                emit(&format!("option name Agent type string default {}", cli.agent));
+                emit(&format!("option name Iterations type spin default {} min 1 max {}", DEFAULT_MCTS_HEURISTIC_ITERATIONS, u32::MAX));
+                let defaults = MctsConfig::default();
+                emit(&format!("option name cpuct type string default {}", defaults.c_puct));
+                emit(&format!("option name endgame type string default {}", defaults.endgame_nodes));
+                emit(&format!("option name priortemp type string default {}", defaults.prior_temperature));
+                emit("uaiok");
+                continue;
+            }
+            "isready" => {
+                emit("readyok");
+                continue;
+            }
+            "stop" => {
+                stop.store(true, Ordering::SeqCst);
+                continue;
+            }
+            "quit" => break,
+            "newgame" => Ok(Command::NewGame),
+            "d" => Ok(Command::Display),
+            "setoption" => parse_setoption(args).map(|(name, value)| Command::SetOption { name, value }),
+            "position" => parse_position(args).map(Command::Position),
+            "go" => parse_go(args).map(Command::Go),
+            _ => Err(format!("unknown command '{}'", word)),
+        };
+        match command {
+            Ok(command) => {
+                if let Command::Go(_) = command {
+                    stop.store(false, Ordering::SeqCst);
+                }
+                if commands.send(command).is_err() {
+                    break;
+                }
+            }
+            Err(e) => emit(&format!("info string {}", e)),
+        }
+    }
+    stop.store(true, Ordering::SeqCst);
+    drop(commands);
+    let _ = engine.join();
+}
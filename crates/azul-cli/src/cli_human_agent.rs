@@ -0,0 +1,417 @@
+use azul_ai::{mcts_heuristic_ai::MctsHeuristicAI, AIAgent, AgentConfig};
+use azul_core::render::{self, TileStyle};
+use azul_core::save::SavedGame;
+use azul_core::{GamePhase, GameState, Move, MoveDestination, MoveSource, ScoringEvent, Winner};
+use std::any::Any;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Instant;
+
+const DEFAULT_HINT_ITERATIONS: u32 = 2000;
+const DEFAULT_SAVE_PATH: &str = "azul_save.json";
+
+/// How much of the table is printed before a human move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// Factories, the center, and every player's board.
+    Full,
+    /// Factories, the center, the mover's board, and everyone else's score.
+    Compact,
+}
+
+impl FromStr for RenderStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(RenderStyle::Full),
+            "compact" => Ok(RenderStyle::Compact),
+            _ => Err(format!("unknown render style '{}' (expected full or compact)", s)),
+        }
+    }
+}
+
+/// Something a human asked for that `play_interactive_game` carries out. `get_move` returns
+/// `None` to hand it over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HumanRequest {
+    /// Take back this player's previous move in the current round.
+    Undo,
+    /// Save the game in progress to this file.
+    Save(PathBuf),
+    /// Replace the game in progress with the one saved in this file.
+    Load(PathBuf),
+}
+
+/// A human player at the terminal. Prints the table, lists the legal moves, and reads a choice
+/// from stdin. Besides a move number or notation (`F3:B->L2`) it understands `hint`, `eval`,
+/// `undo`, `:save`, `:load`, and `help`.
+pub struct CliHumanAgent {
+    hint_iterations: u32,
+    render_style: RenderStyle,
+    tile_style: TileStyle,
+    request: Option<HumanRequest>,
+}
+
+impl CliHumanAgent {
+    pub fn new() -> Self {
+        Self { hint_iterations: DEFAULT_HINT_ITERATIONS, render_style: RenderStyle::Full, tile_style: terminal_tile_style(), request: None }
+    }
+
+    pub fn with_hint_iterations(mut self, hint_iterations: u32) -> Self {
+        self.hint_iterations = hint_iterations;
+        self
+    }
+
+    pub fn with_render_style(mut self, render_style: RenderStyle) -> Self {
+        self.render_style = render_style;
+        self
+    }
+
+    pub fn with_tile_style(mut self, tile_style: TileStyle) -> Self {
+        self.tile_style = tile_style;
+        self
+    }
+
+    /// Returns (once) what the player asked for if the last `get_move` returned `None` because of it.
+    pub fn take_request(&mut self) -> Option<HumanRequest> {
+        self.request.take()
+    }
+
+    fn search(&self, game_state: &GameState) -> (Option<Move>, Option<f32>) {
+        let mut engine = MctsHeuristicAI::new(self.hint_iterations);
+        let best_move = engine.get_move(game_state);
+        (best_move, engine.root_value())
+    }
+}
+
+impl Default for CliHumanAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AIAgent for CliHumanAgent {
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        let legal_moves = game_state.get_legal_moves();
+        if legal_moves.is_empty() {
+            return None;
+        }
+
+        print_table(game_state, self.render_style, self.tile_style);
+        println!("Legal moves:");
+        for (i, m) in legal_moves.iter().enumerate() {
+            println!("  {:>2}: {:<11} {}", i + 1, m.to_notation(), describe_move(m));
+        }
+
+        let stdin = io::stdin();
+        loop {
+            print!("Player {}, enter a move number or notation (or 'help'): ", game_state.current_player_idx + 1);
+            io::stdout().flush().ok();
+
+            let mut input = String::new();
+            if stdin.lock().read_line(&mut input).unwrap_or(0) == 0 {
+                // End of input: nobody is left to answer.
+                return None;
+            }
+
+            if let Some(request) = parse_session_command(input.trim()) {
+                self.request = Some(request);
+                return None;
+            }
+            match input.trim().to_lowercase().as_str() {
+                "hint" => match self.search(game_state) {
+                    (Some(m), _) => println!("Hint: {}", describe_move(&m)),
+                    (None, _) => println!("The engine has no suggestion."),
+                },
+                "eval" => match self.search(game_state) {
+                    (Some(m), Some(value)) => println!("Engine evaluation: {:.2} (best: {})", value, describe_move(&m)),
+                    _ => println!("The engine could not evaluate this position."),
+                },
+                "undo" => {
+                    self.request = Some(HumanRequest::Undo);
+                    return None;
+                }
+                "help" => {
+                    println!("  <number>  play the listed move");
+                    println!("  <move>    play a move by notation, e.g. F3:B->L2 or C:W->floor");
+                    println!("  hint      ask the engine for a suggestion");
+                    println!("  eval      show the engine's evaluation of this position");
+                    println!("  undo      take back your previous move this round");
+                    println!("  :save [file]  save the game to finish later (default {})", DEFAULT_SAVE_PATH);
+                    println!("  :load [file]  continue a saved game with the same number of players");
+                }
+                other if other.contains(':') => match Move::from_notation(other) {
+                    Ok(m) => match game_state.validate_move(&m) {
+                        Ok(()) => return Some(m),
+                        Err(e) => println!("Illegal move: {}.", e),
+                    },
+                    Err(e) => println!("Invalid notation: {}.", e),
+                },
+                other => match other.parse::<usize>() {
+                    Ok(num) if num > 0 && num <= legal_moves.len() => {
+                        return Some(legal_moves[num - 1].clone());
+                    }
+                    _ => println!("Invalid input. Please enter a number between 1 and {} or a move like F3:B->L2.", legal_moves.len()),
+                },
+            }
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "CliHumanAgent"
+    }
+
+    fn reset(&mut self) {
+        self.request = None;
+    }
+
+    /// `iterations` sets the search behind `hint` and `eval`.
+    fn configure(&mut self, config: &AgentConfig) {
+        if let Some(iterations) = config.iterations {
+            self.hint_iterations = iterations;
+        }
+    }
+}
+
+/// `:save [file]` or `:load [file]`, the file defaulting to `DEFAULT_SAVE_PATH`.
+fn parse_session_command(input: &str) -> Option<HumanRequest> {
+    let (command, file) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let path = PathBuf::from(if file.trim().is_empty() { DEFAULT_SAVE_PATH } else { file.trim() });
+    match command.to_lowercase().as_str() {
+        ":save" => Some(HumanRequest::Save(path)),
+        ":load" => Some(HumanRequest::Load(path)),
+        _ => None,
+    }
+}
+
+pub fn describe_move(m: &Move) -> String {
+    let source_str = match m.source {
+        MoveSource::Factory(idx) => format!("factory {}", idx + 1),
+        MoveSource::Center => "the center".to_string(),
+    };
+    let dest_str = match m.destination {
+        MoveDestination::PatternLine(idx) => format!("pattern line {}", idx + 1),
+        MoveDestination::Floor => "the floor".to_string(),
+    };
+    format!("Take {:?} from {}, place on {}", m.tile, source_str, dest_str)
+}
+
+/// ANSI colors when stdout is a terminal and NO_COLOR isn't set, letters otherwise.
+pub fn terminal_tile_style() -> TileStyle {
+    if io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() { TileStyle::Ansi } else { TileStyle::Letters }
+}
+
+fn print_table(game_state: &GameState, render_style: RenderStyle, tile_style: TileStyle) {
+    println!();
+    if render_style == RenderStyle::Full {
+        print!("{}", render::table(game_state, tile_style));
+        return;
+    }
+    print!("{}", render::factories(game_state, tile_style));
+    for (i, player) in game_state.players.iter().enumerate() {
+        if i != game_state.current_player_idx {
+            println!("Player {} score: {}", i + 1, player.score);
+            continue;
+        }
+        println!("\nPlayer {}: {} points (to move)", i + 1, player.score);
+        print!("{}", render::board(player, tile_style));
+    }
+}
+
+/// Plays `game` to completion with any mix of human and engine seats, printing each round's
+/// progress, how long each engine move took, and a final summary. Engine seats ponder while a
+/// human is thinking. Human seats may undo back to their previous move within the current round,
+/// and save the game or load a saved one. Returns false if a seat stops answering before the
+/// game is over.
+pub fn play_interactive_game(game: &mut GameState, agents: &mut [Box<dyn AIAgent>], seat_names: &[String]) -> bool {
+    let mut round_counter = 1;
+    // Every move so far, for saving; the positions before this round's moves, for undoing.
+    let mut rounds: Vec<Vec<Move>> = Vec::new();
+    while !game.is_game_over() {
+        println!("\n\n--- Starting Round {} ---", round_counter);
+        let mut round_history: Vec<GameState> = Vec::new();
+        rounds.push(Vec::new());
+
+        while game.phase() == GamePhase::Drafting {
+            let player_idx = game.current_player_idx;
+            let is_human = agents[player_idx].as_any().downcast_ref::<CliHumanAgent>().is_some();
+            if is_human {
+                // Engines keep searching while the human thinks.
+                for (seat, other) in agents.iter_mut().enumerate() {
+                    if seat != player_idx {
+                        other.start_ponder(game);
+                    }
+                }
+            } else {
+                print!("\nPlayer {} ({}) is thinking... ", player_idx + 1, seat_names[player_idx]);
+                io::stdout().flush().ok();
+            }
+            let agent = &mut agents[player_idx];
+
+            let start_time = Instant::now();
+            match agent.get_move(game) {
+                Some(chosen_move) => {
+                    if !is_human {
+                        println!("plays {} ({:.2}s)", describe_move(&chosen_move), start_time.elapsed().as_secs_f64());
+                    }
+                    round_history.push(game.clone());
+                    if let Err(e) = game.advance(Some(&chosen_move)) {
+                        println!("\nPlayer {} chose an illegal move ({}). Stopping the game.", player_idx + 1, e);
+                        stop_pondering(agents);
+                        return false;
+                    }
+                    rounds.last_mut().expect("a round is in progress").push(chosen_move);
+                }
+                None => {
+                    let request = agent.as_any().downcast_mut::<CliHumanAgent>().and_then(|human| human.take_request());
+                    match request {
+                        None => {
+                            println!("\nPlayer {} has no move. Stopping the game.", player_idx + 1);
+                            stop_pondering(agents);
+                            return false;
+                        }
+                        Some(HumanRequest::Undo) => match round_history.iter().rposition(|s| s.current_player_idx == player_idx) {
+                            Some(idx) => {
+                                *game = round_history[idx].clone();
+                                round_history.truncate(idx);
+                                rounds.last_mut().expect("a round is in progress").truncate(idx);
+                                println!("\nUndid Player {}'s previous move.", player_idx + 1);
+                            }
+                            None => println!("\nNothing to undo this round."),
+                        },
+                        Some(HumanRequest::Save(path)) => match SavedGame::new(game.clone(), rounds.clone()).save(&path) {
+                            Ok(()) => println!("\nSaved the game to {}.", path.display()),
+                            Err(e) => println!("\nCould not save the game: {}.", e),
+                        },
+                        Some(HumanRequest::Load(path)) => match load_game(&path, agents.len()) {
+                            Ok(saved) => {
+                                stop_pondering(agents);
+                                for agent in agents.iter_mut() {
+                                    agent.reset();
+                                }
+                                *game = saved.state;
+                                rounds = saved.rounds;
+                                if rounds.is_empty() {
+                                    rounds.push(Vec::new());
+                                }
+                                round_counter = rounds.len();
+                                round_history.clear();
+                                println!("\nLoaded the game from {}: round {}, {} moves played.", path.display(), round_counter, rounds.iter().map(Vec::len).sum::<usize>());
+                            }
+                            Err(e) => println!("\nCould not load the game: {}.", e),
+                        },
+                    }
+                }
+            }
+        }
+
+        stop_pondering(agents);
+        println!("\n--- Tiling Phase ---");
+        let events = game.advance(None).expect("the drafting phase is over");
+        for event in events.iter().filter(|e| !matches!(e, ScoringEvent::EndGameBonus { .. })) {
+            println!("{}", describe_scoring_event(event));
+        }
+
+        if game.is_game_over() {
+            println!("\nFinal round completed!");
+        } else {
+            println!("--- End of Round {} Scores ---", round_counter);
+            for (i, player) in game.players.iter().enumerate() {
+                println!("Player {} score: {}", i + 1, player.score);
+            }
+            println!("\nPlayer {} will start the next round.", game.current_player_idx + 1);
+            round_counter += 1;
+        }
+    }
+
+    print_final_summary(game, seat_names);
+    true
+}
+
+/// Reads a saved game that can continue at this table: one with `num_players` players that
+/// isn't over.
+fn load_game(path: &std::path::Path, num_players: usize) -> Result<SavedGame, String> {
+    let saved = SavedGame::load(path)?;
+    if saved.state.players.len() != num_players {
+        return Err(format!("it has {} players, but this game has {}", saved.state.players.len(), num_players));
+    }
+    if saved.state.is_game_over() {
+        return Err("that game is already over".to_string());
+    }
+    Ok(saved)
+}
+
+fn stop_pondering(agents: &mut [Box<dyn AIAgent>]) {
+    for agent in agents.iter_mut() {
+        agent.stop_ponder();
+    }
+}
+
+/// A one-line explanation of a tiling-phase score change.
+pub fn describe_scoring_event(event: &ScoringEvent) -> String {
+    match event {
+        ScoringEvent::TilePlaced { player, row, col, tile, horizontal_points, vertical_points, points, completed_row } => {
+            let mut line = format!("Player {} places {:?} at row {}, column {}: +{}", player + 1, tile, row + 1, col + 1, points);
+            if *horizontal_points > 0 && *vertical_points > 0 {
+                line.push_str(&format!(" ({} across + {} down)", horizontal_points, vertical_points));
+            }
+            if *completed_row {
+                line.push_str(" - row complete!");
+            }
+            line
+        }
+        ScoringEvent::FloorPenalty { player, floor_tiles, penalty } => {
+            format!("Player {} loses {} for {} floor tile(s)", player + 1, penalty, floor_tiles)
+        }
+        ScoringEvent::EndGameBonus { player, bonuses, points } => format!(
+            "Player {} scores +{} in bonuses ({} rows, {} columns, {} colors)",
+            player + 1, points, bonuses.rows, bonuses.columns, bonuses.colors
+        ),
+    }
+}
+
+/// Prints each player's end-game bonuses (already applied by `advance`) and the final
+/// standings, breaking score ties on complete horizontal rows.
+pub fn print_final_summary(game: &GameState, seat_names: &[String]) {
+    let bonuses: Vec<_> = game.players.iter().map(|p| p.end_game_bonuses()).collect();
+    let pre_bonus_scores: Vec<u32> = game.players.iter().zip(&bonuses).map(|(p, b)| p.score - b.total()).collect();
+
+    println!("\n--- Final Scoring ---");
+    for (i, player) in game.players.iter().enumerate() {
+        let b = &bonuses[i];
+        println!(
+            "Player {} ({}): {} + rows {}x2 + columns {}x7 + colors {}x10 = {}",
+            i + 1, seat_names[i], pre_bonus_scores[i], b.rows, b.columns, b.colors, player.score
+        );
+    }
+
+    let rankings = game.rankings();
+    println!("\n--- Final Standings ---");
+    for r in &rankings {
+        println!(
+            "{}. Player {} ({}) - {} points, {} complete rows",
+            r.rank, r.player + 1, seat_names[r.player], r.score, r.complete_rows
+        );
+    }
+
+    match game.winner() {
+        Winner::Shared(winners) => {
+            let names: Vec<String> = winners.iter().map(|i| format!("Player {}", i + 1)).collect();
+            println!("\nShared victory between {}.", names.join(" and "));
+        }
+        Winner::Single(leader) => {
+            let tied_on_score = rankings.iter().filter(|r| r.score == game.players[leader].score).count();
+            if tied_on_score > 1 {
+                println!("\nPlayer {} wins the tie-break on complete rows.", leader + 1);
+            } else {
+                println!("\nPlayer {} wins!", leader + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Early stopping for `headless --confidence`: a simulation stops as soon as every agent's
+//! win rate is known to within `margin` at the `confidence` level, judged by the Wilson score
+//! interval, which stays sensible for win rates near 0 or 1 and for small samples.
+
+#[derive(Debug, Clone, Copy)]
+pub struct StoppingRule {
+    /// The two-sided confidence level, e.g. 0.95.
+    pub confidence: f64,
+    /// The largest half-width of a win-rate interval that counts as tight, e.g. 0.02.
+    pub margin: f64,
+    /// The normal quantile for `confidence`, e.g. 1.96 for 0.95.
+    z: f64,
+}
+
+impl StoppingRule {
+    pub fn new(confidence: f64, margin: f64) -> Result<Self, String> {
+        if confidence.is_nan() || confidence <= 0.0 || confidence >= 1.0 {
+            return Err(format!("The confidence level must be between 0 and 1, got {}", confidence));
+        }
+        if margin.is_nan() || margin <= 0.0 || margin >= 1.0 {
+            return Err(format!("The margin must be between 0% and 100%, got {}%", margin * 100.0));
+        }
+        Ok(Self { confidence, margin, z: normal_quantile(1.0 - (1.0 - confidence) / 2.0) })
+    }
+
+    /// The half-width of the Wilson score interval for `wins` wins in `games` games.
+    pub fn half_width(&self, wins: u32, games: u32) -> f64 {
+        if games == 0 {
+            return 0.5;
+        }
+        let (n, z2) = (games as f64, self.z * self.z);
+        let p = wins as f64 / n;
+        self.z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n)
+    }
+
+    /// Whether the win rate of `wins` in `games` is known to within the margin.
+    pub fn is_tight(&self, wins: u32, games: u32) -> bool {
+        self.half_width(wins, games) <= self.margin
+    }
+}
+
+/// Parses a margin given as a percentage ("2%") or a fraction ("0.02").
+pub fn parse_margin(s: &str) -> Result<f64, String> {
+    let s = s.trim();
+    let (number, scale) = match s.strip_suffix('%') {
+        Some(percent) => (percent, 100.0),
+        None => (s, 1.0),
+    };
+    number.trim().parse::<f64>().map(|value| value / scale).map_err(|_| format!("Invalid margin '{}'; expected e.g. 2% or 0.02", s))
+}
+
+/// The standard normal quantile at `p` in (0, 1), by Acklam's rational approximation
+/// (relative error below 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    const P_LOW: f64 = 0.02425;
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
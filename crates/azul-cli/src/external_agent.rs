@@ -0,0 +1,195 @@
+//! Agents that run as a separate program speaking the UAI protocol (the `uai` binary's), so
+//! that bots written in any language can sit at the table. Each move sends the position as the
+//! player to move observes it, with the bag as counts and no RNG, so a bot can't read the
+//! coming draws, and asks for a move within the agent's time.
+
+use azul_ai::{registry::AgentRegistry, AIAgent, SearchStats};
+use azul_core::{GameState, Move};
+use std::any::Any;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Thinking time per move when the spec doesn't give one.
+pub const DEFAULT_MOVE_TIME: Duration = Duration::from_secs(1);
+/// How long a program gets to answer `uai` when it starts.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Allowance for process and pipe latency on top of the move time, and again after `stop`.
+const TIME_GRACE: Duration = Duration::from_millis(500);
+
+pub struct ExternalAgent {
+    child: Child,
+    stdin: ChildStdin,
+    /// The program's stdout, a line at a time, from a reader thread.
+    lines: Receiver<String>,
+    /// From the program's `id name`, or else its command.
+    name: String,
+    move_time: Duration,
+    last_search: Option<SearchStats>,
+}
+
+impl ExternalAgent {
+    /// Starts `command` (a program and its arguments, split on whitespace) and waits for it to
+    /// finish the `uai` handshake.
+    pub fn spawn(command: &str, move_time: Duration) -> Result<Self, String> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or("The external agent's command is empty")?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("Could not start '{}': {}", command, e))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut agent = Self { child, stdin, lines, name: program.to_string(), move_time, last_search: None };
+        agent.send("uai")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            let line = agent.next_line(deadline).map_err(|e| format!("'{}' did not finish the uai handshake: {}", command, e))?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                agent.name = name.trim().to_string();
+            } else if line.trim() == "uaiok" {
+                return Ok(agent);
+            }
+        }
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("could not write to {}: {}", self.name, e))
+    }
+
+    fn next_line(&self, deadline: Instant) -> Result<String, String> {
+        match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err("timed out".to_string()),
+            Err(RecvTimeoutError::Disconnected) => Err("the program exited".to_string()),
+        }
+    }
+
+    /// Reads up to `deadline` for the `bestmove`, noting the search size from `info` lines.
+    fn read_best_move(&mut self, deadline: Instant) -> Result<String, String> {
+        loop {
+            let line = self.next_line(deadline)?;
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("bestmove") => return words.next().map(str::to_string).ok_or_else(|| "empty bestmove".to_string()),
+                Some("info") => {
+                    let words: Vec<&str> = words.collect();
+                    let value = |key: &str| words.windows(2).find(|pair| pair[0] == key).and_then(|pair| pair[1].parse().ok());
+                    if let Some(iterations) = value("iterations") {
+                        self.last_search = Some(SearchStats { iterations: iterations as u32, nodes: value("nodes").unwrap_or(iterations) });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn request_move(&mut self, game_state: &GameState) -> Result<Move, String> {
+        // A bestmove that came too late for the previous request is no answer to this one.
+        while self.lines.try_recv().is_ok() {}
+        self.last_search = None;
+        let observation = game_state.observation_for(game_state.current_player_idx);
+        let json = serde_json::to_string(&observation).expect("an observation always serializes");
+        self.send(&format!("position observation {}", json))?;
+        self.send(&format!("go movetime {}", self.move_time.as_millis()))?;
+        let deadline = Instant::now() + self.move_time + TIME_GRACE;
+        let notation = match self.read_best_move(deadline) {
+            Ok(notation) => notation,
+            Err(_) if Instant::now() >= deadline => {
+                self.send("stop")?;
+                let notation = self.read_best_move(Instant::now() + TIME_GRACE)
+                    .map_err(|_| format!("no move within {} ms, even after stop", self.move_time.as_millis()))?;
+                eprintln!("Warning: {} overran its {} ms", self.name, self.move_time.as_millis());
+                notation
+            }
+            Err(e) => return Err(e),
+        };
+        let m = Move::from_notation(&notation).map_err(|e| format!("bestmove {}: {}", notation, e))?;
+        game_state.validate_move(&m).map_err(|e| format!("bestmove {} is illegal: {}", m, e))?;
+        Ok(m)
+    }
+}
+
+impl AIAgent for ExternalAgent {
+    /// `None` if the program doesn't come up with a legal move in time; the reason goes to
+    /// stderr.
+    fn get_move(&mut self, game_state: &GameState) -> Option<Move> {
+        match self.request_move(game_state) {
+            Ok(m) => Some(m),
+            Err(e) => {
+                eprintln!("Error: {}: {}", self.name, e);
+                None
+            }
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn reset(&mut self) {
+        // A program that has died will fail its next move instead.
+        let _ = self.send("newgame");
+    }
+
+    fn search_stats(&self) -> Option<SearchStats> {
+        self.last_search
+    }
+}
+
+impl Drop for ExternalAgent {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = Instant::now() + TIME_GRACE;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Adds `external:<command>[:time=<seconds>]` to `registry`, e.g.
+/// `external:python3 bot.py:time=0.5`. Colons in the command are kept, so `external:C:\bot.exe`
+/// works, but a part with an `=` in it is read as an option.
+pub fn register_external_agent(registry: &mut AgentRegistry) {
+    registry.register("external", |spec| {
+        let command = spec.args.join(":");
+        if command.trim().is_empty() {
+            return Err(format!("Missing command in agent spec: {} (e.g. external:./mybot)", spec));
+        }
+        let mut move_time = DEFAULT_MOVE_TIME;
+        for (key, value) in &spec.options {
+            match key.as_str() {
+                "time" => {
+                    move_time = value.parse().ok().and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                        .ok_or_else(|| format!("Invalid time '{}' in agent spec: {}", value, spec))?;
+                }
+                _ => return Err(format!("Agent 'external' takes no option '{}'", key)),
+            }
+        }
+        Ok(Box::new(ExternalAgent::spawn(&command, move_time)?))
+    });
+}
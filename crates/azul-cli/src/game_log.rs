@@ -0,0 +1,164 @@
+//! The game logs that `headless` simulations save: one JSON `GameLog` per line
+//! (`game_logs.jsonl`), written as each game finishes, and optionally gzipped
+//! (`game_logs.jsonl.gz`). Runs from before the streaming format saved one pretty-printed
+//! JSON array (`game_logs.json`), which `read_game_logs` still reads.
+
+use azul_ai::SearchStats;
+use azul_core::{GamePhase, GameState, Move, ScoringEvent, TileBagSummary, TurnState};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize)]
+pub struct GameTurn {
+    pub player_index: usize,
+    pub state_before_move: TurnState,
+    pub chosen_move: Move,
+    /// How long the agent took to choose the move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub think_seconds: Option<f64>,
+    /// The agent's search effort for the move, if it searches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<SearchStats>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GameRound {
+    pub round_number: usize,
+    pub tile_bag_at_start_of_round: TileBagSummary,
+    pub turns: Vec<GameTurn>,
+    /// How each player's score changed in this round's tiling phase.
+    #[serde(default)]
+    pub scoring_events: Vec<ScoringEvent>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GameLog {
+    pub matchup: Vec<String>,
+    /// The seed the game's tiles and agents were seeded from, for a seeded run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    pub history: Vec<GameRound>,
+    pub final_scores: Vec<u32>,
+    /// The seat that gave no legal move and forfeited, leaving the game unfinished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forfeited_by: Option<usize>,
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+/// Appends game logs to a file, one JSON line each.
+pub struct GameLogWriter {
+    sink: Sink,
+    count: usize,
+}
+
+impl GameLogWriter {
+    /// Creates `path`, gzipped if `gzip` is set.
+    pub fn create(path: &Path, gzip: bool) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        let sink = if gzip { Sink::Gzip(GzEncoder::new(file, Compression::default())) } else { Sink::Plain(file) };
+        Ok(Self { sink, count: 0 })
+    }
+
+    pub fn write(&mut self, log: &GameLog) -> io::Result<()> {
+        let writer: &mut dyn Write = match &mut self.sink {
+            Sink::Plain(writer) => writer,
+            Sink::Gzip(writer) => writer,
+        };
+        serde_json::to_writer(&mut *writer, log)?;
+        writer.write_all(b"\n")?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Logs written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Flushes the file, writing the gzip trailer if it is gzipped.
+    pub fn finish(self) -> io::Result<()> {
+        match self.sink {
+            Sink::Plain(mut writer) => writer.flush(),
+            Sink::Gzip(writer) => writer.finish()?.flush(),
+        }
+    }
+}
+
+/// The game logs in `path`, one at a time: JSON lines, gunzipped first if the name ends in
+/// `.gz`, or a JSON array if it ends in `.json`.
+pub fn read_game_logs(path: &Path) -> io::Result<Box<dyn Iterator<Item = io::Result<GameLog>>>> {
+    let file = BufReader::new(File::open(path)?);
+    let name = path.to_string_lossy();
+    if name.ends_with(".json") {
+        let logs: Vec<GameLog> = serde_json::from_reader(file)?;
+        return Ok(Box::new(logs.into_iter().map(Ok)));
+    }
+    let reader: Box<dyn BufRead> = if name.ends_with(".gz") { Box::new(BufReader::new(GzDecoder::new(file))) } else { Box::new(file) };
+    Ok(Box::new(
+        serde_json::Deserializer::from_reader(reader).into_iter::<GameLog>().map(|log| log.map_err(io::Error::from)),
+    ))
+}
+
+/// Replays `log` through the engine: every move must be legal from the recorded position and
+/// lead to the next one, and each round's scoring and the final scores must come out as
+/// recorded. A seeded game is replayed from its seed, which checks the draws as well; otherwise
+/// each round starts over from its first recorded position, since the refills were random.
+pub fn verify_game_log(log: &GameLog) -> Result<(), String> {
+    let mut game = log.seed.map(|seed| GameState::new_with_seed(log.matchup.len(), seed));
+    for round in &log.history {
+        let number = round.round_number;
+        let first = round.turns.first().ok_or_else(|| format!("Round {} has no moves", number))?;
+        let mut state = match game.take() {
+            Some(state) if log.seed.is_some() => {
+                let bag = TileBagSummary::from_vec(&state.tile_bag);
+                if bag != round.tile_bag_at_start_of_round {
+                    return Err(format!(
+                        "Round {}: the bag holds {:?}, but the log has {:?}",
+                        number, bag, round.tile_bag_at_start_of_round
+                    ));
+                }
+                state
+            }
+            Some(state) => {
+                if state.players != first.state_before_move.players {
+                    return Err(format!("Round {}: the boards after the last round's scoring don't match the log", number));
+                }
+                GameState::from_turn_state(&first.state_before_move)
+            }
+            None => GameState::from_turn_state(&first.state_before_move),
+        };
+        for (i, turn) in round.turns.iter().enumerate() {
+            if TurnState::from(&state) != turn.state_before_move {
+                return Err(format!("Round {}, turn {}: the position doesn't match the log", number, i + 1));
+            }
+            state.advance(Some(&turn.chosen_move))
+                .map_err(|e| format!("Round {}, turn {}: {} is illegal: {}", number, i + 1, turn.chosen_move, e))?;
+        }
+        if state.phase() != GamePhase::Tiling {
+            return Err(format!("Round {}: the logged moves don't finish the drafting", number));
+        }
+        let events = state.advance(None).map_err(|e| format!("Round {}: {}", number, e))?;
+        // Logs from before scoring events were recorded have none to compare.
+        if !round.scoring_events.is_empty() && events != round.scoring_events {
+            return Err(format!("Round {}: the scoring doesn't match the log", number));
+        }
+        game = Some(state);
+    }
+    let scores: Vec<u32> = match &game {
+        Some(state) => state.players.iter().map(|p| p.score).collect(),
+        None => vec![0; log.matchup.len()],
+    };
+    if scores != log.final_scores {
+        return Err(format!("The final scores are {:?}, but the log has {:?}", scores, log.final_scores));
+    }
+    Ok(())
+}
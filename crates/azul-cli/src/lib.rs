@@ -0,0 +1,15 @@
+//! Terminal front-end shared by the `azul-engine`, `headless`, `train`, `gate` and
+//! `coordinator` binaries.
+
+#[cfg(feature = "native")]
+pub mod agent_file;
+pub mod cli_human_agent;
+pub mod confidence;
+pub mod external_agent;
+#[cfg(feature = "native")]
+pub mod game_log;
+pub mod self_play_http;
+pub mod sprt;
+#[cfg(feature = "native")]
+pub mod tournament;
+pub mod watch;
@@ -0,0 +1,111 @@
+use azul_ai::{registry::AgentRegistry, AIAgent, AgentConfig};
+use azul_cli::cli_human_agent::{play_interactive_game, terminal_tile_style, CliHumanAgent, RenderStyle};
+use azul_cli::external_agent::register_external_agent;
+use azul_core::render::TileStyle;
+use azul_core::GameState;
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Play Azul in the terminal", long_about = None)]
+struct Cli {
+    /// Number of players (2-4). Seats not covered by --players are human. Without --players,
+    /// a game started at a terminal asks for each seat's agent.
+    #[arg(short = 'n', long, default_value_t = 2)]
+    num_players: usize,
+    /// Agent for each seat, in order: human, random[:seed], simpleai, greedyai, heuristicai[:weights.json], difficulty:<level>, alphabeta[:depth], maxn[:depth], mctsheuristic[:iterations][:key=value...], or external:<program>[:time=<seconds>] for a program speaking the UAI protocol.
+    #[arg(short, long, num_args = 1.., value_delimiter = ' ')]
+    players: Vec<String>,
+    /// How much of the table to print before a human move: full or compact.
+    #[arg(long, default_value = "full")]
+    render: RenderStyle,
+    /// How to draw tiles: ansi (letters on their colors), letters ([B]), or blocks (colored
+    /// Unicode squares). Defaults to ansi at a terminal without NO_COLOR, letters otherwise.
+    #[arg(long)]
+    tiles: Option<TileStyle>,
+    /// Seed the tiles, and the random choices of the agents, so that a game can be replayed
+    /// exactly.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn agent_registry(render_style: RenderStyle, tile_style: TileStyle) -> AgentRegistry {
+    let mut registry = AgentRegistry::new();
+    registry.register("human", move |spec| {
+        spec.expect_no_options()?;
+        Ok(Box::new(CliHumanAgent::new().with_render_style(render_style).with_tile_style(tile_style)))
+    });
+    register_external_agent(&mut registry);
+    registry
+}
+
+/// Asks for each seat's agent on stdin until it gets a spec the registry accepts; an empty
+/// answer seats a human. Returns `None` if stdin closes first.
+fn choose_seats(num_players: usize, registry: &AgentRegistry) -> Option<Vec<String>> {
+    println!("Choose an agent for each seat, or press Enter for a human player.");
+    println!("Agents: {}", registry.names().collect::<Vec<_>>().join(", "));
+    println!("e.g. difficulty:medium, heuristicai, mctsheuristic:2000");
+    let mut lines = io::stdin().lock().lines();
+    let mut seat_names = Vec::new();
+    while seat_names.len() < num_players {
+        print!("Player {}: ", seat_names.len() + 1);
+        io::stdout().flush().ok();
+        let answer = lines.next()?.ok()?;
+        let spec = if answer.trim().is_empty() { "human".to_string() } else { answer.trim().to_string() };
+        match registry.create_from_str(&spec) {
+            Ok(_) => seat_names.push(spec),
+            Err(e) => println!("{}", e),
+        }
+    }
+    Some(seat_names)
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let num_players = cli.num_players.max(cli.players.len());
+    if !(2..=4).contains(&num_players) {
+        eprintln!("Error: The number of players must be between 2 and 4.");
+        std::process::exit(1);
+    }
+
+    let registry = agent_registry(cli.render, cli.tiles.unwrap_or_else(terminal_tile_style));
+    let seat_names: Vec<String> = if cli.players.is_empty() && io::stdin().is_terminal() {
+        match choose_seats(num_players, &registry) {
+            Some(seat_names) => seat_names,
+            None => return,
+        }
+    } else {
+        (0..num_players)
+            .map(|seat| cli.players.get(seat).cloned().unwrap_or_else(|| "human".to_string()))
+            .collect()
+    };
+    // Each agent is seeded from the game's seed, as headless --seed does.
+    let mut rng = cli.seed.map(StdRng::seed_from_u64);
+    let mut agents: Vec<Box<dyn AIAgent>> = Vec::with_capacity(num_players);
+    for spec in &seat_names {
+        match registry.create_from_str(spec) {
+            Ok(mut agent) => {
+                if let Some(rng) = &mut rng {
+                    agent.configure(&AgentConfig { seed: Some(rng.gen()), ..AgentConfig::default() });
+                }
+                agents.push(agent);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    println!("Starting Azul Game!");
+    for (seat, spec) in seat_names.iter().enumerate() {
+        println!("  Player {}: {}", seat + 1, spec);
+    }
+    let mut game = match cli.seed {
+        Some(seed) => GameState::new_with_seed(num_players, seed),
+        None => GameState::new(num_players),
+    };
+    play_interactive_game(&mut game, &mut agents, &seat_names);
+}
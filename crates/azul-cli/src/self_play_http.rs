@@ -0,0 +1,175 @@
+//! The protocol between distributed self-play workers (`headless --worker`) and the
+//! `coordinator` binary: plain HTTP/1.1 over a TCP connection per request.
+//!
+//! - `GET /model` returns the coordinator's newest weights file, with its version in the
+//!   `X-Model-Version` header, or `204 No Content` if there is no model yet.
+//! - `POST /samples` uploads a samples file (see `azul_ai::training_io`), recorded with the
+//!   model version given in `X-Model-Version`.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+pub const MODEL_PATH: &str = "/model";
+pub const SAMPLES_PATH: &str = "/samples";
+pub const MODEL_VERSION_HEADER: &str = "X-Model-Version";
+
+/// Bodies above this size are refused.
+const MAX_BODY_BYTES: usize = 256 * 1024 * 1024;
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A request or a response, minus the first line.
+#[derive(Debug, Default)]
+pub struct Message {
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Message {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    pub fn model_version(&self) -> Option<u32> {
+        self.header(MODEL_VERSION_HEADER)?.trim().parse().ok()
+    }
+}
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub message: Message,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub message: Message,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads headers up to the blank line, then a body of their Content-Length.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Message> {
+    let mut message = Message::default();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid_data("Connection closed in the headers".to_string()));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':').ok_or_else(|| invalid_data(format!("Malformed header '{}'", line)))?;
+        message.headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    let length = match message.header("Content-Length") {
+        Some(length) => length.parse::<usize>().map_err(|_| invalid_data(format!("Bad Content-Length '{}'", length)))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(invalid_data(format!("Body of {} bytes is over the {} byte limit", length, MAX_BODY_BYTES)));
+    }
+    message.body = vec![0; length];
+    reader.read_exact(&mut message.body)?;
+    Ok(message)
+}
+
+fn write_message(stream: &mut impl Write, first_line: &str, headers: &[(&str, String)], body: &[u8]) -> io::Result<()> {
+    let mut head = format!("{}\r\nContent-Length: {}\r\nConnection: close\r\n", first_line, body.len());
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// Reads one request from a connection accepted by the coordinator.
+pub fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(invalid_data(format!("Malformed request line '{}'", request_line.trim_end())));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+    Ok(Request { method, path, message: read_message(&mut reader)? })
+}
+
+pub fn write_response(stream: &mut TcpStream, status: u16, headers: &[(&str, String)], body: &[u8]) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write_message(stream, &format!("HTTP/1.1 {} {}", status, reason), headers, body)
+}
+
+/// A client for one coordinator, e.g. `http://192.168.1.10:8000`.
+pub struct Client {
+    host: String,
+}
+
+impl Client {
+    /// Only plain `http://host:port` URLs are supported.
+    pub fn new(url: &str) -> Result<Self, String> {
+        let host = url.strip_prefix("http://")
+            .ok_or_else(|| format!("Expected an http:// coordinator URL, got '{}'", url))?
+            .trim_end_matches('/');
+        if host.is_empty() || host.contains('/') {
+            return Err(format!("Expected a URL of the form http://host:port, got '{}'", url));
+        }
+        Ok(Self { host: host.to_string() })
+    }
+
+    pub fn request(&self, method: &str, path: &str, headers: &[(&str, String)], body: &[u8]) -> io::Result<Response> {
+        let mut stream = TcpStream::connect(&self.host)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        let mut all_headers = vec![("Host", self.host.clone())];
+        all_headers.extend_from_slice(headers);
+        write_message(&mut stream, &format!("{} {} HTTP/1.1", method, path), &all_headers, body)?;
+
+        let mut reader = BufReader::new(stream);
+        let mut status_line = String::new();
+        reader.read_line(&mut status_line)?;
+        let status = status_line.split_whitespace().nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| invalid_data(format!("Malformed status line '{}'", status_line.trim_end())))?;
+        Ok(Response { status, message: read_message(&mut reader)? })
+    }
+
+    /// The coordinator's current model and its version, or `None` if it has none yet.
+    pub fn fetch_model(&self) -> io::Result<Option<(u32, Vec<u8>)>> {
+        let response = self.request("GET", MODEL_PATH, &[], &[])?;
+        match response.status {
+            200 => {
+                let version = response.message.model_version()
+                    .ok_or_else(|| invalid_data(format!("Model response without a {} header", MODEL_VERSION_HEADER)))?;
+                Ok(Some((version, response.message.body)))
+            }
+            204 => Ok(None),
+            status => Err(io::Error::other(format!("Fetching the model failed with status {}", status))),
+        }
+    }
+
+    /// Uploads a samples file recorded with model `version` (0 for a random network).
+    pub fn upload_samples(&self, version: u32, samples: &[u8]) -> io::Result<()> {
+        let response = self.request("POST", SAMPLES_PATH, &[(MODEL_VERSION_HEADER, version.to_string())], samples)?;
+        if response.status != 200 {
+            return Err(io::Error::other(format!(
+                "Uploading samples failed with status {}: {}",
+                response.status, String::from_utf8_lossy(&response.message.body)
+            )));
+        }
+        Ok(())
+    }
+}
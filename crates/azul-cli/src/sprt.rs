@@ -0,0 +1,120 @@
+//! The sequential probability ratio test behind `headless --sprt`: plays games between two
+//! agents until there's enough evidence that the first is `elo1` stronger than the second
+//! (H1) or only `elo0` (H0), with error rates `alpha` and `beta`. It uses the usual
+//! normal approximation to the log-likelihood ratio of the game scores.
+
+/// Which hypothesis the test accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// The first agent is no more than `elo0` stronger.
+    H0,
+    /// The first agent is at least `elo1` stronger.
+    H1,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    /// The chance of accepting H1 when H0 holds.
+    pub alpha: f64,
+    /// The chance of accepting H0 when H1 holds.
+    pub beta: f64,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+/// The expected score of a player `elo` points stronger than its opponent.
+fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+impl Sprt {
+    /// A test from `key=value` settings: `elo0` and `elo1` are required, `alpha` and `beta`
+    /// default to 0.05.
+    pub fn parse(settings: &[String]) -> Result<Self, String> {
+        let (mut elo0, mut elo1, mut alpha, mut beta) = (None, None, 0.05, 0.05);
+        for setting in settings {
+            let (key, value) = setting.split_once('=')
+                .ok_or_else(|| format!("Expected key=value in the SPRT settings, got '{}'", setting))?;
+            let value: f64 = value.parse().map_err(|_| format!("Invalid number in '{}'", setting))?;
+            match key {
+                "elo0" => elo0 = Some(value),
+                "elo1" => elo1 = Some(value),
+                "alpha" => alpha = value,
+                "beta" => beta = value,
+                _ => return Err(format!("Unknown SPRT setting '{}'; expected elo0, elo1, alpha or beta", key)),
+            }
+        }
+        let (Some(elo0), Some(elo1)) = (elo0, elo1) else {
+            return Err("The SPRT needs both elo0 and elo1 (e.g. --sprt elo0=0 elo1=20)".to_string());
+        };
+        if elo1 <= elo0 {
+            return Err(format!("elo1 ({}) must be greater than elo0 ({})", elo1, elo0));
+        }
+        for (name, rate) in [("alpha", alpha), ("beta", beta)] {
+            if rate.is_nan() || rate <= 0.0 || rate >= 0.5 {
+                return Err(format!("{} must be between 0 and 0.5, got {}", name, rate));
+            }
+        }
+        Ok(Self { elo0, elo1, alpha, beta, wins: 0, draws: 0, losses: 0 })
+    }
+
+    /// Records the first agent's points in a game: 1.0, 0.5 (a shared win) or 0.0.
+    pub fn record(&mut self, points: f64) {
+        if points > 0.5 {
+            self.wins += 1;
+        } else if points < 0.5 {
+            self.losses += 1;
+        } else {
+            self.draws += 1;
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// The first agent's points per game.
+    pub fn score(&self) -> Option<f64> {
+        let games = self.games();
+        (games > 0).then(|| (self.wins as f64 + 0.5 * self.draws as f64) / games as f64)
+    }
+
+    /// The Elo difference the score so far implies, unbounded if one side won every game.
+    pub fn elo_estimate(&self) -> Option<f64> {
+        self.score().map(|score| -400.0 * (1.0 / score - 1.0).log10())
+    }
+
+    /// The log-likelihood ratio of H1 against H0. Half a virtual game of each outcome is
+    /// added, so that the score varies even when one side has won every game.
+    pub fn llr(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        let (wins, draws, losses) = (self.wins as f64 + 0.5, self.draws as f64 + 0.5, self.losses as f64 + 0.5);
+        let games = wins + draws + losses;
+        let mean = (wins + 0.5 * draws) / games;
+        let variance = (wins + 0.25 * draws) / games - mean * mean;
+        let (score0, score1) = (expected_score(self.elo0), expected_score(self.elo1));
+        games * (score1 - score0) * (2.0 * mean - score0 - score1) / (2.0 * variance)
+    }
+
+    /// The LLR below which H0 is accepted and above which H1 is.
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    pub fn decision(&self) -> Option<Decision> {
+        let llr = self.llr();
+        let (lower, upper) = self.bounds();
+        if llr <= lower {
+            Some(Decision::H0)
+        } else if llr >= upper {
+            Some(Decision::H1)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Head-to-head tournaments between agents for `headless --tournament`: who plays whom, the
+//! crosstable of the results, and the Elo ratings they imply.
+
+use serde::Serialize;
+
+// Elo points per factor of 10 in the odds of winning.
+const ELO_SCALE: f64 = 400.0;
+const RATING_ITERATIONS: usize = 1000;
+
+/// One finished 2-player game between entrants `first` (seat 0) and `second` (seat 1).
+#[derive(Debug, Clone, Copy)]
+pub struct GameResult {
+    pub first: usize,
+    pub second: usize,
+    /// The first player's points: 1.0 for a win, 0.5 for a shared win, 0.0 for a loss. A
+    /// player that gives no legal move forfeits, losing the game.
+    pub points: f64,
+    /// The first player's final score minus the second's, or 0 for a forfeited game.
+    pub margin: i32,
+}
+
+/// Every pairing of `entrants` entrants, once each, lower index first.
+pub fn round_robin_pairings(entrants: usize) -> Vec<(usize, usize)> {
+    (0..entrants)
+        .flat_map(|a| (a + 1..entrants).map(move |b| (a, b)))
+        .collect()
+}
+
+/// One entrant's results against one opponent, or against the whole field.
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct Cell {
+    pub games: u32,
+    /// Wins plus half the shared wins.
+    pub points: f64,
+    /// The sum of the final score differences.
+    pub margin: i64,
+}
+
+impl Cell {
+    fn add(&mut self, points: f64, margin: i32) {
+        self.games += 1;
+        self.points += points;
+        self.margin += margin as i64;
+    }
+
+    /// Points per game, or `None` before any games.
+    pub fn score_rate(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.points / self.games as f64)
+    }
+
+    pub fn average_margin(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.margin as f64 / self.games as f64)
+    }
+}
+
+/// Every entrant's results against every other, from both seats.
+#[derive(Debug, Serialize)]
+pub struct Crosstable {
+    pub entrants: Vec<String>,
+    /// `cells[a][b]` is `a`'s results against `b`.
+    pub cells: Vec<Vec<Cell>>,
+}
+
+impl Crosstable {
+    pub fn new(entrants: Vec<String>) -> Self {
+        let cells = vec![vec![Cell::default(); entrants.len()]; entrants.len()];
+        Self { entrants, cells }
+    }
+
+    pub fn record(&mut self, result: &GameResult) {
+        self.cells[result.first][result.second].add(result.points, result.margin);
+        self.cells[result.second][result.first].add(1.0 - result.points, -result.margin);
+    }
+
+    /// `entrant`'s results against the whole field.
+    pub fn total(&self, entrant: usize) -> Cell {
+        self.cells[entrant].iter().fold(Cell::default(), |mut total, cell| {
+            total.games += cell.games;
+            total.points += cell.points;
+            total.margin += cell.margin;
+            total
+        })
+    }
+
+    /// Elo ratings that best explain the results (a Bradley-Terry fit, with shared wins counted
+    /// as half a win each way), relative to an average of 0. Each entrant also gets one
+    /// virtual draw against an average opponent, so that ratings stay finite for an entrant
+    /// that won or lost every game.
+    pub fn ratings(&self) -> Vec<f64> {
+        let n = self.entrants.len();
+        let mut strengths = vec![1.0; n];
+        for _ in 0..RATING_ITERATIONS {
+            // Minorization-maximization: strength = points / sum of games / combined strength.
+            let updated: Vec<f64> = (0..n)
+                .map(|a| {
+                    let mut expected = 1.0 / (strengths[a] + 1.0);
+                    for b in (0..n).filter(|&b| b != a) {
+                        expected += self.cells[a][b].games as f64 / (strengths[a] + strengths[b]);
+                    }
+                    (self.total(a).points + 0.5) / expected
+                })
+                .collect();
+            strengths = updated;
+        }
+        let mean_log = strengths.iter().map(|s| s.log10()).sum::<f64>() / n.max(1) as f64;
+        strengths.iter().map(|s| ELO_SCALE * (s.log10() - mean_log)).collect()
+    }
+
+    /// Entrants from the highest score rate down.
+    pub fn standings(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.entrants.len()).collect();
+        order.sort_by(|&a, &b| {
+            let rate = |idx: usize| self.total(idx).score_rate().unwrap_or(0.0);
+            rate(b).total_cmp(&rate(a))
+        });
+        order
+    }
+
+    /// Prints the standings with each entrant's score rate and average score difference against
+    /// every opponent, which are numbered by their place in the standings.
+    pub fn print(&self) {
+        let order = self.standings();
+        println!("\n--- Crosstable (score rate, average score difference) ---");
+        for (place, &idx) in order.iter().enumerate() {
+            println!("  {}. {}", place + 1, self.entrants[idx]);
+        }
+        print!("\n{:>4}", "");
+        for place in 1..=order.len() {
+            print!(" {:>14}", format!("vs {}", place));
+        }
+        println!(" {:>14}", "total");
+        let format_cell = |cell: &Cell| match (cell.score_rate(), cell.average_margin()) {
+            (Some(rate), Some(margin)) => format!("{:.1}% {:+.1}", rate * 100.0, margin),
+            _ => "-".to_string(),
+        };
+        for (place, &idx) in order.iter().enumerate() {
+            print!("{:>4}", format!("{}.", place + 1));
+            for &opponent in &order {
+                print!(" {:>14}", format_cell(&self.cells[idx][opponent]));
+            }
+            println!(" {:>14}", format_cell(&self.total(idx)));
+        }
+    }
+}
+
+/// The pairings of a Swiss-system tournament: every round pairs entrants with similar running
+/// scores who haven't played each other yet, so a large field sorts itself out in a few
+/// rounds instead of playing every pairing.
+pub struct Swiss {
+    /// 1 for each match won (or bye), 0.5 for each match drawn.
+    pub match_points: Vec<f64>,
+    opponents: Vec<Vec<usize>>,
+    had_bye: Vec<bool>,
+}
+
+impl Swiss {
+    pub fn new(entrants: usize) -> Self {
+        Self { match_points: vec![0.0; entrants], opponents: vec![Vec::new(); entrants], had_bye: vec![false; entrants] }
+    }
+
+    /// Entrants by match points, then by game points in `crosstable`.
+    pub fn standings(&self, crosstable: &Crosstable) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.match_points.len()).collect();
+        order.sort_by(|&a, &b| {
+            self.match_points[b].total_cmp(&self.match_points[a])
+                .then(crosstable.total(b).points.total_cmp(&crosstable.total(a).points))
+        });
+        order
+    }
+
+    /// The next round's pairings, and the entrant sitting it out when there's an odd number:
+    /// the lowest-placed one that hasn't had a bye yet, who is awarded the match. Going down
+    /// the standings, each entrant is paired with the next one it hasn't played, or the next
+    /// one at all if it has played them all.
+    pub fn pair_round(&mut self, crosstable: &Crosstable) -> (Vec<(usize, usize)>, Option<usize>) {
+        let mut unpaired = self.standings(crosstable);
+        let bye = if unpaired.len() % 2 == 1 {
+            let pos = unpaired.iter().rposition(|&idx| !self.had_bye[idx]).unwrap_or(unpaired.len() - 1);
+            let idx = unpaired.remove(pos);
+            self.had_bye[idx] = true;
+            self.match_points[idx] += 1.0;
+            Some(idx)
+        } else {
+            None
+        };
+        let mut pairings = Vec::new();
+        while unpaired.len() >= 2 {
+            let a = unpaired.remove(0);
+            let pos = unpaired.iter().position(|b| !self.opponents[a].contains(b)).unwrap_or(0);
+            let b = unpaired.remove(pos);
+            self.opponents[a].push(b);
+            self.opponents[b].push(a);
+            pairings.push((a, b));
+        }
+        (pairings, bye)
+    }
+
+    /// Awards the match between `a` and `b` to whoever scored more points in its games.
+    pub fn record_match(&mut self, a: usize, b: usize, a_points: f64, b_points: f64) {
+        match a_points.total_cmp(&b_points) {
+            std::cmp::Ordering::Greater => self.match_points[a] += 1.0,
+            std::cmp::Ordering::Less => self.match_points[b] += 1.0,
+            std::cmp::Ordering::Equal => {
+                self.match_points[a] += 0.5;
+                self.match_points[b] += 0.5;
+            }
+        }
+    }
+}
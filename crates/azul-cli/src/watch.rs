@@ -0,0 +1,92 @@
+//! `headless --watch`: plays one game between engine seats and prints every position, move
+//! and scoring event in color, for eyeballing how agents play without reading JSON logs.
+
+use crate::cli_human_agent::{describe_move, describe_scoring_event, print_final_summary, terminal_tile_style};
+use azul_ai::AIAgent;
+use azul_core::render::{self, TileStyle};
+use azul_core::{GamePhase, GameState, ScoringEvent};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+
+/// ANSI escapes for the terminal, or nothing when stdout isn't one or NO_COLOR is set.
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    tiles: TileStyle,
+}
+
+impl Palette {
+    fn detect() -> Self {
+        Self { tiles: terminal_tile_style() }
+    }
+
+    fn style(&self, escape: &str, text: &str) -> String {
+        if self.tiles == TileStyle::Ansi { format!("{}{}{}", escape, text, RESET) } else { text.to_string() }
+    }
+}
+
+fn print_indented(indent: &str, text: &str) {
+    for line in text.lines() {
+        println!("{}{}", indent, line);
+    }
+}
+
+fn print_table(palette: Palette, game: &GameState, seat_names: &[String]) {
+    print_indented("  ", &render::factories(game, palette.tiles));
+    for (i, board) in game.players.iter().enumerate() {
+        let to_move = if i == game.current_player_idx && game.phase() == GamePhase::Drafting { " (to move)" } else { "" };
+        println!("\n  {}", palette.style(BOLD, &format!("Player {} ({}): {} points{}", i + 1, seat_names[i], board.score, to_move)));
+        print_indented("    ", &render::board(board, palette.tiles));
+    }
+}
+
+/// Plays `game` to the end with `agents`, printing the table before every move, each move
+/// with the time it took, and each round's scoring, pausing `delay` after each move. Returns
+/// false if an agent has no move or plays an illegal one.
+pub fn watch_game(game: &mut GameState, agents: &mut [Box<dyn AIAgent>], seat_names: &[String], delay: Duration) -> bool {
+    let palette = Palette::detect();
+    let mut round = 1;
+    while !game.is_game_over() {
+        println!("\n{}", palette.style(BOLD, &format!("=== Round {} ===", round)));
+        while game.phase() == GamePhase::Drafting {
+            println!();
+            print_table(palette, game, seat_names);
+            let player = game.current_player_idx;
+            let start_time = Instant::now();
+            let Some(chosen_move) = agents[player].get_move(game) else {
+                println!("\nPlayer {} has no move. Stopping the game.", player + 1);
+                return false;
+            };
+            let seconds = start_time.elapsed().as_secs_f64();
+            println!(
+                "\n  {} {} [{}] ({:.2}s)",
+                palette.style(BOLD, &format!("Player {} ({}):", player + 1, seat_names[player])),
+                describe_move(&chosen_move), chosen_move, seconds
+            );
+            if let Err(e) = game.advance(Some(&chosen_move)) {
+                println!("\nPlayer {} chose an illegal move ({}). Stopping the game.", player + 1, e);
+                return false;
+            }
+            if !delay.is_zero() {
+                thread::sleep(delay);
+            }
+        }
+        println!("\n{}", palette.style(BOLD, &format!("--- Round {} scoring ---", round)));
+        let events = game.advance(None).expect("the drafting phase is over");
+        for event in &events {
+            let escape = match event {
+                ScoringEvent::TilePlaced { .. } => "\x1b[32m",
+                ScoringEvent::FloorPenalty { .. } => "\x1b[31m",
+                ScoringEvent::EndGameBonus { .. } => "\x1b[1;33m",
+            };
+            println!("  {}", palette.style(escape, &describe_scoring_event(event)));
+        }
+        round += 1;
+    }
+    println!("\n{}", palette.style(BOLD, "=== Final position ==="));
+    print_table(palette, game, seat_names);
+    print_final_summary(game, seat_names);
+    true
+}
@@ -0,0 +1,183 @@
+//! `GameStateBuilder` for setting up arbitrary positions: tests, puzzles, and analysis.
+
+use crate::{factory_count, GameRng, GameState, PlayerBoard, Tile, ALL_COLORS, TILES_PER_COLOR, WALL_LAYOUT};
+use alloc::{format, string::String, vec, vec::Vec};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Builds a mid-game position piece by piece. Every tile not placed explicitly goes into the
+/// bag, which is shuffled with a seeded `GameRng` so the built game refills reproducibly.
+///
+/// ```
+/// use azul_core::{GameStateBuilder, Tile::*};
+///
+/// let state = GameStateBuilder::new(2)
+///     .factory(0, [Blue, Blue, Red, White])
+///     .wall(0, 0, [Blue, Yellow])
+///     .pattern_line(0, 2, Red, 2)
+///     .build()
+///     .unwrap();
+/// assert_eq!(state.players[0].pattern_lines[2], vec![Red, Red]);
+/// ```
+///
+/// Player, factory, and row indices are 0-based. A player or row index out of range panics; a
+/// factory the table doesn't have, e.g. because the player count is invalid, is reported by
+/// `build`.
+///
+/// ```
+/// use azul_core::{GameStateBuilder, Tile::*};
+///
+/// assert!(GameStateBuilder::new(2).factory(5, [Red]).build().is_err());
+/// assert!(GameStateBuilder::new(1).factory(0, [Red]).build().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameStateBuilder {
+    num_players: usize,
+    players: Vec<PlayerBoard>,
+    factories: Vec<Vec<Tile>>,
+    center: Vec<Tile>,
+    discard_pile: Vec<Tile>,
+    current_player_idx: usize,
+    first_player_marker_holder: Option<usize>,
+    end_game_triggered: bool,
+    seed: u64,
+    /// The first factory index that was out of range, for `build` to report.
+    missing_factory: Option<usize>,
+}
+
+impl GameStateBuilder {
+    /// An empty table: no tiles in the factories or the center, the first-player marker in the
+    /// center, and player 0 to move.
+    pub fn new(num_players: usize) -> Self {
+        Self {
+            num_players,
+            players: (0..num_players).map(|_| PlayerBoard::new()).collect(),
+            factories: vec![Vec::new(); factory_count(num_players).unwrap_or(0)],
+            center: Vec::new(),
+            discard_pile: Vec::new(),
+            current_player_idx: 0,
+            first_player_marker_holder: None,
+            end_game_triggered: false,
+            seed: 0,
+            missing_factory: None,
+        }
+    }
+
+    pub fn factory(mut self, factory_idx: usize, tiles: impl IntoIterator<Item = Tile>) -> Self {
+        match self.factories.get_mut(factory_idx) {
+            Some(factory) => *factory = tiles.into_iter().collect(),
+            None => {
+                self.missing_factory.get_or_insert(factory_idx);
+            }
+        }
+        self
+    }
+
+    pub fn center(mut self, tiles: impl IntoIterator<Item = Tile>) -> Self {
+        self.center = tiles.into_iter().collect();
+        self
+    }
+
+    pub fn discard_pile(mut self, tiles: impl IntoIterator<Item = Tile>) -> Self {
+        self.discard_pile = tiles.into_iter().collect();
+        self
+    }
+
+    /// Puts each tile on `player`'s wall in `row`, in the column the wall layout gives it.
+    pub fn wall(mut self, player: usize, row: usize, tiles: impl IntoIterator<Item = Tile>) -> Self {
+        for tile in tiles {
+            let col = WALL_LAYOUT[row].iter().position(|&t| t == tile).unwrap();
+            self.players[player].wall[row][col] = Some(tile);
+        }
+        self
+    }
+
+    pub fn pattern_line(mut self, player: usize, line: usize, tile: Tile, count: usize) -> Self {
+        self.players[player].pattern_lines[line] = vec![tile; count];
+        self
+    }
+
+    pub fn floor(mut self, player: usize, tiles: impl IntoIterator<Item = Tile>) -> Self {
+        self.players[player].floor_line = tiles.into_iter().collect();
+        self
+    }
+
+    pub fn score(mut self, player: usize, score: u32) -> Self {
+        self.players[player].score = score;
+        self
+    }
+
+    pub fn current_player(mut self, player: usize) -> Self {
+        self.current_player_idx = player;
+        self
+    }
+
+    /// Gives the first-player marker to `player`, or puts it back in the center with `None`.
+    pub fn first_player_marker(mut self, player: Option<usize>) -> Self {
+        self.first_player_marker_holder = player;
+        self
+    }
+
+    pub fn end_game_triggered(mut self, triggered: bool) -> Self {
+        self.end_game_triggered = triggered;
+        self
+    }
+
+    /// Seeds the bag shuffle and every later refill. Defaults to 0.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Fills the bag with the tiles that weren't placed and checks the position with
+    /// `GameState::check_invariants`.
+    pub fn build(self) -> Result<GameState, String> {
+        if factory_count(self.num_players).is_none() {
+            return Err(format!("invalid player count {}", self.num_players));
+        }
+        if let Some(idx) = self.missing_factory {
+            return Err(format!("factory {} doesn't exist with {} players", idx, self.num_players));
+        }
+
+        let mut remaining = [TILES_PER_COLOR; 5];
+        let placed = self.factories.iter().flatten()
+            .chain(&self.center)
+            .chain(&self.discard_pile)
+            .chain(self.players.iter().flat_map(|p| {
+                p.pattern_lines.iter().flatten()
+                    .chain(&p.floor_line)
+                    .chain(p.wall.iter().flatten().flatten())
+            }));
+        for tile in placed {
+            let idx = ALL_COLORS.iter().position(|c| c == tile).unwrap();
+            remaining[idx] = remaining[idx].checked_sub(1)
+                .ok_or_else(|| format!("more than {} {:?} tiles placed", TILES_PER_COLOR, tile))?;
+        }
+
+        let mut rng = GameRng::seed_from_u64(self.seed);
+        let mut tile_bag: Vec<Tile> = ALL_COLORS.iter()
+            .zip(remaining)
+            .flat_map(|(&tile, count)| core::iter::repeat_n(tile, count))
+            .collect();
+        tile_bag.shuffle(&mut rng);
+
+        let mut players = self.players;
+        if let Some(holder) = self.first_player_marker_holder {
+            players[holder].has_first_player_marker = true;
+        }
+
+        let state = GameState {
+            players,
+            factories: self.factories,
+            center: self.center,
+            tile_bag,
+            discard_pile: self.discard_pile,
+            current_player_idx: self.current_player_idx,
+            first_player_marker_in_center: self.first_player_marker_holder.is_none(),
+            end_game_triggered: self.end_game_triggered,
+            rng: Some(rng),
+        };
+        state.check_invariants()?;
+        Ok(state)
+    }
+}
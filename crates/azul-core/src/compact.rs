@@ -0,0 +1,483 @@
+//! A packed copy of a `GameState` for searches that copy the position at every node. Walls are
+//! bit rows, pattern lines are a color and a count, and the factories, center, floor lines, bag
+//! and discard pile are per-color tile counts, so a `CompactState` lives on the stack and
+//! copies without allocating.
+//!
+//! Packing drops what the rules never look at: the order of the tiles in each of those
+//! places, and a seeded game's RNG. `to_game_state` lays tiles out in color order, and refills
+//! draw from the RNG they are given, so a packed position is also a determinized one.
+
+use crate::{
+    EndGameBonuses, GamePhase, GameState, Move, MoveDestination, MoveError, MoveSource, PlayerBoard, Tile,
+    ALL_COLORS, FLOOR_PENALTY_VALUES, NUM_COLS, NUM_ROWS, WALL_LAYOUT,
+};
+use alloc::vec::Vec;
+use rand::Rng;
+
+/// The most seats and factories a game can have.
+const MAX_PLAYERS: usize = 4;
+const MAX_FACTORIES: usize = 9;
+/// A wall row with every slot tiled.
+const FULL_ROW: u8 = (1 << NUM_COLS) - 1;
+
+/// How many tiles of each color, indexed in `ALL_COLORS` order.
+pub type TileCounts = [u8; 5];
+
+fn color_index(tile: Tile) -> usize {
+    tile as usize
+}
+
+/// The wall column that `tile` goes to in `row`: each row shifts the layout one to the right.
+fn wall_column(row: usize, tile: Tile) -> usize {
+    (color_index(tile) + row) % NUM_COLS
+}
+
+fn count_tiles(tiles: &[Tile]) -> TileCounts {
+    let mut counts = [0; 5];
+    for &tile in tiles {
+        counts[color_index(tile)] += 1;
+    }
+    counts
+}
+
+fn unpack_tiles(counts: &TileCounts) -> Vec<Tile> {
+    ALL_COLORS.iter().zip(counts)
+        .flat_map(|(&tile, &count)| core::iter::repeat_n(tile, count as usize))
+        .collect()
+}
+
+fn total(counts: &TileCounts) -> usize {
+    counts.iter().map(|&count| count as usize).sum()
+}
+
+fn add(counts: &mut TileCounts, other: &TileCounts) {
+    for (count, &more) in counts.iter_mut().zip(other) {
+        *count += more;
+    }
+}
+
+/// A packed `PlayerBoard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompactBoard {
+    pub score: u32,
+    /// One bit per tiled slot: bit `col` of `wall[row]`.
+    pub wall: [u8; NUM_ROWS],
+    /// Each pattern line's color, `None` while it is empty, and how many tiles it holds.
+    pub pattern_lines: [(Option<Tile>, u8); NUM_ROWS],
+    pub floor: TileCounts,
+    pub has_first_player_marker: bool,
+}
+
+impl From<&PlayerBoard> for CompactBoard {
+    fn from(board: &PlayerBoard) -> Self {
+        let mut wall = [0; NUM_ROWS];
+        for (bits, row) in wall.iter_mut().zip(&board.wall) {
+            for (col, slot) in row.iter().enumerate() {
+                if slot.is_some() {
+                    *bits |= 1 << col;
+                }
+            }
+        }
+        let mut pattern_lines = [(None, 0); NUM_ROWS];
+        for (packed, line) in pattern_lines.iter_mut().zip(&board.pattern_lines) {
+            *packed = (line.first().copied(), line.len() as u8);
+        }
+        Self {
+            score: board.score,
+            wall,
+            pattern_lines,
+            floor: count_tiles(&board.floor_line),
+            has_first_player_marker: board.has_first_player_marker,
+        }
+    }
+}
+
+impl CompactBoard {
+    pub fn to_player_board(&self) -> PlayerBoard {
+        let mut board = PlayerBoard::new();
+        board.score = self.score;
+        for (row, (slots, &bits)) in board.wall.iter_mut().zip(&self.wall).enumerate() {
+            for (col, slot) in slots.iter_mut().enumerate() {
+                if bits & (1 << col) != 0 {
+                    *slot = Some(WALL_LAYOUT[row][col]);
+                }
+            }
+        }
+        for (line, &(color, count)) in board.pattern_lines.iter_mut().zip(&self.pattern_lines) {
+            if let Some(tile) = color {
+                line.extend(core::iter::repeat_n(tile, count as usize));
+            }
+        }
+        board.floor_line = unpack_tiles(&self.floor);
+        board.has_first_player_marker = self.has_first_player_marker;
+        board
+    }
+
+    fn has_on_wall(&self, row: usize, tile: Tile) -> bool {
+        self.wall[row] & (1 << wall_column(row, tile)) != 0
+    }
+
+    pub fn is_placement_valid(&self, row: usize, tile: Tile) -> bool {
+        let (color, count) = self.pattern_lines[row];
+        count as usize <= row && color.is_none_or(|color| color == tile) && !self.has_on_wall(row, tile)
+    }
+
+    /// Puts `count` tiles of `tile` on `destination`, overflowing to the floor.
+    fn place_tiles(&mut self, tile: Tile, count: u8, destination: &MoveDestination) {
+        let overflow = match *destination {
+            MoveDestination::Floor => count,
+            MoveDestination::PatternLine(row) => {
+                let line = &mut self.pattern_lines[row];
+                let placed = count.min(row as u8 + 1 - line.1);
+                *line = (Some(tile), line.1 + placed);
+                count - placed
+            }
+        };
+        self.floor[color_index(tile)] += overflow;
+    }
+
+    fn will_complete_horizontal_row(&self, row: usize) -> bool {
+        self.pattern_lines[row].1 as usize == row + 1 && self.wall[row].count_ones() == 4
+    }
+
+    pub fn count_complete_rows(&self) -> usize {
+        self.wall.iter().filter(|&&bits| bits == FULL_ROW).count()
+    }
+
+    /// Floor tiles plus the first-player marker, if this player holds it.
+    pub fn floor_items_count(&self) -> usize {
+        total(&self.floor) + usize::from(self.has_first_player_marker)
+    }
+
+    /// The penalty the floor line would cost at the end of the round, before it is capped by
+    /// the player's score.
+    pub fn floor_penalty(&self) -> u32 {
+        FLOOR_PENALTY_VALUES[..self.floor_items_count().min(FLOOR_PENALTY_VALUES.len())].iter().sum()
+    }
+
+    /// What a tile placed at (row, col) scores: the runs it joins, or 1 if it joins none.
+    fn placement_points(&self, row: usize, col: usize) -> u32 {
+        let filled = |r: usize, c: usize| self.wall[r] & (1 << c) != 0;
+        let horizontal = 1 + (0..col).rev().take_while(|&c| filled(row, c)).count()
+            + (col + 1..NUM_COLS).take_while(|&c| filled(row, c)).count();
+        let vertical = 1 + (0..row).rev().take_while(|&r| filled(r, col)).count()
+            + (row + 1..NUM_ROWS).take_while(|&r| filled(r, col)).count();
+        let run_points = |run: usize| if run > 1 { run as u32 } else { 0 };
+        (run_points(horizontal) + run_points(vertical)).max(1)
+    }
+
+    /// Moves every full pattern line to the wall, sending the rest of each line to `discard`.
+    /// Returns the points scored and whether a wall row was completed.
+    fn tile_pattern_lines(&mut self, discard: &mut TileCounts) -> (u32, bool) {
+        let mut points = 0;
+        let mut completed_row = false;
+        for row in 0..NUM_ROWS {
+            let (Some(tile), count) = self.pattern_lines[row] else { continue };
+            if count as usize != row + 1 || self.has_on_wall(row, tile) {
+                continue;
+            }
+            let col = wall_column(row, tile);
+            points += self.placement_points(row, col);
+            self.wall[row] |= 1 << col;
+            completed_row |= self.wall[row] == FULL_ROW;
+            discard[color_index(tile)] += count - 1;
+            self.pattern_lines[row] = (None, 0);
+        }
+        (points, completed_row)
+    }
+
+    /// Tiles the full pattern lines and applies the floor penalty, like
+    /// `PlayerBoard::run_tiling_phase`. Returns whether a wall row was completed.
+    fn run_tiling_phase(&mut self, discard: &mut TileCounts) -> bool {
+        let (points, completed_row) = self.tile_pattern_lines(discard);
+        self.score += points;
+        self.score -= self.floor_penalty().min(self.score);
+        add(discard, &self.floor);
+        self.floor = [0; 5];
+        self.has_first_player_marker = false;
+        completed_row
+    }
+
+    /// What the tiling phase would score for this board if the round ended now: the points for
+    /// every full pattern line minus the floor penalty, without the clamp at zero.
+    pub fn projected_round_points(&self) -> i32 {
+        let mut board = *self;
+        let (points, _) = board.tile_pattern_lines(&mut [0; 5]);
+        points as i32 - self.floor_penalty() as i32
+    }
+
+    pub fn end_game_bonuses(&self) -> EndGameBonuses {
+        let rows = self.count_complete_rows();
+        let columns = (0..NUM_COLS).filter(|&col| self.wall.iter().all(|&bits| bits & (1 << col) != 0)).count();
+        let colors = ALL_COLORS.iter()
+            .filter(|&&tile| (0..NUM_ROWS).all(|row| self.has_on_wall(row, tile)))
+            .count();
+        EndGameBonuses { rows, columns, colors }
+    }
+}
+
+/// A packed `GameState`. Moves, tiling and refills follow the same rules, so a search can play
+/// on a `CompactState` and only unpack the positions it hands to code written for `GameState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompactState {
+    num_players: u8,
+    num_factories: u8,
+    players: [CompactBoard; MAX_PLAYERS],
+    factories: [TileCounts; MAX_FACTORIES],
+    center: TileCounts,
+    bag: TileCounts,
+    discard_pile: TileCounts,
+    current_player_idx: u8,
+    first_player_marker_in_center: bool,
+    end_game_triggered: bool,
+}
+
+/// Panics if the state has more than four players or nine factories, which no game does.
+impl From<&GameState> for CompactState {
+    fn from(game_state: &GameState) -> Self {
+        assert!(game_state.players.len() <= MAX_PLAYERS, "a game has at most {} players", MAX_PLAYERS);
+        assert!(game_state.factories.len() <= MAX_FACTORIES, "a game has at most {} factories", MAX_FACTORIES);
+        let empty_board = CompactBoard::from(&PlayerBoard::new());
+        let mut players = [empty_board; MAX_PLAYERS];
+        for (packed, board) in players.iter_mut().zip(&game_state.players) {
+            *packed = CompactBoard::from(board);
+        }
+        let mut factories = [[0; 5]; MAX_FACTORIES];
+        for (packed, factory) in factories.iter_mut().zip(&game_state.factories) {
+            *packed = count_tiles(factory);
+        }
+        Self {
+            num_players: game_state.players.len() as u8,
+            num_factories: game_state.factories.len() as u8,
+            players,
+            factories,
+            center: count_tiles(&game_state.center),
+            bag: count_tiles(&game_state.tile_bag),
+            discard_pile: count_tiles(&game_state.discard_pile),
+            current_player_idx: game_state.current_player_idx as u8,
+            first_player_marker_in_center: game_state.first_player_marker_in_center,
+            end_game_triggered: game_state.end_game_triggered,
+        }
+    }
+}
+
+impl CompactState {
+    /// Unpacks the position, with tiles in color order and no RNG.
+    pub fn to_game_state(&self) -> GameState {
+        GameState {
+            players: self.players().iter().map(CompactBoard::to_player_board).collect(),
+            factories: self.factories().iter().map(unpack_tiles).collect(),
+            center: unpack_tiles(&self.center),
+            tile_bag: unpack_tiles(&self.bag),
+            discard_pile: unpack_tiles(&self.discard_pile),
+            current_player_idx: self.current_player_idx as usize,
+            first_player_marker_in_center: self.first_player_marker_in_center,
+            end_game_triggered: self.end_game_triggered,
+            rng: None,
+        }
+    }
+
+    pub fn players(&self) -> &[CompactBoard] {
+        &self.players[..self.num_players as usize]
+    }
+
+    pub fn factories(&self) -> &[TileCounts] {
+        &self.factories[..self.num_factories as usize]
+    }
+
+    pub fn center(&self) -> &TileCounts {
+        &self.center
+    }
+
+    pub fn bag(&self) -> &TileCounts {
+        &self.bag
+    }
+
+    pub fn discard_pile(&self) -> &TileCounts {
+        &self.discard_pile
+    }
+
+    pub fn current_player_idx(&self) -> usize {
+        self.current_player_idx as usize
+    }
+
+    pub fn first_player_marker_in_center(&self) -> bool {
+        self.first_player_marker_in_center
+    }
+
+    pub fn end_game_triggered(&self) -> bool {
+        self.end_game_triggered
+    }
+
+    /// The legal moves, in the order of `GameState::get_legal_moves` except that each source's
+    /// colors come in `ALL_COLORS` order.
+    pub fn get_legal_moves(&self) -> Vec<Move> {
+        let board = &self.players[self.current_player_idx()];
+        let sources = self.factories().iter().enumerate()
+            .map(|(idx, tiles)| (MoveSource::Factory(idx), tiles))
+            .chain([(MoveSource::Center, &self.center)]);
+        let mut legal_moves = Vec::new();
+        for (source, tiles) in sources {
+            for (&tile, _) in ALL_COLORS.iter().zip(tiles).filter(|(_, &count)| count > 0) {
+                for row in (0..NUM_ROWS).filter(|&row| board.is_placement_valid(row, tile)) {
+                    legal_moves.push(Move { source: source.clone(), tile, destination: MoveDestination::PatternLine(row) });
+                }
+                legal_moves.push(Move { source: source.clone(), tile, destination: MoveDestination::Floor });
+            }
+        }
+        legal_moves
+    }
+
+    /// Checks `player_move` against the current position, like `GameState::validate_move`.
+    pub fn validate_move(&self, player_move: &Move) -> Result<(), MoveError> {
+        let tiles = match player_move.source {
+            MoveSource::Factory(idx) => self.factories().get(idx).ok_or(MoveError::NoSuchFactory(idx))?,
+            MoveSource::Center => &self.center,
+        };
+        if total(tiles) == 0 {
+            return Err(MoveError::EmptySource(player_move.source.clone()));
+        }
+        if tiles[color_index(player_move.tile)] == 0 {
+            return Err(MoveError::ColorNotInSource { source: player_move.source.clone(), tile: player_move.tile });
+        }
+
+        if let MoveDestination::PatternLine(row) = player_move.destination {
+            let board = &self.players[self.current_player_idx()];
+            let &(color, count) = board.pattern_lines.get(row).ok_or(MoveError::NoSuchPatternLine(row))?;
+            if count as usize > row {
+                return Err(MoveError::PatternLineFull(row));
+            }
+            if let Some(line_color) = color.filter(|&color| color != player_move.tile) {
+                return Err(MoveError::WrongColorOnLine { line: row, line_color, tile: player_move.tile });
+            }
+            if board.has_on_wall(row, player_move.tile) {
+                return Err(MoveError::WallSlotOccupied { row, tile: player_move.tile });
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies `player_move` without checking it, like `GameState::apply_move`.
+    pub fn apply_move(&mut self, player_move: &Move) {
+        let color = color_index(player_move.tile);
+        let player = &mut self.players[self.current_player_idx as usize];
+        let taken = match player_move.source {
+            MoveSource::Factory(idx) => {
+                let mut factory = core::mem::take(&mut self.factories[idx]);
+                let taken = core::mem::take(&mut factory[color]);
+                add(&mut self.center, &factory);
+                taken
+            }
+            MoveSource::Center => {
+                if self.first_player_marker_in_center {
+                    self.first_player_marker_in_center = false;
+                    player.has_first_player_marker = true;
+                }
+                core::mem::take(&mut self.center[color])
+            }
+        };
+        player.place_tiles(player_move.tile, taken, &player_move.destination);
+        if let MoveDestination::PatternLine(row) = player_move.destination {
+            if !self.end_game_triggered && player.will_complete_horizontal_row(row) {
+                self.end_game_triggered = true;
+            }
+        }
+        self.current_player_idx = (self.current_player_idx + 1) % self.num_players;
+    }
+
+    pub fn is_round_over(&self) -> bool {
+        self.factories().iter().all(|factory| total(factory) == 0) && total(&self.center) == 0
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        if !self.is_round_over() {
+            return GamePhase::Drafting;
+        }
+        // The move that triggers the end leaves a full pattern line, which tiling empties.
+        let awaiting_tiling = self.players().iter()
+            .any(|p| p.pattern_lines.iter().enumerate().any(|(row, &(_, count))| count as usize == row + 1));
+        if self.end_game_triggered && !awaiting_tiling {
+            GamePhase::GameOver
+        } else {
+            GamePhase::Tiling
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.phase() == GamePhase::GameOver
+    }
+
+    /// Tiles every wall and applies the floor penalties, like `GameState::run_tiling_phase`.
+    pub fn run_tiling_phase(&mut self) {
+        let num_players = self.num_players as usize;
+        let next_starter_idx = self.players().iter().position(|p| p.has_first_player_marker)
+            .unwrap_or(self.current_player_idx());
+        for player in &mut self.players[..num_players] {
+            if player.run_tiling_phase(&mut self.discard_pile) {
+                self.end_game_triggered = true;
+            }
+        }
+        self.current_player_idx = next_starter_idx as u8;
+    }
+
+    /// Deals four tiles to each factory, drawn at random from the bag, which is refilled from
+    /// the discard pile when it runs out.
+    pub fn refill_factories_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for idx in 0..self.num_factories as usize {
+            self.factories[idx] = [0; 5];
+            for _ in 0..4 {
+                if total(&self.bag) == 0 {
+                    if total(&self.discard_pile) == 0 { break; }
+                    self.bag = core::mem::take(&mut self.discard_pile);
+                }
+                let mut draw = rng.gen_range(0..total(&self.bag));
+                let color = self.bag.iter()
+                    .position(|&count| {
+                        let found = draw < count as usize;
+                        draw = draw.saturating_sub(count as usize);
+                        found
+                    })
+                    .expect("the draw falls within the bag");
+                self.bag[color] -= 1;
+                self.factories[idx][color] += 1;
+            }
+        }
+        self.center = [0; 5];
+        self.first_player_marker_in_center = true;
+    }
+
+    pub fn end_round_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.run_tiling_phase();
+        if !self.end_game_triggered {
+            self.refill_factories_with_rng(rng);
+        }
+    }
+
+    pub fn apply_end_game_scoring(&mut self) {
+        let num_players = self.num_players as usize;
+        for player in &mut self.players[..num_players] {
+            player.score += player.end_game_bonuses().total();
+        }
+    }
+
+    /// Drives the game one step, like `GameState::advance_with_rng`, without the scoring events.
+    pub fn advance_with_rng<R: Rng + ?Sized>(&mut self, player_move: Option<&Move>, rng: &mut R) -> Result<(), MoveError> {
+        match (self.phase(), player_move) {
+            (GamePhase::Drafting, Some(m)) => {
+                self.validate_move(m)?;
+                self.apply_move(m);
+                Ok(())
+            }
+            (GamePhase::Drafting, None) => Err(MoveError::MoveRequired),
+            (phase, Some(_)) => Err(MoveError::NotDrafting(phase)),
+            (GamePhase::Tiling, None) => {
+                self.end_round_with_rng(rng);
+                if self.is_game_over() {
+                    self.apply_end_game_scoring();
+                }
+                Ok(())
+            }
+            (GamePhase::GameOver, None) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,1209 @@
+//! The Azul rules engine: game state, move generation, tiling and scoring.
+//!
+//! Without the "std" feature the engine only needs `core` and `alloc`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "thread-rng")]
+use rand::thread_rng;
+
+mod builder;
+pub mod compact;
+#[cfg(feature = "openspiel")]
+pub mod openspiel;
+#[cfg(feature = "render")]
+pub mod render;
+mod rng;
+#[cfg(feature = "save")]
+pub mod save;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use builder::GameStateBuilder;
+pub use rng::GameRng;
+
+
+// --- Structs for Game Logic ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Tile {
+    Blue,
+    Yellow,
+    Red,
+    Black,
+    White,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TileBagSummary {
+    pub blue: usize,
+    pub yellow: usize,
+    pub red: usize,
+    pub black: usize,
+    pub white: usize,
+}
+
+impl TileBagSummary {
+    pub fn from_vec(tiles: &[Tile]) -> Self {
+        let mut summary = Self { blue: 0, yellow: 0, red: 0, black: 0, white: 0 };
+        for &tile in tiles {
+            match tile {
+                Tile::Blue => summary.blue += 1,
+                Tile::Yellow => summary.yellow += 1,
+                Tile::Red => summary.red += 1,
+                Tile::Black => summary.black += 1,
+                Tile::White => summary.white += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn total(&self) -> usize {
+        self.blue + self.yellow + self.red + self.black + self.white
+    }
+}
+
+impl fmt::Display for TileBagSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "{} tiles (B {}, Y {}, R {}, K {}, W {})",
+            self.total(), self.blue, self.yellow, self.red, self.black, self.white
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerBoard {
+    pub score: u32,
+    pub pattern_lines: Vec<Vec<Tile>>,
+    pub wall: Vec<Vec<Option<Tile>>>,
+    pub floor_line: Vec<Tile>,
+    pub has_first_player_marker: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameState {
+    pub players: Vec<PlayerBoard>,
+    pub factories: Vec<Vec<Tile>>,
+    pub center: Vec<Tile>,
+    pub tile_bag: Vec<Tile>,
+    pub discard_pile: Vec<Tile>,
+    pub current_player_idx: usize,
+    pub first_player_marker_in_center: bool,
+    pub end_game_triggered: bool,
+    /// Set by `new_with_seed`. When present, `refill_factories` and `end_round` draw from it
+    /// instead of the thread-local RNG, so the whole game replays from the seed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rng: Option<GameRng>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TurnState {
+    pub players: Vec<PlayerBoard>,
+    pub factories: Vec<Vec<Tile>>,
+    pub center: Vec<Tile>,
+    pub current_player_idx: usize,
+    pub first_player_marker_in_center: bool,
+    pub end_game_triggered: bool,
+}
+
+impl From<&GameState> for TurnState {
+    fn from(game_state: &GameState) -> Self {
+        Self {
+            players: game_state.players.clone(),
+            factories: game_state.factories.clone(),
+            center: game_state.center.clone(),
+            current_player_idx: game_state.current_player_idx,
+            first_player_marker_in_center: game_state.first_player_marker_in_center,
+            end_game_triggered: game_state.end_game_triggered,
+        }
+    }
+}
+
+/// What one player can see of a game: everything but the order of the bag and the RNG that
+/// deals the coming rounds, which would give away future draws. Built by
+/// `GameState::observation_for`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Observation {
+    /// The player it was made for.
+    pub player: usize,
+    pub players: Vec<PlayerBoard>,
+    pub factories: Vec<Vec<Tile>>,
+    pub center: Vec<Tile>,
+    /// How many tiles of each color are left in the bag.
+    pub bag: TileBagSummary,
+    pub discard_pile: Vec<Tile>,
+    pub current_player_idx: usize,
+    pub first_player_marker_in_center: bool,
+    pub end_game_triggered: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MoveSource {
+    Factory(usize),
+    Center,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MoveDestination {
+    PatternLine(usize),
+    Floor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Move {
+    pub source: MoveSource,
+    pub tile: Tile,
+    pub destination: MoveDestination,
+}
+
+/// What `GameState::apply_move_undoable` changed, for `GameState::undo` to take back.
+#[derive(Debug)]
+pub struct UndoToken {
+    player_move: Move,
+    /// Where the taken tiles sat in their source, one bit per position. A source never holds
+    /// more than 64 tiles: the center tops out at 3 per factory.
+    taken_positions: u64,
+    source_len: usize,
+    center_len: usize,
+    line_len: usize,
+    floor_len: usize,
+    took_first_player_marker: bool,
+    triggered_end_game: bool,
+}
+
+/// One step of the tiling phase's scoring, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScoringEvent {
+    /// A full pattern line moved a tile to the wall. `horizontal_points` and `vertical_points`
+    /// are the lengths of the runs the tile joined (0 when it has no neighbor in that
+    /// direction); `points` is what the player scored, at least 1.
+    TilePlaced {
+        player: usize,
+        row: usize,
+        col: usize,
+        tile: Tile,
+        horizontal_points: u32,
+        vertical_points: u32,
+        points: u32,
+        completed_row: bool,
+    },
+    /// Tiles (and the first-player marker) on the floor line cost points. `penalty` is what was
+    /// actually deducted, which is less than the floor's face value when the score hits zero.
+    FloorPenalty {
+        player: usize,
+        floor_tiles: usize,
+        penalty: u32,
+    },
+    /// The end-of-game bonuses for complete rows, columns and colors.
+    EndGameBonus {
+        player: usize,
+        bonuses: EndGameBonuses,
+        points: u32,
+    },
+}
+
+/// Where a game is in its round cycle. Derived from the state by `GameState::phase`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GamePhase {
+    /// Tiles are left on the table and the current player must take some.
+    Drafting,
+    /// The table is empty and the walls still have to be tiled, or the factories refilled.
+    Tiling,
+    /// The final round has been tiled.
+    GameOver,
+}
+
+// --- Errors ---
+
+/// Why a move was rejected by `GameState::try_apply_move`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// The source names a factory that doesn't exist in this game.
+    NoSuchFactory(usize),
+    /// The factory or the center has no tiles left.
+    EmptySource(MoveSource),
+    /// The source holds tiles, but none of the requested color.
+    ColorNotInSource { source: MoveSource, tile: Tile },
+    /// The destination names a pattern line outside 0-4.
+    NoSuchPatternLine(usize),
+    /// The pattern line is already full.
+    PatternLineFull(usize),
+    /// The pattern line already holds tiles of another color.
+    WrongColorOnLine { line: usize, line_color: Tile, tile: Tile },
+    /// The wall row already has a tile of this color.
+    WallSlotOccupied { row: usize, tile: Tile },
+    /// A move was given to `GameState::advance` outside the drafting phase.
+    NotDrafting(GamePhase),
+    /// `GameState::advance` was called without a move during the drafting phase.
+    MoveRequired,
+}
+
+/// Messages number factories, pattern lines and wall rows from 1, like the move notation.
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NoSuchFactory(idx) => write!(f, "factory {} does not exist", idx + 1),
+            MoveError::EmptySource(MoveSource::Factory(idx)) => write!(f, "factory {} is empty", idx + 1),
+            MoveError::EmptySource(MoveSource::Center) => write!(f, "the center is empty"),
+            MoveError::ColorNotInSource { source, tile } => match source {
+                MoveSource::Factory(idx) => write!(f, "factory {} has no {:?} tiles", idx + 1, tile),
+                MoveSource::Center => write!(f, "the center has no {:?} tiles", tile),
+            },
+            MoveError::NoSuchPatternLine(idx) => write!(f, "pattern line {} does not exist", idx + 1),
+            MoveError::PatternLineFull(idx) => write!(f, "pattern line {} is full", idx + 1),
+            MoveError::WrongColorOnLine { line, line_color, tile } => {
+                write!(f, "pattern line {} already holds {:?}, not {:?}", line + 1, line_color, tile)
+            }
+            MoveError::WallSlotOccupied { row, tile } => {
+                write!(f, "wall row {} already has a {:?} tile", row + 1, tile)
+            }
+            MoveError::NotDrafting(phase) => write!(f, "moves can't be made during {:?}", phase),
+            MoveError::MoveRequired => write!(f, "the player to move must choose a move"),
+        }
+    }
+}
+
+impl core::error::Error for MoveError {}
+
+// --- Constants ---
+pub const NUM_ROWS: usize = 5;
+pub const NUM_COLS: usize = 5;
+pub const TILES_PER_COLOR: usize = 20;
+pub const ALL_COLORS: [Tile; 5] = [Tile::Blue, Tile::Yellow, Tile::Red, Tile::Black, Tile::White];
+pub const FLOOR_PENALTY_VALUES: [u32; 7] = [1, 1, 2, 2, 2, 3, 3];
+pub const WALL_LAYOUT: [[Tile; NUM_COLS]; NUM_ROWS] = [
+    [Tile::Blue, Tile::Yellow, Tile::Red, Tile::Black, Tile::White],
+    [Tile::White, Tile::Blue, Tile::Yellow, Tile::Red, Tile::Black],
+    [Tile::Black, Tile::White, Tile::Blue, Tile::Yellow, Tile::Red],
+    [Tile::Red, Tile::Black, Tile::White, Tile::Blue, Tile::Yellow],
+    [Tile::Yellow, Tile::Red, Tile::Black, Tile::White, Tile::Blue],
+];
+
+// --- Game Logic Implementation ---
+
+/// The number of factories for a player count, or `None` outside 2-4 players.
+pub fn factory_count(num_players: usize) -> Option<usize> {
+    match num_players {
+        2 => Some(5),
+        3 => Some(7),
+        4 => Some(9),
+        _ => None,
+    }
+}
+
+impl GameState {
+    #[cfg(feature = "thread-rng")]
+    pub fn new(num_players: usize) -> Self {
+        Self::new_with_rng(num_players, &mut thread_rng())
+    }
+
+    /// Creates a reproducible game: the opening and every later refill are drawn from an RNG
+    /// seeded with `seed` and stored in the state.
+    pub fn new_with_seed(num_players: usize, seed: u64) -> Self {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut game_state = Self::new_with_rng(num_players, &mut rng);
+        game_state.rng = Some(rng);
+        game_state
+    }
+
+    /// Creates a new game, drawing the opening factories from `rng`.
+    pub fn new_with_rng<R: Rng + ?Sized>(num_players: usize, rng: &mut R) -> Self {
+        let players = (0..num_players).map(|_| PlayerBoard::new()).collect();
+        let mut tile_bag: Vec<Tile> = ALL_COLORS
+            .iter()
+            .flat_map(|&tile| core::iter::repeat_n(tile, TILES_PER_COLOR))
+            .collect();
+        tile_bag.shuffle(rng);
+
+        let num_factories = factory_count(num_players).expect("Invalid number of players.");
+
+        let mut game_state = Self {
+            players,
+            factories: vec![vec![]; num_factories],
+            center: Vec::new(),
+            tile_bag,
+            discard_pile: Vec::new(),
+            current_player_idx: 0,
+            first_player_marker_in_center: true,
+            end_game_triggered: false,
+            rng: None,
+        };
+        game_state.refill_factories_with_rng(rng);
+        game_state
+    }
+
+    #[cfg(feature = "thread-rng")]
+    pub fn from_turn_state(turn_state: &TurnState) -> Self {
+        Self::from_turn_state_with_rng(turn_state, &mut thread_rng())
+    }
+
+    /// Rebuilds a playable state from a logged `TurnState`. Logs don't record the bag or
+    /// discard pile, so every tile that isn't visible on the table goes back into the bag.
+    pub fn from_turn_state_with_rng<R: Rng + ?Sized>(turn_state: &TurnState, rng: &mut R) -> Self {
+        let mut remaining = [TILES_PER_COLOR; 5];
+        let mut take = |tile: &Tile| {
+            let idx = ALL_COLORS.iter().position(|c| c == tile).unwrap();
+            remaining[idx] = remaining[idx].saturating_sub(1);
+        };
+        turn_state.factories.iter().flatten().for_each(&mut take);
+        turn_state.center.iter().for_each(&mut take);
+        for player in &turn_state.players {
+            player.pattern_lines.iter().flatten().for_each(&mut take);
+            player.wall.iter().flatten().flatten().for_each(&mut take);
+            player.floor_line.iter().for_each(&mut take);
+        }
+
+        let mut tile_bag: Vec<Tile> = ALL_COLORS.iter()
+            .zip(remaining)
+            .flat_map(|(&tile, count)| core::iter::repeat_n(tile, count))
+            .collect();
+        tile_bag.shuffle(rng);
+
+        Self {
+            players: turn_state.players.clone(),
+            factories: turn_state.factories.clone(),
+            center: turn_state.center.clone(),
+            tile_bag,
+            discard_pile: Vec::new(),
+            current_player_idx: turn_state.current_player_idx,
+            first_player_marker_in_center: turn_state.first_player_marker_in_center,
+            end_game_triggered: turn_state.end_game_triggered,
+            rng: None,
+        }
+    }
+
+    #[cfg(feature = "thread-rng")]
+    pub fn from_observation(observation: &Observation) -> Self {
+        Self::from_observation_with_rng(observation, &mut thread_rng())
+    }
+
+    /// A playable state matching `observation`: the bag holds the counted tiles in an order
+    /// drawn from `rng`, and there is no stored RNG, so later rounds are dealt at random.
+    pub fn from_observation_with_rng<R: Rng + ?Sized>(observation: &Observation, rng: &mut R) -> Self {
+        let bag = &observation.bag;
+        let counts = [bag.blue, bag.yellow, bag.red, bag.black, bag.white];
+        let mut tile_bag: Vec<Tile> = ALL_COLORS.iter()
+            .zip(counts)
+            .flat_map(|(&tile, count)| core::iter::repeat_n(tile, count))
+            .collect();
+        tile_bag.shuffle(rng);
+
+        Self {
+            players: observation.players.clone(),
+            factories: observation.factories.clone(),
+            center: observation.center.clone(),
+            tile_bag,
+            discard_pile: observation.discard_pile.clone(),
+            current_player_idx: observation.current_player_idx,
+            first_player_marker_in_center: observation.first_player_marker_in_center,
+            end_game_triggered: observation.end_game_triggered,
+            rng: None,
+        }
+    }
+
+    /// Refills the factories from the game's own RNG if it was seeded, otherwise from the
+    /// thread-local RNG.
+    #[cfg(feature = "thread-rng")]
+    pub fn refill_factories(&mut self) {
+        match self.rng.take() {
+            Some(mut rng) => {
+                self.refill_factories_with_rng(&mut rng);
+                self.rng = Some(rng);
+            }
+            None => self.refill_factories_with_rng(&mut thread_rng()),
+        }
+    }
+
+    /// Replaces what no player can know with a fresh sample from `rng`: the order of the bag and
+    /// the stored RNG, if any, which would otherwise fix every future refill. The bag's contents
+    /// stay, since players can count them from the tiles on display and in the discard pile.
+    /// Searches run on a determinized copy so that they can't plan around the real draws.
+    pub fn determinize<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.tile_bag.shuffle(rng);
+        if self.rng.is_some() {
+            self.rng = Some(GameRng::seed_from_u64(rng.gen()));
+        }
+    }
+
+    /// The game as `player_idx` sees it, for handing to clients that shouldn't learn the
+    /// coming draws. Azul has no private hands, so every player's observation shows the same
+    /// table. Panics if `player_idx` isn't a seat in this game.
+    pub fn observation_for(&self, player_idx: usize) -> Observation {
+        assert!(player_idx < self.players.len(), "player {} is not in a {}-player game", player_idx, self.players.len());
+        Observation {
+            player: player_idx,
+            players: self.players.clone(),
+            factories: self.factories.clone(),
+            center: self.center.clone(),
+            bag: TileBagSummary::from_vec(&self.tile_bag),
+            discard_pile: self.discard_pile.clone(),
+            current_player_idx: self.current_player_idx,
+            first_player_marker_in_center: self.first_player_marker_in_center,
+            end_game_triggered: self.end_game_triggered,
+        }
+    }
+
+    pub fn refill_factories_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for factory in self.factories.iter_mut() {
+            factory.clear();
+            for _ in 0..4 {
+                if self.tile_bag.is_empty() {
+                    if self.discard_pile.is_empty() { break; }
+                    core::mem::swap(&mut self.tile_bag, &mut self.discard_pile);
+                    self.tile_bag.shuffle(rng);
+                }
+                if !self.tile_bag.is_empty() {
+                    let random_index = rng.gen_range(0..self.tile_bag.len());
+                    let tile = self.tile_bag.remove(random_index);
+                    factory.push(tile);
+                }
+            }
+        }
+        self.center.clear();
+        self.first_player_marker_in_center = true;
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<Move> {
+        let mut legal_moves = Vec::new();
+        let current_player_board = &self.players[self.current_player_idx];
+
+        let mut generate_moves_for_source = |source: MoveSource, tiles: &[Tile]| {
+            let mut unique_tiles: Vec<Tile> = Vec::with_capacity(ALL_COLORS.len());
+            for &tile in tiles {
+                if !unique_tiles.contains(&tile) { unique_tiles.push(tile); }
+            }
+            for &tile in unique_tiles.iter() {
+                for i in 0..NUM_ROWS {
+                    if current_player_board.is_placement_valid(i, tile) {
+                        legal_moves.push(Move {
+                            source: source.clone(),
+                            tile,
+                            destination: MoveDestination::PatternLine(i),
+                        });
+                    }
+                }
+                legal_moves.push(Move {
+                    source: source.clone(),
+                    tile,
+                    destination: MoveDestination::Floor,
+                });
+            }
+        };
+
+        for (i, factory) in self.factories.iter().enumerate() {
+            if !factory.is_empty() {
+                generate_moves_for_source(MoveSource::Factory(i), factory);
+            }
+        }
+        if !self.center.is_empty() {
+            generate_moves_for_source(MoveSource::Center, &self.center);
+        }
+        legal_moves
+    }
+
+    /// Checks `player_move` against the current position without applying it.
+    pub fn validate_move(&self, player_move: &Move) -> Result<(), MoveError> {
+        let source_tiles = match player_move.source {
+            MoveSource::Factory(idx) => self.factories.get(idx).ok_or(MoveError::NoSuchFactory(idx))?,
+            MoveSource::Center => &self.center,
+        };
+        if source_tiles.is_empty() {
+            return Err(MoveError::EmptySource(player_move.source.clone()));
+        }
+        if !source_tiles.contains(&player_move.tile) {
+            return Err(MoveError::ColorNotInSource { source: player_move.source.clone(), tile: player_move.tile });
+        }
+
+        if let MoveDestination::PatternLine(idx) = player_move.destination {
+            let player = &self.players[self.current_player_idx];
+            let line = player.pattern_lines.get(idx).ok_or(MoveError::NoSuchPatternLine(idx))?;
+            if line.len() > idx {
+                return Err(MoveError::PatternLineFull(idx));
+            }
+            if let Some(&line_color) = line.first() {
+                if line_color != player_move.tile {
+                    return Err(MoveError::WrongColorOnLine { line: idx, line_color, tile: player_move.tile });
+                }
+            }
+            if player.wall[idx].contains(&Some(player_move.tile)) {
+                return Err(MoveError::WallSlotOccupied { row: idx, tile: player_move.tile });
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies `player_move` only if it is legal, leaving the state untouched otherwise.
+    pub fn try_apply_move(&mut self, player_move: &Move) -> Result<(), MoveError> {
+        self.validate_move(player_move)?;
+        self.apply_move(player_move);
+        Ok(())
+    }
+
+    /// Applies `player_move` without checking it. Use `try_apply_move` for untrusted input.
+    pub fn apply_move(&mut self, player_move: &Move) {
+        let player = &mut self.players[self.current_player_idx];
+        let tile = player_move.tile;
+        // Tiles are moved by count and the emptied vectors keep their buffers, so a move
+        // allocates nothing once the containers have grown.
+        let taken = match player_move.source {
+            MoveSource::Factory(idx) => {
+                let factory = &mut self.factories[idx];
+                self.center.extend(factory.iter().copied().filter(|&t| t != tile));
+                let taken = factory.iter().filter(|&&t| t == tile).count();
+                factory.clear();
+                taken
+            }
+            MoveSource::Center => {
+                let center_len = self.center.len();
+                self.center.retain(|&t| t != tile);
+                if self.first_player_marker_in_center {
+                    self.first_player_marker_in_center = false;
+                    player.has_first_player_marker = true;
+                }
+                center_len - self.center.len()
+            }
+        };
+        player.place_tile_run(tile, taken, &player_move.destination);
+        if let MoveDestination::PatternLine(idx) = player_move.destination {
+            if !self.end_game_triggered && player.will_complete_horizontal_row(idx) {
+                self.end_game_triggered = true;
+            }
+        }
+        self.current_player_idx = (self.current_player_idx + 1) % self.players.len();
+    }
+
+    /// `apply_move`, returning what `undo` needs to take the move back, so that a search can
+    /// walk its tree on one state instead of cloning it at every node. Only drafting moves can
+    /// be undone; tiling and refills can't.
+    pub fn apply_move_undoable(&mut self, player_move: &Move) -> UndoToken {
+        let source_tiles = match player_move.source {
+            MoveSource::Factory(idx) => &self.factories[idx],
+            MoveSource::Center => &self.center,
+        };
+        let taken_positions = source_tiles.iter().enumerate()
+            .filter(|&(_, &tile)| tile == player_move.tile)
+            .fold(0, |positions, (pos, _)| positions | 1 << pos);
+        let source_len = source_tiles.len();
+        let player = &self.players[self.current_player_idx];
+        let line_len = match player_move.destination {
+            MoveDestination::PatternLine(idx) => player.pattern_lines[idx].len(),
+            MoveDestination::Floor => 0,
+        };
+        let floor_len = player.floor_line.len();
+        let center_len = self.center.len();
+        let took_first_player_marker = player_move.source == MoveSource::Center && self.first_player_marker_in_center;
+        let end_game_was_triggered = self.end_game_triggered;
+        self.apply_move(player_move);
+        UndoToken {
+            player_move: player_move.clone(),
+            taken_positions,
+            source_len,
+            center_len,
+            line_len,
+            floor_len,
+            took_first_player_marker,
+            triggered_end_game: !end_game_was_triggered && self.end_game_triggered,
+        }
+    }
+
+    /// Takes back the move that returned `token`, which must be the last one applied.
+    pub fn undo(&mut self, token: UndoToken) {
+        let num_players = self.players.len();
+        self.current_player_idx = (self.current_player_idx + num_players - 1) % num_players;
+        let player = &mut self.players[self.current_player_idx];
+        if let MoveDestination::PatternLine(idx) = token.player_move.destination {
+            player.pattern_lines[idx].truncate(token.line_len);
+        }
+        player.floor_line.truncate(token.floor_len);
+        if token.took_first_player_marker {
+            player.has_first_player_marker = false;
+            self.first_player_marker_in_center = true;
+        }
+        if token.triggered_end_game {
+            self.end_game_triggered = false;
+        }
+
+        // Put the taken tiles back among the ones the move left behind.
+        let tile = token.player_move.tile;
+        let is_taken = |pos: usize| token.taken_positions & (1 << pos) != 0;
+        match token.player_move.source {
+            MoveSource::Factory(idx) => {
+                let factory = &mut self.factories[idx];
+                let mut left_behind = self.center.drain(token.center_len..);
+                for pos in 0..token.source_len {
+                    factory.push(if is_taken(pos) { tile } else { left_behind.next().expect("the rest of the factory went to the center") });
+                }
+            }
+            MoveSource::Center => {
+                for pos in (0..token.source_len).filter(|&pos| is_taken(pos)) {
+                    self.center.insert(pos, tile);
+                }
+            }
+        }
+    }
+
+    /// Checks the structural invariants every reachable state satisfies: all 100 tiles are
+    /// accounted for, pattern lines hold one color within capacity, wall tiles match the layout
+    /// and aren't duplicated in their row's pattern line, and the first-player marker is in
+    /// exactly one place at most.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        if !(2..=4).contains(&self.players.len()) {
+            return Err(format!("invalid player count {}", self.players.len()));
+        }
+        if self.current_player_idx >= self.players.len() {
+            return Err(format!("current player {} out of range", self.current_player_idx));
+        }
+        if let Some(idx) = self.factories.iter().position(|f| f.len() > 4) {
+            return Err(format!("factory {} holds {} tiles", idx, self.factories[idx].len()));
+        }
+
+        let mut counts = [0usize; 5];
+        let mut count = |tile: &Tile| counts[ALL_COLORS.iter().position(|c| c == tile).unwrap()] += 1;
+        self.tile_bag.iter().for_each(&mut count);
+        self.discard_pile.iter().for_each(&mut count);
+        self.factories.iter().flatten().for_each(&mut count);
+        self.center.iter().for_each(&mut count);
+        for player in &self.players {
+            player.pattern_lines.iter().flatten().for_each(&mut count);
+            player.wall.iter().flatten().flatten().for_each(&mut count);
+            player.floor_line.iter().for_each(&mut count);
+        }
+        for (color, &n) in ALL_COLORS.iter().zip(counts.iter()) {
+            if n != TILES_PER_COLOR {
+                return Err(format!("{:?} tiles are not conserved: found {}", color, n));
+            }
+        }
+
+        let markers_held = self.players.iter().filter(|p| p.has_first_player_marker).count();
+        if markers_held + self.first_player_marker_in_center as usize > 1 {
+            return Err("the first-player marker is in more than one place".into());
+        }
+
+        for (player_idx, player) in self.players.iter().enumerate() {
+            if player.pattern_lines.len() != NUM_ROWS || player.wall.len() != NUM_ROWS {
+                return Err(format!("player {} has a malformed board", player_idx));
+            }
+            for (row_idx, line) in player.pattern_lines.iter().enumerate() {
+                if line.len() > row_idx + 1 {
+                    return Err(format!("player {} pattern line {} is over capacity", player_idx, row_idx));
+                }
+                if let Some(&first) = line.first() {
+                    if line.iter().any(|&t| t != first) {
+                        return Err(format!("player {} pattern line {} mixes colors", player_idx, row_idx));
+                    }
+                    if player.wall[row_idx].contains(&Some(first)) {
+                        return Err(format!("player {} pattern line {} repeats a wall color", player_idx, row_idx));
+                    }
+                }
+            }
+            for (row_idx, row) in player.wall.iter().enumerate() {
+                if row.len() != NUM_COLS {
+                    return Err(format!("player {} has a malformed wall row {}", player_idx, row_idx));
+                }
+                for (col_idx, tile) in row.iter().enumerate() {
+                    if let Some(tile) = tile {
+                        if *tile != WALL_LAYOUT[row_idx][col_idx] {
+                            return Err(format!("player {} wall ({}, {}) holds {:?}", player_idx, row_idx, col_idx, tile));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_round_over(&self) -> bool {
+        self.factories.iter().all(|f| f.is_empty()) && self.center.is_empty()
+    }
+
+    /// Moves every full pattern line to the wall and applies floor penalties, returning what
+    /// each player scored.
+    pub fn run_tiling_phase(&mut self) -> Vec<ScoringEvent> {
+        let next_starter_idx = self.players.iter().position(|p| p.has_first_player_marker)
+            .unwrap_or(self.current_player_idx);
+        let mut discard_pile_ref = core::mem::take(&mut self.discard_pile);
+        let mut events = Vec::new();
+        for (player_idx, player) in self.players.iter_mut().enumerate() {
+            events.extend(player.run_tiling_phase(player_idx, &mut discard_pile_ref));
+        }
+        if events.iter().any(|e| matches!(e, ScoringEvent::TilePlaced { completed_row: true, .. })) {
+            self.end_game_triggered = true;
+        }
+        self.discard_pile = discard_pile_ref;
+        self.current_player_idx = next_starter_idx;
+        events
+    }
+
+    /// Finishes a drafting round: tiles every wall and, unless the game just ended, refills the
+    /// factories for the next round.
+    #[cfg(feature = "thread-rng")]
+    pub fn end_round(&mut self) -> Vec<ScoringEvent> {
+        let events = self.run_tiling_phase();
+        if !self.end_game_triggered {
+            self.refill_factories();
+        }
+        events
+    }
+
+    pub fn end_round_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Vec<ScoringEvent> {
+        let events = self.run_tiling_phase();
+        if !self.end_game_triggered {
+            self.refill_factories_with_rng(rng);
+        }
+        events
+    }
+
+    /// True once the final round has been tiled. End-game bonuses are applied separately, by
+    /// `apply_end_game_scoring` or by the `advance` call that finished the game.
+    pub fn is_game_over(&self) -> bool {
+        self.phase() == GamePhase::GameOver
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        if !self.is_round_over() {
+            return GamePhase::Drafting;
+        }
+        // The move that triggers the end leaves a full pattern line, which tiling empties.
+        let awaiting_tiling = self.players.iter()
+            .any(|p| p.pattern_lines.iter().enumerate().any(|(idx, line)| line.len() == idx + 1));
+        if self.end_game_triggered && !awaiting_tiling {
+            GamePhase::GameOver
+        } else {
+            GamePhase::Tiling
+        }
+    }
+
+    /// Drives the game one step: applies `player_move` while drafting, or with `None` finishes
+    /// the round (tiling, then refilling or, after the final round, the end-game bonuses).
+    /// Returns the scoring events of that step; a drafting move returns none.
+    #[cfg(feature = "thread-rng")]
+    pub fn advance(&mut self, player_move: Option<&Move>) -> Result<Vec<ScoringEvent>, MoveError> {
+        match self.rng.take() {
+            Some(mut rng) => {
+                let result = self.advance_with_rng(player_move, &mut rng);
+                self.rng = Some(rng);
+                result
+            }
+            None => self.advance_with_rng(player_move, &mut thread_rng()),
+        }
+    }
+
+    pub fn advance_with_rng<R: Rng + ?Sized>(&mut self, player_move: Option<&Move>, rng: &mut R) -> Result<Vec<ScoringEvent>, MoveError> {
+        match (self.phase(), player_move) {
+            (GamePhase::Drafting, Some(m)) => self.try_apply_move(m).map(|_| Vec::new()),
+            (GamePhase::Drafting, None) => Err(MoveError::MoveRequired),
+            (phase, Some(_)) => Err(MoveError::NotDrafting(phase)),
+            (GamePhase::Tiling, None) => {
+                let mut events = self.end_round_with_rng(rng);
+                if self.is_game_over() {
+                    events.extend(self.apply_end_game_scoring());
+                }
+                Ok(events)
+            }
+            (GamePhase::GameOver, None) => Ok(Vec::new()),
+        }
+    }
+
+    /// The standings by score, then complete rows. Call after end-game scoring for final
+    /// results; mid-game it ranks the current scores.
+    pub fn rankings(&self) -> Vec<Ranking> {
+        let mut rankings: Vec<Ranking> = self.players.iter().enumerate()
+            .map(|(player, board)| Ranking { player, rank: 0, score: board.score, complete_rows: board.count_complete_rows() })
+            .collect();
+        rankings.sort_by_key(|r| (core::cmp::Reverse((r.score, r.complete_rows)), r.player));
+        for idx in 0..rankings.len() {
+            let tied_with_previous = idx > 0
+                && (rankings[idx].score, rankings[idx].complete_rows) == (rankings[idx - 1].score, rankings[idx - 1].complete_rows);
+            rankings[idx].rank = if tied_with_previous { rankings[idx - 1].rank } else { idx + 1 };
+        }
+        rankings
+    }
+
+    pub fn winner(&self) -> Winner {
+        let mut winners: Vec<usize> = self.rankings().iter()
+            .take_while(|r| r.rank == 1)
+            .map(|r| r.player)
+            .collect();
+        if winners.len() == 1 {
+            Winner::Single(winners[0])
+        } else {
+            winners.sort_unstable();
+            Winner::Shared(winners)
+        }
+    }
+
+    pub fn apply_end_game_scoring(&mut self) -> Vec<ScoringEvent> {
+        let mut events = Vec::with_capacity(self.players.len());
+        for (player_idx, player) in self.players.iter_mut().enumerate() {
+            let bonuses = player.end_game_bonuses();
+            let points = bonuses.total();
+            player.score += points;
+            events.push(ScoringEvent::EndGameBonus { player: player_idx, bonuses, points });
+        }
+        events
+    }
+}
+
+impl Default for PlayerBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayerBoard {
+    pub fn new() -> Self {
+        Self {
+            score: 0,
+            pattern_lines: vec![
+                Vec::with_capacity(1), Vec::with_capacity(2), Vec::with_capacity(3),
+                Vec::with_capacity(4), Vec::with_capacity(5),
+            ],
+            wall: vec![vec![None; NUM_COLS]; NUM_ROWS],
+            floor_line: Vec::new(),
+            has_first_player_marker: false,
+        }
+    }
+    
+    pub fn count_complete_rows(&self) -> usize {
+        self.wall.iter().filter(|row| row.iter().all(|tile| tile.is_some())).count()
+    }
+
+    fn will_complete_horizontal_row(&self, pattern_line_idx: usize) -> bool {
+        if self.pattern_lines[pattern_line_idx].len() != pattern_line_idx + 1 { return false; }
+        self.wall[pattern_line_idx].iter().filter(|tile| tile.is_some()).count() == 4
+    }
+
+    pub fn place_tiles(&mut self, tiles_to_place: &mut Vec<Tile>, destination: &MoveDestination) {
+        match destination {
+            MoveDestination::Floor => self.floor_line.append(tiles_to_place),
+            MoveDestination::PatternLine(idx) => {
+                let pattern_line = &mut self.pattern_lines[*idx];
+                let capacity = *idx + 1;
+                while !tiles_to_place.is_empty() && pattern_line.len() < capacity {
+                    pattern_line.push(tiles_to_place.pop().unwrap());
+                }
+                self.floor_line.append(tiles_to_place);
+            }
+        }
+    }
+
+    /// `place_tiles` for `count` tiles of one color, without building a `Vec` of them.
+    fn place_tile_run(&mut self, tile: Tile, count: usize, destination: &MoveDestination) {
+        let mut overflow = count;
+        if let MoveDestination::PatternLine(idx) = *destination {
+            let pattern_line = &mut self.pattern_lines[idx];
+            let placed = count.min(idx + 1 - pattern_line.len());
+            pattern_line.extend(core::iter::repeat_n(tile, placed));
+            overflow -= placed;
+        }
+        self.floor_line.extend(core::iter::repeat_n(tile, overflow));
+    }
+
+    pub fn is_placement_valid(&self, pattern_line_idx: usize, tile_color: Tile) -> bool {
+        let line = &self.pattern_lines[pattern_line_idx];
+        if line.len() > pattern_line_idx { return false; }
+        if !line.is_empty() && line[0] != tile_color { return false; }
+        if let Some(col_idx) = WALL_LAYOUT[pattern_line_idx].iter().position(|&t| t == tile_color) {
+            if self.wall[pattern_line_idx][col_idx].is_some() { return false; }
+        }
+        true
+    }
+
+    /// Tiles this board's full pattern lines and applies its floor penalty. `player_idx` is
+    /// only used to label the returned events.
+    pub fn run_tiling_phase(&mut self, player_idx: usize, discard_pile: &mut Vec<Tile>) -> Vec<ScoringEvent> {
+        let mut events = Vec::new();
+        let mut new_score: u32 = 0;
+
+        for (row_idx, layout_row) in WALL_LAYOUT.iter().enumerate() {
+            if self.pattern_lines[row_idx].len() == row_idx + 1 {
+                let tile_color = self.pattern_lines[row_idx][0];
+                if let Some(col_idx) = layout_row.iter().position(|&t| t == tile_color) {
+                    if self.wall[row_idx][col_idx].is_none() {
+                        let (horizontal_points, vertical_points) = self.placement_points(row_idx, col_idx);
+                        let points = (horizontal_points + vertical_points).max(1);
+                        new_score += points;
+                        self.wall[row_idx][col_idx] = Some(tile_color);
+                        // One tile moves to the wall; only the rest of the line is discarded.
+                        let line = &mut self.pattern_lines[row_idx];
+                        discard_pile.extend_from_slice(&line[..row_idx]);
+                        line.clear();
+                        events.push(ScoringEvent::TilePlaced {
+                            player: player_idx,
+                            row: row_idx,
+                            col: col_idx,
+                            tile: tile_color,
+                            horizontal_points,
+                            vertical_points,
+                            points,
+                            completed_row: self.wall[row_idx].iter().all(Option::is_some),
+                        });
+                    }
+                }
+            }
+        }
+        self.score += new_score;
+
+        let floor_items_count = self.floor_items_count();
+        if floor_items_count > 0 {
+            let penalty = self.floor_penalty();
+            let deducted = penalty.min(self.score);
+            self.score -= deducted;
+            events.push(ScoringEvent::FloorPenalty { player: player_idx, floor_tiles: floor_items_count, penalty: deducted });
+        }
+        discard_pile.append(&mut self.floor_line);
+        self.has_first_player_marker = false;
+        events
+    }
+
+    /// Floor tiles plus the first-player marker, if this player holds it.
+    pub fn floor_items_count(&self) -> usize {
+        self.floor_line.len() + usize::from(self.has_first_player_marker)
+    }
+
+    /// The penalty the floor line would cost at the end of the round, before it is capped by
+    /// the player's score.
+    pub fn floor_penalty(&self) -> u32 {
+        FLOOR_PENALTY_VALUES[..self.floor_items_count().min(FLOOR_PENALTY_VALUES.len())].iter().sum()
+    }
+
+    /// What the tiling phase would score for this board if the round ended now: the points for
+    /// every full pattern line minus the floor penalty, without the clamp at zero.
+    pub fn projected_round_points(&self) -> i32 {
+        let mut board = self.clone();
+        let placed: u32 = board.run_tiling_phase(0, &mut Vec::new()).iter()
+            .map(|event| match event {
+                ScoringEvent::TilePlaced { points, .. } => *points,
+                _ => 0,
+            })
+            .sum();
+        placed as i32 - self.floor_penalty() as i32
+    }
+
+    /// The horizontal and vertical runs a tile placed at (row, col) would join, counting the
+    /// tile itself. A direction without neighbors scores 0, so a lone tile scores (0, 0).
+    fn placement_points(&self, row: usize, col: usize) -> (u32, u32) {
+        let mut horizontal_score = 1;
+        for i in (0..col).rev() { if self.wall[row][i].is_some() { horizontal_score += 1; } else { break; } }
+        for i in (col + 1)..NUM_COLS { if self.wall[row][i].is_some() { horizontal_score += 1; } else { break; } }
+        let mut vertical_score = 1;
+        for i in (0..row).rev() { if self.wall[i][col].is_some() { vertical_score += 1; } else { break; } }
+        for i in (row + 1)..NUM_ROWS { if self.wall[i][col].is_some() { vertical_score += 1; } else { break; } }
+        let run_points = |run: u32| if run > 1 { run } else { 0 };
+        (run_points(horizontal_score), run_points(vertical_score))
+    }
+
+    pub fn calculate_end_game_bonuses(&self) -> u32 {
+        self.end_game_bonuses().total()
+    }
+
+    pub fn end_game_bonuses(&self) -> EndGameBonuses {
+        let rows = (0..NUM_ROWS).filter(|&row| self.wall[row].iter().all(Option::is_some)).count();
+        let columns = (0..NUM_COLS).filter(|&col| (0..NUM_ROWS).all(|row| self.wall[row][col].is_some())).count();
+        let colors = ALL_COLORS.iter()
+            .filter(|&&color| self.wall.iter().flatten().filter(|&&tile| tile == Some(color)).count() == 5)
+            .count();
+        EndGameBonuses { rows, columns, colors }
+    }
+}
+
+/// A player's place in the standings. Ties on score are broken by complete horizontal rows;
+/// players still level after that share a rank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ranking {
+    pub player: usize,
+    /// 1-based. Tied players share a rank and the next rank is skipped (1, 1, 3).
+    pub rank: usize,
+    pub score: u32,
+    pub complete_rows: usize,
+}
+
+/// Who won, under the official tie-break.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Winner {
+    Single(usize),
+    /// Tied on both score and complete rows: the victory is shared.
+    Shared(Vec<usize>),
+}
+
+impl Winner {
+    pub fn players(&self) -> &[usize] {
+        match self {
+            Winner::Single(player) => core::slice::from_ref(player),
+            Winner::Shared(players) => players,
+        }
+    }
+}
+
+/// The end-of-game bonuses a wall earns: 2 per complete row, 7 per complete column,
+/// and 10 per color placed five times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EndGameBonuses {
+    pub rows: usize,
+    pub columns: usize,
+    pub colors: usize,
+}
+
+impl EndGameBonuses {
+    pub fn total(&self) -> u32 {
+        (self.rows * 2 + self.columns * 7 + self.colors * 10) as u32
+    }
+}
+
+fn tile_to_char(tile: Tile) -> char {
+    match tile {
+        Tile::Blue => 'B',
+        Tile::Yellow => 'Y',
+        Tile::Red => 'R',
+        Tile::Black => 'K',
+        Tile::White => 'W',
+    }
+}
+
+fn char_to_tile(c: char) -> Option<Tile> {
+    ALL_COLORS.iter().copied().find(|&tile| tile_to_char(tile) == c.to_ascii_uppercase())
+}
+
+// --- Move Notation ---
+
+impl Move {
+    /// A short notation such as `F3:B->L2` or `C:W->floor`. Factories and pattern lines are
+    /// numbered from 1, and tiles are written B, Y, R, K (black) and W.
+    pub fn to_notation(&self) -> String {
+        let source = match self.source {
+            MoveSource::Factory(idx) => format!("F{}", idx + 1),
+            MoveSource::Center => String::from("C"),
+        };
+        let destination = match self.destination {
+            MoveDestination::PatternLine(idx) => format!("L{}", idx + 1),
+            MoveDestination::Floor => String::from("floor"),
+        };
+        format!("{}:{}->{}", source, tile_to_char(self.tile), destination)
+    }
+
+    /// Parses the notation written by `to_notation`, ignoring case and spaces. The move is
+    /// not checked against any position; use `GameState::validate_move` for that.
+    pub fn from_notation(notation: &str) -> Result<Move, String> {
+        let compact: String = notation.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        let (source, rest) = compact.split_once(':')
+            .ok_or_else(|| format!("'{}' is missing the ':' after the source", notation))?;
+        let (tile, destination) = rest.split_once("->")
+            .ok_or_else(|| format!("'{}' is missing the '->' before the destination", notation))?;
+
+        let source = match source {
+            "c" => MoveSource::Center,
+            _ => match source.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => MoveSource::Factory(n - 1),
+                _ => return Err(format!("unknown source '{}' (expected C or F1, F2, ...)", source)),
+            },
+        };
+        let mut tile_chars = tile.chars();
+        let tile = match (tile_chars.next().and_then(char_to_tile), tile_chars.next()) {
+            (Some(tile), None) => tile,
+            _ => return Err(format!("unknown tile '{}' (expected B, Y, R, K or W)", tile)),
+        };
+        let destination = match destination {
+            "floor" => MoveDestination::Floor,
+            _ => match destination.strip_prefix('l').and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if (1..=NUM_ROWS).contains(&n) => MoveDestination::PatternLine(n - 1),
+                _ => return Err(format!("unknown destination '{}' (expected L1-L5 or floor)", destination)),
+            },
+        };
+        Ok(Move { source, tile, destination })
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_notation())
+    }
+}
+
+impl core::str::FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Move::from_notation(s)
+    }
+}
+
+impl fmt::Display for PlayerBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score: {}", self.score)?;
+        writeln!(f, "------------------------------------")?;
+        writeln!(f, "Pattern Lines         Wall")?;
+        for i in 0..5 {
+            let capacity = i + 1;
+            let line = &self.pattern_lines[i];
+            
+            for _ in 0..(5 - capacity) { write!(f, "  ")?; }
+            for _ in 0..(capacity - line.len()) { write!(f, "[_] ")?; }
+            for tile in line { write!(f, "[{}] ", tile_to_char(*tile))?; }
+            write!(f, "   |   ")?;
+            for tile_option in &self.wall[i] {
+                match tile_option {
+                    Some(tile) => write!(f, "[{}] ", tile_to_char(*tile))?,
+                    None => write!(f, "[.] ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "------------------------------------")?;
+        write!(f, "Floor Line: ")?;
+        if self.has_first_player_marker { write!(f, "[1] ")?; }
+        for tile in &self.floor_line { write!(f, "[{}] ", tile_to_char(*tile))?; }
+        writeln!(f)
+    }
+}
+
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.phase() {
+            GamePhase::Drafting => writeln!(f, "Drafting, player {} to move", self.current_player_idx + 1)?,
+            GamePhase::Tiling => writeln!(f, "Tiling")?,
+            GamePhase::GameOver => writeln!(f, "Game over")?,
+        }
+        if self.end_game_triggered { writeln!(f, "Final round: a wall row has been completed")?; }
+        writeln!(f, "Factories:")?;
+        for (i, factory) in self.factories.iter().enumerate() {
+            write!(f, "  F{}: ", i + 1)?;
+            if factory.is_empty() { write!(f, "-")?; }
+            for tile in factory { write!(f, "[{}] ", tile_to_char(*tile))?; }
+            writeln!(f)?;
+        }
+        write!(f, "Center: ")?;
+        if self.first_player_marker_in_center { write!(f, "[1] ")?; }
+        for tile in &self.center { write!(f, "[{}] ", tile_to_char(*tile))?; }
+        writeln!(f)?;
+        writeln!(f, "Bag: {}", TileBagSummary::from_vec(&self.tile_bag))?;
+        writeln!(f, "Discard: {}", TileBagSummary::from_vec(&self.discard_pile))?;
+        for (i, player) in self.players.iter().enumerate() {
+            let to_move = if i == self.current_player_idx && self.phase() == GamePhase::Drafting { " (to move)" } else { "" };
+            writeln!(f, "\nPlayer {}{}", i + 1, to_move)?;
+            write!(f, "{}", player)?;
+        }
+        Ok(())
+    }
+}
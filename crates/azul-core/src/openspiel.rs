@@ -0,0 +1,360 @@
+//! Azul in the shape of OpenSpiel's `Game`/`State` interface, for benchmarking against its
+//! algorithms: moves are integer actions, every tile dealt into a factory is an explicit
+//! chance node, and observations come as flat tensors. Method names follow OpenSpiel's, so a
+//! binding only has to forward calls.
+//!
+//! Chance outcomes are colors, weighted by how many tiles of each color the bag holds; the
+//! bag's order never matters, so the game has perfect information.
+
+use crate::{GameState, Move, MoveDestination, MoveSource, PlayerBoard, Tile, TileBagSummary, Winner, ALL_COLORS, NUM_ROWS, TILES_PER_COLOR};
+use alloc::{format, string::String, vec, vec::Vec};
+
+/// OpenSpiel's player id for chance nodes.
+pub const CHANCE_PLAYER_ID: i32 = -1;
+/// OpenSpiel's player id for terminal states.
+pub const TERMINAL_PLAYER_ID: i32 = -4;
+
+const MAX_FACTORIES: usize = 9;
+const MAX_PLAYERS: usize = 4;
+const NUM_COLORS: usize = 5;
+const TILES_PER_FACTORY: usize = 4;
+const FLOOR_SLOTS: usize = 7;
+// Action sources are the factories, then the center; destinations the pattern lines, then the floor.
+const NUM_SOURCES: usize = MAX_FACTORIES + 1;
+const NUM_DESTINATIONS: usize = NUM_ROWS + 1;
+
+/// Player actions: one per source × color × destination.
+pub const NUM_DISTINCT_ACTIONS: usize = NUM_SOURCES * NUM_COLORS * NUM_DESTINATIONS;
+/// Chance actions: the color of the tile dealt, in `ALL_COLORS` order.
+pub const MAX_CHANCE_OUTCOMES: usize = NUM_COLORS;
+
+const PLAYER_FEATURES: usize = 1 + 1 + NUM_ROWS * (NUM_COLORS + 1) + NUM_ROWS * NUM_ROWS + 1 + 1;
+/// The length of `AzulState::observation_tensor`: the factories, the center, the bag and
+/// discard pile, each seat's board (the observer's first), and whose turn it is.
+pub const OBSERVATION_TENSOR_SIZE: usize =
+    MAX_FACTORIES * NUM_COLORS + NUM_COLORS + 1 + 2 * NUM_COLORS + MAX_PLAYERS * PLAYER_FEATURES + MAX_PLAYERS;
+
+fn color_index(tile: Tile) -> usize {
+    ALL_COLORS.iter().position(|&c| c == tile).expect("every tile has a color")
+}
+
+/// The action for `m`, or `None` if its factory or pattern line is out of range.
+pub fn move_to_action(m: &Move) -> Option<i32> {
+    let source = match m.source {
+        MoveSource::Factory(idx) if idx < MAX_FACTORIES => idx,
+        MoveSource::Factory(_) => return None,
+        MoveSource::Center => MAX_FACTORIES,
+    };
+    let destination = match m.destination {
+        MoveDestination::PatternLine(row) if row < NUM_ROWS => row,
+        MoveDestination::PatternLine(_) => return None,
+        MoveDestination::Floor => NUM_ROWS,
+    };
+    Some(((source * NUM_COLORS + color_index(m.tile)) * NUM_DESTINATIONS + destination) as i32)
+}
+
+/// The move for player action `action`, or `None` if it is out of range.
+pub fn action_to_move(action: i32) -> Option<Move> {
+    let action = usize::try_from(action).ok().filter(|&a| a < NUM_DISTINCT_ACTIONS)?;
+    let destination = action % NUM_DESTINATIONS;
+    let color = (action / NUM_DESTINATIONS) % NUM_COLORS;
+    let source = action / (NUM_DESTINATIONS * NUM_COLORS);
+    Some(Move {
+        source: if source == MAX_FACTORIES { MoveSource::Center } else { MoveSource::Factory(source) },
+        tile: ALL_COLORS[color],
+        destination: if destination == NUM_ROWS { MoveDestination::Floor } else { MoveDestination::PatternLine(destination) },
+    })
+}
+
+/// The game's parameters, like OpenSpiel's `Game`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AzulGame {
+    num_players: usize,
+}
+
+impl AzulGame {
+    /// A game for 2-4 players.
+    pub fn new(num_players: usize) -> Result<Self, String> {
+        if !(2..=MAX_PLAYERS).contains(&num_players) {
+            return Err(format!("Azul is for 2 to {} players, not {}", MAX_PLAYERS, num_players));
+        }
+        Ok(Self { num_players })
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.num_players
+    }
+
+    pub fn num_distinct_actions(&self) -> usize {
+        NUM_DISTINCT_ACTIONS
+    }
+
+    pub fn max_chance_outcomes(&self) -> usize {
+        MAX_CHANCE_OUTCOMES
+    }
+
+    /// Returns are 1 for a win, -1 for a loss and 0 for a shared victory.
+    pub fn min_utility(&self) -> f64 {
+        -1.0
+    }
+
+    pub fn max_utility(&self) -> f64 {
+        1.0
+    }
+
+    /// Zero-sum with two players; with more, the losers' -1s don't balance one win.
+    pub fn is_zero_sum(&self) -> bool {
+        self.num_players == 2
+    }
+
+    pub fn observation_tensor_shape(&self) -> [usize; 1] {
+        [OBSERVATION_TENSOR_SIZE]
+    }
+
+    /// Empty factories and a full bag: the first round's deal is the first chance nodes.
+    pub fn new_initial_state(&self) -> AzulState {
+        let num_factories = crate::factory_count(self.num_players).expect("the player count was checked");
+        let game_state = GameState {
+            players: (0..self.num_players).map(|_| PlayerBoard::new()).collect(),
+            factories: vec![Vec::new(); num_factories],
+            center: Vec::new(),
+            tile_bag: ALL_COLORS.iter().flat_map(|&tile| core::iter::repeat_n(tile, TILES_PER_COLOR)).collect(),
+            discard_pile: Vec::new(),
+            current_player_idx: 0,
+            first_player_marker_in_center: true,
+            end_game_triggered: false,
+            rng: None,
+        };
+        AzulState { game_state, to_deal: num_factories * TILES_PER_FACTORY, history: Vec::new() }
+    }
+}
+
+/// A position, like OpenSpiel's `State`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AzulState {
+    game_state: GameState,
+    /// Tiles still to deal into the factories before drafting starts.
+    to_deal: usize,
+    history: Vec<i32>,
+}
+
+impl AzulState {
+    /// The engine's view of the position. Mid-deal, some factories are still short of tiles.
+    pub fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    pub fn current_player(&self) -> i32 {
+        if self.is_terminal() {
+            TERMINAL_PLAYER_ID
+        } else if self.is_chance_node() {
+            CHANCE_PLAYER_ID
+        } else {
+            self.game_state.current_player_idx as i32
+        }
+    }
+
+    pub fn is_chance_node(&self) -> bool {
+        self.to_deal > 0
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.game_state.is_game_over()
+    }
+
+    /// The legal actions, in increasing order, for the player to move or the chance node.
+    pub fn legal_actions(&self) -> Vec<i32> {
+        if self.is_chance_node() {
+            return self.chance_outcomes().into_iter().map(|(action, _)| action).collect();
+        }
+        if self.is_terminal() {
+            return Vec::new();
+        }
+        let mut actions: Vec<i32> = self.game_state.get_legal_moves().iter().filter_map(move_to_action).collect();
+        actions.sort_unstable();
+        actions
+    }
+
+    /// Each color that can be dealt next, with its probability. The bag is refilled from the
+    /// discard pile when it runs out, as in the game.
+    pub fn chance_outcomes(&self) -> Vec<(i32, f64)> {
+        if !self.is_chance_node() {
+            return Vec::new();
+        }
+        let source = if self.game_state.tile_bag.is_empty() { &self.game_state.discard_pile } else { &self.game_state.tile_bag };
+        let mut counts = [0usize; NUM_COLORS];
+        for &tile in source {
+            counts[color_index(tile)] += 1;
+        }
+        counts.iter().enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(color, &count)| (color as i32, count as f64 / source.len() as f64))
+            .collect()
+    }
+
+    /// Plays a player action or deals the chance outcome `action`. Finishing the drafting
+    /// round tiles the walls and, unless the game is over, starts the next deal.
+    pub fn apply_action(&mut self, action: i32) -> Result<(), String> {
+        if self.is_chance_node() {
+            self.deal(action)?;
+        } else {
+            if self.is_terminal() {
+                return Err(String::from("the game is over"));
+            }
+            let m = action_to_move(action).ok_or_else(|| format!("{} is not an action", action))?;
+            self.game_state.try_apply_move(&m).map_err(|e| format!("{} is illegal: {}", m, e))?;
+            if self.game_state.is_round_over() {
+                self.end_round();
+            }
+        }
+        self.history.push(action);
+        Ok(())
+    }
+
+    fn deal(&mut self, action: i32) -> Result<(), String> {
+        let color = usize::try_from(action).ok().filter(|&c| c < NUM_COLORS)
+            .ok_or_else(|| format!("{} is not a chance outcome", action))?;
+        let tile = ALL_COLORS[color];
+        if self.game_state.tile_bag.is_empty() {
+            core::mem::swap(&mut self.game_state.tile_bag, &mut self.game_state.discard_pile);
+        }
+        let idx = self.game_state.tile_bag.iter().position(|&t| t == tile)
+            .ok_or_else(|| format!("the bag has no {:?} tiles", tile))?;
+        self.game_state.tile_bag.swap_remove(idx);
+        let dealt = self.game_state.factories.len() * TILES_PER_FACTORY - self.to_deal;
+        self.game_state.factories[dealt / TILES_PER_FACTORY].push(tile);
+        self.to_deal -= 1;
+        // With the bag and discard pile both empty, the rest of the factories stay short.
+        if self.game_state.tile_bag.is_empty() && self.game_state.discard_pile.is_empty() {
+            self.to_deal = 0;
+        }
+        Ok(())
+    }
+
+    fn end_round(&mut self) {
+        self.game_state.run_tiling_phase();
+        if self.game_state.end_game_triggered {
+            self.game_state.apply_end_game_scoring();
+            return;
+        }
+        for factory in &mut self.game_state.factories {
+            factory.clear();
+        }
+        self.game_state.center.clear();
+        self.game_state.first_player_marker_in_center = true;
+        self.to_deal = self.game_state.factories.len() * TILES_PER_FACTORY;
+        if self.game_state.tile_bag.is_empty() && self.game_state.discard_pile.is_empty() {
+            self.to_deal = 0;
+        }
+    }
+
+    /// 1 for the winner and -1 for everyone else, or 0 for each player sharing the victory;
+    /// all 0 until the game is over.
+    pub fn returns(&self) -> Vec<f64> {
+        let mut returns = vec![0.0; self.game_state.players.len()];
+        if !self.is_terminal() {
+            return returns;
+        }
+        match self.game_state.winner() {
+            Winner::Single(winner) => {
+                for (player, value) in returns.iter_mut().enumerate() {
+                    *value = if player == winner { 1.0 } else { -1.0 };
+                }
+            }
+            Winner::Shared(winners) => {
+                for (player, value) in returns.iter_mut().enumerate() {
+                    *value = if winners.contains(&player) { 0.0 } else { -1.0 };
+                }
+            }
+        }
+        returns
+    }
+
+    /// The actions played and dealt so far, from the initial state.
+    pub fn history(&self) -> &[i32] {
+        &self.history
+    }
+
+    pub fn action_to_string(&self, player: i32, action: i32) -> String {
+        if player == CHANCE_PLAYER_ID {
+            return match usize::try_from(action).ok().and_then(|color| ALL_COLORS.get(color)) {
+                Some(tile) => format!("Deal {:?}", tile),
+                None => format!("Invalid chance outcome {}", action),
+            };
+        }
+        match action_to_move(action) {
+            Some(m) => format!("{}", m),
+            None => format!("Invalid action {}", action),
+        }
+    }
+
+    /// The position as text. Everything is public, so it is the same for every player.
+    pub fn observation_string(&self, _player: usize) -> String {
+        format!("{}", self.game_state)
+    }
+
+    /// The same as the observation: with perfect information, how the position arose doesn't
+    /// matter.
+    pub fn information_state_string(&self, player: usize) -> String {
+        self.observation_string(player)
+    }
+
+    /// `OBSERVATION_TENSOR_SIZE` features seen from `player`'s seat: the factories' color
+    /// counts, the center's and the first-player marker, the bag's and discard pile's counts,
+    /// then each seat's board starting with `player`'s (present, score, each pattern line's
+    /// color and fill, wall, floor, marker), and the player to move relative to `player`.
+    pub fn observation_tensor(&self, player: usize) -> Vec<f32> {
+        let state = &self.game_state;
+        let mut tensor = Vec::with_capacity(OBSERVATION_TENSOR_SIZE);
+        for idx in 0..MAX_FACTORIES {
+            push_counts(&mut tensor, state.factories.get(idx).map_or(&[][..], |f| f.as_slice()), TILES_PER_FACTORY as f32);
+        }
+        push_counts(&mut tensor, &state.center, (MAX_FACTORIES * (TILES_PER_FACTORY - 1)) as f32);
+        tensor.push(if state.first_player_marker_in_center { 1.0 } else { 0.0 });
+        for summary in [TileBagSummary::from_vec(&state.tile_bag), TileBagSummary::from_vec(&state.discard_pile)] {
+            for count in [summary.blue, summary.yellow, summary.red, summary.black, summary.white] {
+                tensor.push(count as f32 / TILES_PER_COLOR as f32);
+            }
+        }
+        let num_players = state.players.len();
+        for offset in 0..MAX_PLAYERS {
+            match state.players.get((player + offset) % num_players).filter(|_| offset < num_players) {
+                Some(board) => push_board(&mut tensor, board),
+                None => tensor.extend(core::iter::repeat_n(0.0, PLAYER_FEATURES)),
+            }
+        }
+        for offset in 0..MAX_PLAYERS {
+            let to_move = !self.is_terminal() && !self.is_chance_node()
+                && offset < num_players && (player + offset) % num_players == state.current_player_idx;
+            tensor.push(if to_move { 1.0 } else { 0.0 });
+        }
+        debug_assert_eq!(tensor.len(), OBSERVATION_TENSOR_SIZE);
+        tensor
+    }
+}
+
+fn push_counts(tensor: &mut Vec<f32>, tiles: &[Tile], scale: f32) {
+    let mut counts = [0.0; NUM_COLORS];
+    for &tile in tiles {
+        counts[color_index(tile)] += 1.0;
+    }
+    tensor.extend(counts.iter().map(|count| count / scale));
+}
+
+fn push_board(tensor: &mut Vec<f32>, board: &PlayerBoard) {
+    tensor.push(1.0);
+    tensor.push(board.score as f32 / 100.0);
+    for (row, line) in board.pattern_lines.iter().enumerate() {
+        let mut color = [0.0; NUM_COLORS];
+        if let Some(&tile) = line.first() {
+            color[color_index(tile)] = 1.0;
+        }
+        tensor.extend(color);
+        tensor.push(line.len() as f32 / (row + 1) as f32);
+    }
+    for row in &board.wall {
+        tensor.extend(row.iter().map(|slot| if slot.is_some() { 1.0 } else { 0.0 }));
+    }
+    tensor.push(board.floor_line.len() as f32 / FLOOR_SLOTS as f32);
+    tensor.push(if board.has_first_player_marker { 1.0 } else { 0.0 });
+}
@@ -0,0 +1,136 @@
+//! Text pictures of boards and positions for terminals and logs. Every tile and empty slot is
+//! three columns wide, so pattern lines and walls line up whatever the `TileStyle`.
+
+use crate::{GamePhase, GameState, PlayerBoard, Tile, WALL_LAYOUT};
+use alloc::{format, string::String, vec::Vec};
+use core::str::FromStr;
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+const FACTORIES_PER_LINE: usize = 5;
+
+/// How tiles are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TileStyle {
+    /// Letters in brackets, `[B]`, readable anywhere.
+    #[default]
+    Letters,
+    /// Letters on their colors, with ANSI escapes.
+    Ansi,
+    /// Colored Unicode squares, for places that show emoji but not ANSI escapes.
+    Blocks,
+}
+
+impl FromStr for TileStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "letters" => Ok(TileStyle::Letters),
+            "ansi" | "color" => Ok(TileStyle::Ansi),
+            "blocks" => Ok(TileStyle::Blocks),
+            _ => Err(format!("unknown tile style '{}' (expected letters, ansi, or blocks)", s)),
+        }
+    }
+}
+
+fn letter(tile: Tile) -> char {
+    match tile {
+        Tile::Blue => 'B',
+        Tile::Yellow => 'Y',
+        Tile::Red => 'R',
+        Tile::Black => 'K',
+        Tile::White => 'W',
+    }
+}
+
+fn faint(text: &str, style: TileStyle) -> String {
+    if style == TileStyle::Ansi { format!("{}{}{}", DIM, text, RESET) } else { String::from(text) }
+}
+
+/// One tile.
+pub fn tile(tile: Tile, style: TileStyle) -> String {
+    match style {
+        TileStyle::Letters => format!("[{}]", letter(tile)),
+        TileStyle::Ansi => {
+            let escape = match tile {
+                Tile::Blue => "\x1b[1;97;44m",
+                Tile::Yellow => "\x1b[1;30;43m",
+                Tile::Red => "\x1b[1;97;41m",
+                Tile::Black => "\x1b[1;97;100m",
+                Tile::White => "\x1b[1;30;107m",
+            };
+            format!("{} {} {}", escape, letter(tile), RESET)
+        }
+        TileStyle::Blocks => {
+            let square = match tile {
+                Tile::Blue => '🟦',
+                Tile::Yellow => '🟨',
+                Tile::Red => '🟥',
+                Tile::Black => '⬛',
+                Tile::White => '⬜',
+            };
+            format!("{} ", square)
+        }
+    }
+}
+
+/// A run of tiles, e.g. a factory or the floor line.
+pub fn tiles(tiles: &[Tile], style: TileStyle) -> String {
+    tiles.iter().map(|&t| tile(t, style)).collect()
+}
+
+/// Tiles after an optional first player marker, or "-" when there's nothing.
+fn pile(first_player_marker: bool, pile: &[Tile], style: TileStyle) -> String {
+    if !first_player_marker && pile.is_empty() {
+        return String::from("-");
+    }
+    format!("{}{}", if first_player_marker { "[1]" } else { "" }, tiles(pile, style))
+}
+
+/// The pattern lines beside the wall, one row per line, then the floor line with its penalty.
+/// Empty wall slots show, in lowercase, the color that goes there.
+pub fn board(board: &PlayerBoard, style: TileStyle) -> String {
+    let mut text = String::new();
+    for (row, line) in board.pattern_lines.iter().enumerate() {
+        let capacity = row + 1;
+        text.push_str(&"   ".repeat(5 - capacity));
+        text.push_str(&faint(&" _ ".repeat(capacity - line.len()), style));
+        text.push_str(&tiles(line, style));
+        text.push_str("  |  ");
+        for (col, slot) in board.wall[row].iter().enumerate() {
+            match slot {
+                Some(t) => text.push_str(&tile(*t, style)),
+                None => text.push_str(&faint(&format!(" {} ", letter(WALL_LAYOUT[row][col]).to_ascii_lowercase()), style)),
+            }
+        }
+        text.push('\n');
+    }
+    text.push_str(&format!("Floor: {} (-{})\n", pile(board.has_first_player_marker, &board.floor_line, style), board.floor_penalty()));
+    text
+}
+
+/// The factories, a few to a line, then the center with the first player marker if it's there.
+pub fn factories(game: &GameState, style: TileStyle) -> String {
+    let mut text = String::new();
+    let factories: Vec<String> = game.factories.iter().enumerate()
+        .map(|(i, factory)| format!("F{} {}", i + 1, pile(false, factory, style)))
+        .collect();
+    for chunk in factories.chunks(FACTORIES_PER_LINE) {
+        text.push_str(&chunk.join("   "));
+        text.push('\n');
+    }
+    text.push_str(&format!("Center {}\n", pile(game.first_player_marker_in_center, &game.center, style)));
+    text
+}
+
+/// The factories and center, then every player's score and board, marking whose turn it is.
+pub fn table(game: &GameState, style: TileStyle) -> String {
+    let mut text = factories(game, style);
+    for (i, player) in game.players.iter().enumerate() {
+        let to_move = if i == game.current_player_idx && game.phase() == GamePhase::Drafting { " (to move)" } else { "" };
+        text.push_str(&format!("\nPlayer {}: {} points{}\n", i + 1, player.score, to_move));
+        text.push_str(&board(player, style));
+    }
+    text
+}
@@ -0,0 +1,51 @@
+//! The seedable RNG a `GameState` carries so that whole games can be replayed from a seed.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use rand::{Error, RngCore, SeedableRng};
+
+/// A SplitMix64 generator. It is tiny, fast, and serializable, so a seeded game keeps its draw
+/// sequence when it is cloned, saved or sent across the wasm boundary. Not for cryptography.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRng {
+    state: u64,
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for GameRng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self { state: u64::from_le_bytes(seed) }
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self { state }
+    }
+}
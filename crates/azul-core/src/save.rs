@@ -0,0 +1,73 @@
+//! Games saved to finish later: a versioned JSON document with the position, including the
+//! RNG that deals the coming rounds, and the moves played so far.
+
+use crate::{GameState, Move};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// The format `SavedGame::to_json` writes. Documents with any other version are rejected.
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub state: GameState,
+    /// The moves of each round so far, the current round last.
+    pub rounds: Vec<Vec<Move>>,
+}
+
+/// Read first, so that a save from another version fails with a clear message rather than a
+/// missing-field error.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl SavedGame {
+    pub fn new(state: GameState, rounds: Vec<Vec<Move>>) -> Self {
+        Self { version: SAVE_FORMAT_VERSION, state, rounds }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a saved game always serializes")
+    }
+
+    /// Reads a document written by `to_json`, rejecting a position no game could reach, such
+    /// as one with tiles missing.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let header: Header = serde_json::from_str(json).map_err(|e| format!("not a saved game: {}", e))?;
+        if header.version != SAVE_FORMAT_VERSION {
+            return Err(format!(
+                "the game was saved in format version {}, but this build reads version {}",
+                header.version, SAVE_FORMAT_VERSION
+            ));
+        }
+        let saved: Self = serde_json::from_str(json).map_err(|e| format!("corrupt saved game: {}", e))?;
+        saved.state.check_invariants().map_err(|e| format!("corrupt saved game: {}", e))?;
+        Ok(saved)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        Self::from_json(&json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+impl GameState {
+    /// Saves the position without a move history; see `SavedGame` to keep one.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        SavedGame::new(self.clone(), Vec::new()).save(path)
+    }
+
+    /// Loads the position from a file written by `save` or `SavedGame::save`.
+    pub fn load(path: impl AsRef<Path>) -> Result<GameState, String> {
+        SavedGame::load(path).map(|saved| saved.state)
+    }
+}
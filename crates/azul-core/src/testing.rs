@@ -0,0 +1,198 @@
+//! Property-based testing support: proptest strategies for reachable positions, legal moves and
+//! whole games, plus helpers that check two implementations of the rules agree.
+//!
+//! Every generated position is reached by legal play from a seeded opening, so it satisfies
+//! `GameState::check_invariants`. A `GameRecord` can be replayed to reproduce a failing case.
+
+use crate::{GamePhase, GameState, Move, MoveDestination, MoveSource, Tile, ALL_COLORS, NUM_ROWS};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// Safety net for generated games; `full_game` finishes a game in far fewer moves.
+const MAX_GAME_MOVES: usize = 1000;
+
+/// A game played from a seeded opening. The seed drives every tile draw and the moves are
+/// applied in order, ending the round whenever it runs out of tiles.
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub num_players: usize,
+    pub seed: u64,
+    pub moves: Vec<Move>,
+}
+
+impl GameRecord {
+    /// Plays `choices` from the seeded opening, each choice picking a legal move by index.
+    /// Stops when the choices run out or the game ends.
+    pub fn play(num_players: usize, seed: u64, choices: &[u16]) -> Self {
+        let mut choices = choices.iter();
+        Self::play_with(num_players, seed, |legal_moves| {
+            choices.next().map(|&choice| choice as usize % legal_moves.len())
+        })
+    }
+
+    /// Plays from the seeded opening, letting `choose` pick the index of each move among the
+    /// legal moves until it returns `None` or the game ends.
+    pub fn play_with<F>(num_players: usize, seed: u64, mut choose: F) -> Self
+    where
+        F: FnMut(&[Move]) -> Option<usize>,
+    {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut game = GameState::new_with_rng(num_players, &mut rng);
+        let mut moves = Vec::new();
+        while moves.len() < MAX_GAME_MOVES {
+            finish_round(&mut game, &mut rng);
+            if game.is_game_over() {
+                break;
+            }
+            let legal_moves = game.get_legal_moves();
+            let Some(idx) = choose(&legal_moves) else { break };
+            let chosen = legal_moves[idx].clone();
+            game.advance_with_rng(Some(&chosen), &mut rng).unwrap();
+            moves.push(chosen);
+        }
+        Self { num_players, seed, moves }
+    }
+
+    /// Rebuilds the position after every recorded move. A round that ended with the last move
+    /// is finished too, so the result is either mid-draft or a finished, fully scored game.
+    pub fn replay(&self) -> GameState {
+        let mut rng = SmallRng::seed_from_u64(self.seed);
+        let mut game = GameState::new_with_rng(self.num_players, &mut rng);
+        for m in &self.moves {
+            finish_round(&mut game, &mut rng);
+            game.advance_with_rng(Some(m), &mut rng).unwrap();
+        }
+        finish_round(&mut game, &mut rng);
+        game
+    }
+}
+
+fn finish_round(game: &mut GameState, rng: &mut SmallRng) {
+    if game.phase() == GamePhase::Tiling {
+        game.advance_with_rng(None, rng).unwrap();
+    }
+}
+
+/// A player count from 2 to 4.
+pub fn num_players() -> impl Strategy<Value = usize> {
+    2..=4usize
+}
+
+/// A game of at most `max_moves` moves, which may or may not have finished.
+pub fn game_record(max_moves: usize) -> impl Strategy<Value = GameRecord> {
+    (num_players(), any::<u64>(), vec(any::<u16>(), 0..=max_moves))
+        .prop_map(|(num_players, seed, choices)| GameRecord::play(num_players, seed, &choices))
+}
+
+/// A complete game, played until the final round is tiled and scored. Moves are random, but
+/// pattern lines are preferred over the floor so that every game makes progress.
+pub fn full_game() -> impl Strategy<Value = GameRecord> {
+    (num_players(), any::<u64>(), any::<u64>()).prop_map(|(num_players, seed, move_seed)| {
+        let mut move_rng = SmallRng::seed_from_u64(move_seed);
+        GameRecord::play_with(num_players, seed, |legal_moves| {
+            let to_lines: Vec<usize> = (0..legal_moves.len())
+                .filter(|&idx| legal_moves[idx].destination != MoveDestination::Floor)
+                .collect();
+            if to_lines.is_empty() {
+                Some(move_rng.gen_range(0..legal_moves.len()))
+            } else {
+                Some(to_lines[move_rng.gen_range(0..to_lines.len())])
+            }
+        })
+    })
+}
+
+/// A reachable position at any point of a game, including finished games.
+pub fn game_state() -> impl Strategy<Value = GameState> {
+    game_record(120).prop_map(|record| record.replay())
+}
+
+/// A reachable position where the player to move has legal moves.
+pub fn drafting_state() -> impl Strategy<Value = GameState> {
+    game_state().prop_filter("the game is over", |state| !state.is_game_over())
+}
+
+/// A drafting position together with one of its legal moves.
+pub fn state_and_move() -> impl Strategy<Value = (GameState, Move)> {
+    drafting_state().prop_flat_map(|state| {
+        let legal_moves = state.get_legal_moves();
+        (Just(state), select(legal_moves))
+    })
+}
+
+/// A straightforward move generator written directly from the rulebook, kept deliberately
+/// simple so optimized generators can be checked against it.
+pub fn reference_legal_moves(state: &GameState) -> Vec<Move> {
+    let player = &state.players[state.current_player_idx];
+    let sources = state.factories.iter().enumerate()
+        .map(|(idx, tiles)| (MoveSource::Factory(idx), tiles))
+        .chain(core::iter::once((MoveSource::Center, &state.center)));
+
+    let mut moves = Vec::new();
+    for (source, tiles) in sources {
+        for &tile in ALL_COLORS.iter().filter(|c| tiles.contains(c)) {
+            for row in 0..NUM_ROWS {
+                let line = &player.pattern_lines[row];
+                let has_room = line.len() <= row;
+                let same_color = line.iter().all(|&t| t == tile);
+                let on_wall = player.wall[row].contains(&Some(tile));
+                if has_room && same_color && !on_wall {
+                    moves.push(Move { source: source.clone(), tile, destination: MoveDestination::PatternLine(row) });
+                }
+            }
+            moves.push(Move { source: source.clone(), tile, destination: MoveDestination::Floor });
+        }
+    }
+    moves
+}
+
+/// Checks that `generator` produces the same set of moves as `reference_legal_moves`, with no
+/// duplicates. Order is ignored.
+pub fn check_move_generator<F>(state: &GameState, generator: F) -> Result<(), TestCaseError>
+where
+    F: Fn(&GameState) -> Vec<Move>,
+{
+    let mut actual = generator(state);
+    let mut expected = reference_legal_moves(state);
+    actual.sort_by_key(move_key);
+    expected.sort_by_key(move_key);
+    prop_assert!(actual.windows(2).all(|w| w[0] != w[1]), "duplicate moves: {:?}", actual);
+    prop_assert_eq!(actual, expected);
+    Ok(())
+}
+
+/// Checks that two ways of applying a move reach identical states for every legal move of
+/// `state`.
+pub fn check_same_transitions<F, G>(state: &GameState, apply_a: F, apply_b: G) -> Result<(), TestCaseError>
+where
+    F: Fn(&mut GameState, &Move),
+    G: Fn(&mut GameState, &Move),
+{
+    for m in state.get_legal_moves() {
+        let mut a = state.clone();
+        let mut b = state.clone();
+        apply_a(&mut a, &m);
+        apply_b(&mut b, &m);
+        prop_assert_eq!(a, b, "states diverge after {:?}", m);
+    }
+    Ok(())
+}
+
+fn move_key(m: &Move) -> (usize, usize, usize) {
+    let source = match m.source {
+        MoveSource::Factory(idx) => idx,
+        MoveSource::Center => usize::MAX,
+    };
+    let destination = match m.destination {
+        MoveDestination::PatternLine(idx) => idx,
+        MoveDestination::Floor => usize::MAX,
+    };
+    (source, tile_index(m.tile), destination)
+}
+
+fn tile_index(tile: Tile) -> usize {
+    ALL_COLORS.iter().position(|&c| c == tile).unwrap()
+}
@@ -0,0 +1,103 @@
+//! `CompactState` keeps its own copy of the rules; these check that it agrees with `GameState`.
+//! Run with `cargo test -p azul-core --features testing`.
+#![cfg(feature = "testing")]
+
+use azul_core::compact::{CompactState, TileCounts};
+use azul_core::testing::{check_move_generator, drafting_state, full_game, game_state};
+use azul_core::{GamePhase, GameState, Move, MoveDestination, MoveSource, ALL_COLORS, NUM_ROWS};
+use proptest::prelude::*;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// Every move of the action space, legal or not, for `state`'s factory count.
+fn every_move(state: &GameState) -> Vec<Move> {
+    let mut moves = Vec::new();
+    for source_idx in 0..=state.factories.len() {
+        for tile in ALL_COLORS {
+            for destination_idx in 0..=NUM_ROWS {
+                let source = if source_idx < state.factories.len() { MoveSource::Factory(source_idx) } else { MoveSource::Center };
+                let destination = if destination_idx < NUM_ROWS { MoveDestination::PatternLine(destination_idx) } else { MoveDestination::Floor };
+                moves.push(Move { source, tile, destination });
+            }
+        }
+    }
+    moves
+}
+
+/// The tiles of each color that are in the bag, the discard pile or the factories.
+fn tiles_in_supply(state: &CompactState) -> TileCounts {
+    let mut counts = [0u8; 5];
+    for held in state.factories().iter().chain([state.bag(), state.discard_pile()]) {
+        for (count, &n) in counts.iter_mut().zip(held) {
+            *count += n;
+        }
+    }
+    counts
+}
+
+proptest! {
+    #[test]
+    fn compact_moves_match_reference(state in drafting_state()) {
+        check_move_generator(&state, |s| CompactState::from(s).get_legal_moves())?;
+    }
+
+    #[test]
+    fn compact_validation_matches(state in game_state()) {
+        let compact = CompactState::from(&state);
+        prop_assert_eq!(compact.phase(), state.phase());
+        for m in every_move(&state) {
+            prop_assert_eq!(compact.validate_move(&m), state.validate_move(&m), "validation differs for {:?}", m);
+        }
+    }
+
+    #[test]
+    fn compact_moves_reach_the_same_states(state in drafting_state()) {
+        let compact = CompactState::from(&state);
+        for m in state.get_legal_moves() {
+            let mut expected = state.clone();
+            expected.apply_move(&m);
+            let mut actual = compact;
+            actual.apply_move(&m);
+            prop_assert_eq!(actual, CompactState::from(&expected), "states diverge after {:?}", m);
+            prop_assert_eq!(actual.phase(), expected.phase());
+        }
+    }
+
+    /// Tiling, the next deal and end-game scoring, round after round of whole games. The deals
+    /// draw differently, so they're compared by what they take from the supply.
+    #[test]
+    fn compact_rounds_match(record in full_game()) {
+        let mut rng = SmallRng::seed_from_u64(record.seed);
+        let mut game = GameState::new_with_rng(record.num_players, &mut rng);
+        for m in record.moves.iter().map(Some).chain([None]) {
+            prop_assert_eq!(CompactState::from(&game).phase(), game.phase());
+            if game.phase() == GamePhase::Tiling {
+                let mut compact = CompactState::from(&game);
+                let mut tiled = game.clone();
+                compact.run_tiling_phase();
+                tiled.run_tiling_phase();
+                prop_assert_eq!(compact, CompactState::from(&tiled));
+                if tiled.end_game_triggered {
+                    compact.apply_end_game_scoring();
+                    tiled.apply_end_game_scoring();
+                    prop_assert_eq!(compact, CompactState::from(&tiled));
+                    prop_assert!(compact.is_game_over());
+                } else {
+                    compact.refill_factories_with_rng(&mut SmallRng::seed_from_u64(record.seed));
+                    tiled.refill_factories_with_rng(&mut SmallRng::seed_from_u64(record.seed));
+                    let dealt = CompactState::from(&tiled);
+                    prop_assert_eq!(tiles_in_supply(&compact), tiles_in_supply(&dealt));
+                    prop_assert_eq!(
+                        compact.factories().iter().map(|f| f.iter().sum::<u8>()).collect::<Vec<_>>(),
+                        dealt.factories().iter().map(|f| f.iter().sum::<u8>()).collect::<Vec<_>>()
+                    );
+                    prop_assert_eq!(compact.phase(), dealt.phase());
+                }
+                game.advance_with_rng(None, &mut rng).unwrap();
+            }
+            if let Some(m) = m {
+                game.advance_with_rng(Some(m), &mut rng).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Run with `cargo test -p azul-core --features testing,save`.
+#![cfg(all(feature = "testing", feature = "save"))]
+
+use azul_core::save::SavedGame;
+use azul_core::testing::game_state;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn saved_games_load_back(state in game_state()) {
+        let saved = SavedGame::new(state, Vec::new());
+        prop_assert_eq!(SavedGame::from_json(&saved.to_json()), Ok(saved));
+    }
+
+    /// A save edited by hand, or cut short, mustn't load as a game the engine would play on.
+    #[test]
+    fn saves_with_missing_tiles_are_rejected(mut state in game_state()) {
+        prop_assume!(!state.tile_bag.is_empty());
+        state.tile_bag.pop();
+        let json = SavedGame::new(state, Vec::new()).to_json();
+        prop_assert!(SavedGame::from_json(&json).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+//! Run with `cargo test -p azul-core --features testing`.
+#![cfg(feature = "testing")]
+
+use azul_core::testing::full_game;
+use azul_core::{GamePhase, GameState};
+use proptest::prelude::*;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+proptest! {
+    /// Tiling moves one tile of each full pattern line to the wall and discards the rest, so
+    /// every tile is still accounted for once the walls are tiled, before the next deal.
+    #[test]
+    fn tiling_conserves_tiles(record in full_game()) {
+        let mut rng = SmallRng::seed_from_u64(record.seed);
+        let mut game = GameState::new_with_rng(record.num_players, &mut rng);
+        for m in record.moves.iter().map(Some).chain([None]) {
+            if game.phase() == GamePhase::Tiling {
+                let mut tiled = game.clone();
+                tiled.run_tiling_phase();
+                prop_assert_eq!(tiled.check_invariants(), Ok(()));
+                game.advance_with_rng(None, &mut rng).unwrap();
+            }
+            if let Some(m) = m {
+                game.advance_with_rng(Some(m), &mut rng).unwrap();
+            }
+        }
+        prop_assert!(game.is_game_over());
+    }
+}
@@ -0,0 +1,92 @@
+//! `undo` must take a move back exactly, or searches that walk one state drift from the game.
+//! Run with `cargo test -p azul-core --features testing`.
+#![cfg(feature = "testing")]
+
+use azul_core::testing::{drafting_state, state_and_move};
+use azul_core::{GameState, GameStateBuilder, Move, MoveDestination, MoveSource, Tile::*};
+use proptest::prelude::*;
+
+/// Applies `m` undoably to a copy of `state`, then undoes it, expecting `state` back.
+fn check_undo(state: &GameState, m: &Move) -> Result<(), TestCaseError> {
+    let mut game = state.clone();
+    let token = game.apply_move_undoable(m);
+    prop_assert_ne!(&game, state, "{:?} changed nothing", m);
+    game.undo(token);
+    prop_assert_eq!(&game, state, "undoing {:?} didn't restore the state", m);
+    Ok(())
+}
+
+fn check_every_legal_move(state: &GameState) -> Result<(), TestCaseError> {
+    for m in state.get_legal_moves() {
+        check_undo(state, &m)?;
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn undo_restores_the_state((state, m) in state_and_move()) {
+        check_undo(&state, &m)?;
+    }
+
+    #[test]
+    fn undo_restores_the_state_for_every_legal_move(state in drafting_state()) {
+        check_every_legal_move(&state)?;
+    }
+}
+
+#[test]
+fn undo_takes_back_a_factory_move() {
+    let state = GameStateBuilder::new(2)
+        .factory(0, [Blue, Red, Blue, White])
+        .factory(1, [Yellow, Yellow, Black, Black])
+        .center([Red, White])
+        .build()
+        .unwrap();
+    check_every_legal_move(&state).unwrap();
+}
+
+#[test]
+fn undo_returns_the_first_player_marker_to_the_center() {
+    let state = GameStateBuilder::new(3)
+        .center([Red, Blue, Red, Red, Yellow])
+        .pattern_line(1, 1, Red, 1)
+        .current_player(1)
+        .build()
+        .unwrap();
+    let m = Move { source: MoveSource::Center, tile: Red, destination: MoveDestination::PatternLine(1) };
+    let mut taken = state.clone();
+    taken.apply_move(&m);
+    assert!(taken.players[1].has_first_player_marker);
+    check_every_legal_move(&state).unwrap();
+}
+
+#[test]
+fn undo_clears_an_overflowing_floor() {
+    let state = GameStateBuilder::new(2)
+        .factory(0, [Black, Black, Black, Black])
+        .center([Black, Black, White])
+        .floor(0, [Red, Red, Yellow, Yellow, Blue, Blue])
+        .first_player_marker(Some(1))
+        .build()
+        .unwrap();
+    let m = Move { source: MoveSource::Factory(0), tile: Black, destination: MoveDestination::PatternLine(0) };
+    let mut taken = state.clone();
+    taken.apply_move(&m);
+    assert_eq!(taken.players[0].floor_line.len(), 9);
+    check_every_legal_move(&state).unwrap();
+}
+
+#[test]
+fn undo_untriggers_the_end_of_the_game() {
+    let state = GameStateBuilder::new(2)
+        .factory(0, [Blue, Red, Red, Red])
+        .wall(0, 0, [Yellow, Red, Black, White])
+        .build()
+        .unwrap();
+    let m = Move { source: MoveSource::Factory(0), tile: Blue, destination: MoveDestination::PatternLine(0) };
+    let mut taken = state.clone();
+    taken.apply_move(&m);
+    assert!(taken.end_game_triggered);
+    check_every_legal_move(&state).unwrap();
+}
@@ -0,0 +1,148 @@
+#ifndef AZUL_FFI_H
+#define AZUL_FFI_H
+
+/* Generated by cbindgen from crates/azul-ffi/src/lib.rs. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Bumped whenever a function's signature or meaning changes.
+#define AZUL_FFI_ABI_VERSION 1
+
+typedef enum AzulStatus {
+  AZUL_STATUS_OK = 0,
+  // A handle or string argument was NULL.
+  AZUL_STATUS_NULL_ARGUMENT = 1,
+  // A string wasn't UTF-8, or didn't parse.
+  AZUL_STATUS_INVALID_ARGUMENT = 2,
+  // The move isn't legal in the position.
+  AZUL_STATUS_ILLEGAL_MOVE = 3,
+  // The engine panicked; the handle should not be used again.
+  AZUL_STATUS_PANIC = 4,
+} AzulStatus;
+
+// An agent built from a registry spec such as `mctsheuristic:2000`.
+typedef struct AzulAgent AzulAgent;
+
+// A game in progress.
+typedef struct AzulGame AzulGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The message of the last failed call on this thread, or NULL if none has failed. It stays
+// valid until the next failing call on the same thread.
+const char *azul_last_error(void);
+
+uint32_t azul_abi_version(void);
+
+// Frees a string returned by this library. NULL is ignored.
+//
+// # Safety
+// `s` must have come from this library and not been freed already.
+void azul_string_free(char *s);
+
+// A new game for 2-4 players, dealt at random. NULL if the player count is wrong.
+struct AzulGame *azul_game_new(uint32_t num_players);
+
+// A new game whose every deal follows from `seed`, so that it replays exactly.
+struct AzulGame *azul_game_new_with_seed(uint32_t num_players, uint64_t seed);
+
+// A game from the JSON written by `azul_game_state_json`. NULL if it doesn't parse or isn't
+// a valid position. A position whose drafting round is over is tiled (and the next round
+// dealt, or the game scored) as it loads, like the move that ended the round would have.
+//
+// # Safety
+// `json` must be NULL or a NUL-terminated string.
+struct AzulGame *azul_game_from_json(const char *json);
+
+// A copy of `game`, e.g. for a search of the caller's own.
+//
+// # Safety
+// `game` must be NULL or a live handle.
+struct AzulGame *azul_game_clone(const struct AzulGame *game);
+
+// Frees a game. NULL is ignored.
+//
+// # Safety
+// `game` must be NULL or a live handle, which this invalidates.
+void azul_game_free(struct AzulGame *game);
+
+// The whole position as JSON, including the RNG of a seeded game. NULL if `game` is.
+//
+// # Safety
+// `game` must be NULL or a live handle.
+char *azul_game_state_json(const struct AzulGame *game);
+
+// The legal moves, as a JSON array of move notations; empty once the game is over.
+//
+// # Safety
+// `game` must be NULL or a live handle.
+char *azul_game_legal_moves(const struct AzulGame *game);
+
+// Plays `notation` for the player to move. A move that ends the drafting round also tiles the
+// walls and deals the next round, or scores the end of the game, so the game is always
+// either waiting for a move or over.
+//
+// # Safety
+// `game` must be NULL or a live handle, and `notation` NULL or a NUL-terminated string.
+enum AzulStatus azul_game_apply_move(struct AzulGame *game, const char *notation);
+
+// The player to move, from 0, or -1 if `game` is NULL.
+//
+// # Safety
+// `game` must be NULL or a live handle.
+int32_t azul_game_current_player(const struct AzulGame *game);
+
+// # Safety
+// `game` must be NULL or a live handle.
+uint32_t azul_game_num_players(const struct AzulGame *game);
+
+// Whether the final round has been tiled and scored. False if `game` is NULL.
+//
+// # Safety
+// `game` must be NULL or a live handle.
+bool azul_game_is_over(const struct AzulGame *game);
+
+// `player`'s score, or -1 if `game` is NULL or there is no such player.
+//
+// # Safety
+// `game` must be NULL or a live handle.
+int32_t azul_game_score(const struct AzulGame *game, uint32_t player);
+
+// An agent from a spec in the same form as the command-line tools' `--players`, e.g.
+// `heuristicai`, `alphabeta:3` or `mctsheuristic:2000:cpuct=2.0`. NULL if the spec is wrong.
+//
+// # Safety
+// `spec` must be NULL or a NUL-terminated string.
+struct AzulAgent *azul_agent_new(const char *spec);
+
+// Frees an agent. NULL is ignored.
+//
+// # Safety
+// `agent` must be NULL or a live handle, which this invalidates.
+void azul_agent_free(struct AzulAgent *agent);
+
+// The agent's move for the player to move in `game`, in notation, without playing it. NULL
+// if there is none to make, e.g. because the game is over. Blocks for as long as the agent
+// searches.
+//
+// # Safety
+// `agent` and `game` must be NULL or live handles.
+char *azul_agent_get_move(struct AzulAgent *agent, const struct AzulGame *game);
+
+// Forgets what the agent kept from its last game, such as an MCTS tree. Call between games.
+//
+// # Safety
+// `agent` must be NULL or a live handle.
+void azul_agent_reset(struct AzulAgent *agent);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AZUL_FFI_H */
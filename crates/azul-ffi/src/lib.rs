@@ -0,0 +1,327 @@
+//! A C ABI for the engine, for front-ends that embed it natively (Unity, Godot, C++ engines)
+//! and for other languages' FFI. `include/azul_ffi.h` is generated from this file by the
+//! build script; after changing a signature, refresh it with
+//! `AZUL_FFI_UPDATE_HEADER=1 cargo test -p azul-ffi --test header`.
+//!
+//! Games and agents are opaque handles, freed with `azul_game_free` and `azul_agent_free`.
+//! Positions cross the boundary as `GameState` JSON and moves in the `F3:B->L2` notation.
+//! Strings returned to the caller belong to it and are freed with `azul_string_free`.
+//! Functions that can fail return a status (or NULL) and leave a message for
+//! `azul_last_error`.
+
+use azul_ai::{registry::AgentRegistry, AIAgent};
+use azul_core::{factory_count, GamePhase, GameState, Move};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// Bumped whenever a function's signature or meaning changes.
+pub const AZUL_FFI_ABI_VERSION: u32 = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AzulStatus {
+    Ok = 0,
+    /// A handle or string argument was NULL.
+    NullArgument = 1,
+    /// A string wasn't UTF-8, or didn't parse.
+    InvalidArgument = 2,
+    /// The move isn't legal in the position.
+    IllegalMove = 3,
+    /// The engine panicked; the handle should not be used again.
+    Panic = 4,
+}
+
+/// A game in progress.
+pub struct AzulGame {
+    state: GameState,
+}
+
+/// An agent built from a registry spec such as `mctsheuristic:2000`.
+pub struct AzulAgent {
+    agent: Box<dyn AIAgent>,
+}
+
+// --- Errors ---
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Into<String>) {
+    let message = CString::new(message.into().replace('\0', " ")).expect("the NULs were replaced");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn fail(status: AzulStatus, message: impl Into<String>) -> AzulStatus {
+    set_last_error(message);
+    status
+}
+
+/// Runs `f`, turning a panic into `AzulStatus::Panic` instead of unwinding into C.
+fn guard<T>(f: impl FnOnce() -> Result<T, (AzulStatus, String)>) -> Result<T, AzulStatus> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err((status, message))) => Err(fail(status, message)),
+        Err(_) => Err(fail(AzulStatus::Panic, "the engine panicked")),
+    }
+}
+
+unsafe fn read_str<'a>(s: *const c_char, what: &str) -> Result<&'a str, (AzulStatus, String)> {
+    if s.is_null() {
+        return Err((AzulStatus::NullArgument, format!("{} is NULL", what)));
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| (AzulStatus::InvalidArgument, format!("{} is not UTF-8", what)))
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// The message of the last failed call on this thread, or NULL if none has failed. It stays
+/// valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn azul_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[no_mangle]
+pub extern "C" fn azul_abi_version() -> u32 {
+    AZUL_FFI_ABI_VERSION
+}
+
+/// Frees a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `s` must have come from this library and not been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn azul_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+// --- Games ---
+
+fn new_game(num_players: u32, seed: Option<u64>) -> *mut AzulGame {
+    let created = guard(|| {
+        let num_players = num_players as usize;
+        if factory_count(num_players).is_none() {
+            return Err((AzulStatus::InvalidArgument, format!("Azul is for 2 to 4 players, not {}", num_players)));
+        }
+        let state = match seed {
+            Some(seed) => GameState::new_with_seed(num_players, seed),
+            None => GameState::new(num_players),
+        };
+        Ok(Box::into_raw(Box::new(AzulGame { state })))
+    });
+    created.unwrap_or(ptr::null_mut())
+}
+
+/// A new game for 2-4 players, dealt at random. NULL if the player count is wrong.
+#[no_mangle]
+pub extern "C" fn azul_game_new(num_players: u32) -> *mut AzulGame {
+    new_game(num_players, None)
+}
+
+/// A new game whose every deal follows from `seed`, so that it replays exactly.
+#[no_mangle]
+pub extern "C" fn azul_game_new_with_seed(num_players: u32, seed: u64) -> *mut AzulGame {
+    new_game(num_players, Some(seed))
+}
+
+/// A game from the JSON written by `azul_game_state_json`. NULL if it doesn't parse or isn't
+/// a valid position. A position whose drafting round is over is tiled (and the next round
+/// dealt, or the game scored) as it loads, like the move that ended the round would have.
+///
+/// # Safety
+/// `json` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_from_json(json: *const c_char) -> *mut AzulGame {
+    let loaded = guard(|| {
+        let json = read_str(json, "the JSON")?;
+        let mut state: GameState = serde_json::from_str(json)
+            .map_err(|e| (AzulStatus::InvalidArgument, format!("not a game state: {}", e)))?;
+        state.check_invariants().map_err(|e| (AzulStatus::InvalidArgument, format!("invalid game state: {}", e)))?;
+        if state.phase() == GamePhase::Tiling {
+            state.advance(None).expect("drafting is over");
+        }
+        Ok(Box::into_raw(Box::new(AzulGame { state })))
+    });
+    loaded.unwrap_or(ptr::null_mut())
+}
+
+/// A copy of `game`, e.g. for a search of the caller's own.
+///
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_clone(game: *const AzulGame) -> *mut AzulGame {
+    match game.as_ref() {
+        Some(game) => Box::into_raw(Box::new(AzulGame { state: game.state.clone() })),
+        None => {
+            set_last_error("the game is NULL");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a game. NULL is ignored.
+///
+/// # Safety
+/// `game` must be NULL or a live handle, which this invalidates.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_free(game: *mut AzulGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// The whole position as JSON, including the RNG of a seeded game. NULL if `game` is.
+///
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_state_json(game: *const AzulGame) -> *mut c_char {
+    match game.as_ref() {
+        Some(game) => into_c_string(serde_json::to_string(&game.state).expect("a game state always serializes")),
+        None => {
+            set_last_error("the game is NULL");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The legal moves, as a JSON array of move notations; empty once the game is over.
+///
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_legal_moves(game: *const AzulGame) -> *mut c_char {
+    match game.as_ref() {
+        Some(game) => {
+            let moves: Vec<String> = game.state.get_legal_moves().iter().map(Move::to_notation).collect();
+            into_c_string(serde_json::to_string(&moves).expect("strings always serialize"))
+        }
+        None => {
+            set_last_error("the game is NULL");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Plays `notation` for the player to move. A move that ends the drafting round also tiles the
+/// walls and deals the next round, or scores the end of the game, so the game is always
+/// either waiting for a move or over.
+///
+/// # Safety
+/// `game` must be NULL or a live handle, and `notation` NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_apply_move(game: *mut AzulGame, notation: *const c_char) -> AzulStatus {
+    let result = guard(|| {
+        let game = game.as_mut().ok_or((AzulStatus::NullArgument, "the game is NULL".to_string()))?;
+        let player_move = Move::from_notation(read_str(notation, "the move")?).map_err(|e| (AzulStatus::InvalidArgument, e))?;
+        game.state.validate_move(&player_move)
+            .map_err(|e| (AzulStatus::IllegalMove, format!("{} is illegal: {}", player_move, e)))?;
+        game.state.apply_move(&player_move);
+        if game.state.phase() == GamePhase::Tiling {
+            game.state.advance(None).expect("drafting is over");
+        }
+        Ok(())
+    });
+    result.err().unwrap_or(AzulStatus::Ok)
+}
+
+/// The player to move, from 0, or -1 if `game` is NULL.
+///
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_current_player(game: *const AzulGame) -> i32 {
+    game.as_ref().map_or(-1, |game| game.state.current_player_idx as i32)
+}
+
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_num_players(game: *const AzulGame) -> u32 {
+    game.as_ref().map_or(0, |game| game.state.players.len() as u32)
+}
+
+/// Whether the final round has been tiled and scored. False if `game` is NULL.
+///
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_is_over(game: *const AzulGame) -> bool {
+    game.as_ref().is_some_and(|game| game.state.is_game_over())
+}
+
+/// `player`'s score, or -1 if `game` is NULL or there is no such player.
+///
+/// # Safety
+/// `game` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_game_score(game: *const AzulGame, player: u32) -> i32 {
+    game.as_ref()
+        .and_then(|game| game.state.players.get(player as usize))
+        .map_or(-1, |board| board.score as i32)
+}
+
+// --- Agents ---
+
+/// An agent from a spec in the same form as the command-line tools' `--players`, e.g.
+/// `heuristicai`, `alphabeta:3` or `mctsheuristic:2000:cpuct=2.0`. NULL if the spec is wrong.
+///
+/// # Safety
+/// `spec` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn azul_agent_new(spec: *const c_char) -> *mut AzulAgent {
+    let created = guard(|| {
+        let spec = read_str(spec, "the agent spec")?;
+        let agent = AgentRegistry::new().create_from_str(spec).map_err(|e| (AzulStatus::InvalidArgument, e))?;
+        Ok(Box::into_raw(Box::new(AzulAgent { agent })))
+    });
+    created.unwrap_or(ptr::null_mut())
+}
+
+/// Frees an agent. NULL is ignored.
+///
+/// # Safety
+/// `agent` must be NULL or a live handle, which this invalidates.
+#[no_mangle]
+pub unsafe extern "C" fn azul_agent_free(agent: *mut AzulAgent) {
+    if !agent.is_null() {
+        drop(Box::from_raw(agent));
+    }
+}
+
+/// The agent's move for the player to move in `game`, in notation, without playing it. NULL
+/// if there is none to make, e.g. because the game is over. Blocks for as long as the agent
+/// searches.
+///
+/// # Safety
+/// `agent` and `game` must be NULL or live handles.
+#[no_mangle]
+pub unsafe extern "C" fn azul_agent_get_move(agent: *mut AzulAgent, game: *const AzulGame) -> *mut c_char {
+    let chosen = guard(|| {
+        let agent = agent.as_mut().ok_or((AzulStatus::NullArgument, "the agent is NULL".to_string()))?;
+        let game = game.as_ref().ok_or((AzulStatus::NullArgument, "the game is NULL".to_string()))?;
+        let chosen = agent.agent.get_move(&game.state)
+            .ok_or((AzulStatus::InvalidArgument, format!("{} has no move to make", agent.agent.name())))?;
+        Ok(into_c_string(chosen.to_notation()))
+    });
+    chosen.unwrap_or(ptr::null_mut())
+}
+
+/// Forgets what the agent kept from its last game, such as an MCTS tree. Call between games.
+///
+/// # Safety
+/// `agent` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn azul_agent_reset(agent: *mut AzulAgent) {
+    if let Some(agent) = agent.as_mut() {
+        agent.agent.reset();
+    }
+}
@@ -0,0 +1,22 @@
+//! The committed `include/azul_ffi.h` is what C callers build against, so it must match the
+//! header the build script generates from `src/lib.rs`. To refresh it after changing the API:
+//! `AZUL_FFI_UPDATE_HEADER=1 cargo test -p azul-ffi --test header`.
+
+use std::fs;
+use std::path::Path;
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/azul_ffi.h"));
+
+#[test]
+fn committed_header_is_up_to_date() {
+    let committed_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("include/azul_ffi.h");
+    if std::env::var_os("AZUL_FFI_UPDATE_HEADER").is_some() {
+        fs::write(&committed_path, GENERATED).expect("include/azul_ffi.h is writable");
+        return;
+    }
+    let committed = fs::read_to_string(&committed_path).expect("include/azul_ffi.h exists");
+    assert!(
+        committed == GENERATED,
+        "include/azul_ffi.h is out of date; run `AZUL_FFI_UPDATE_HEADER=1 cargo test -p azul-ffi --test header`"
+    );
+}
@@ -0,0 +1,107 @@
+//! Drives the C ABI from Rust the way a native front-end would: handles in, strings out, and a
+//! status or NULL with `azul_last_error` when a call fails.
+
+use azul_ffi::*;
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+/// Takes ownership of a string the library returned.
+unsafe fn take_string(s: *mut std::ffi::c_char) -> String {
+    assert!(!s.is_null(), "unexpected NULL: {}", last_error());
+    let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+    azul_string_free(s);
+    owned
+}
+
+fn last_error() -> String {
+    let message = azul_last_error();
+    if message.is_null() { String::new() } else { unsafe { CStr::from_ptr(message) }.to_str().unwrap().to_string() }
+}
+
+#[test]
+fn a_game_plays_to_the_end() {
+    unsafe {
+        let game = azul_game_new_with_seed(2, 7);
+        assert!(!game.is_null());
+        assert_eq!(azul_game_num_players(game), 2);
+        let spec = CString::new("greedyai").unwrap();
+        let agent = azul_agent_new(spec.as_ptr());
+        assert!(!agent.is_null(), "{}", last_error());
+
+        let mut moves_played = 0;
+        while !azul_game_is_over(game) {
+            let legal: Vec<String> = serde_json::from_str(&take_string(azul_game_legal_moves(game))).unwrap();
+            assert!(!legal.is_empty());
+            let chosen = take_string(azul_agent_get_move(agent, game));
+            assert!(legal.contains(&chosen), "{} isn't among {:?}", chosen, legal);
+            let chosen = CString::new(chosen).unwrap();
+            assert_eq!(azul_game_apply_move(game, chosen.as_ptr()), AzulStatus::Ok, "{}", last_error());
+            moves_played += 1;
+        }
+        assert!(moves_played > 0);
+        assert_eq!(take_string(azul_game_legal_moves(game)), "[]");
+        assert!(azul_agent_get_move(agent, game).is_null());
+        assert!(azul_game_score(game, 0) >= 0);
+
+        // The finished position reloads from its JSON.
+        let json = CString::new(take_string(azul_game_state_json(game))).unwrap();
+        let reloaded = azul_game_from_json(json.as_ptr());
+        assert!(!reloaded.is_null(), "{}", last_error());
+        assert!(azul_game_is_over(reloaded));
+
+        azul_game_free(reloaded);
+        azul_agent_free(agent);
+        azul_game_free(game);
+    }
+}
+
+#[test]
+fn null_handles_are_reported() {
+    unsafe {
+        let notation = CString::new("F1:B->L1").unwrap();
+        assert_eq!(azul_game_apply_move(ptr::null_mut(), notation.as_ptr()), AzulStatus::NullArgument);
+        assert!(!last_error().is_empty());
+        let game = azul_game_new(2);
+        assert_eq!(azul_game_apply_move(game, ptr::null()), AzulStatus::NullArgument);
+        assert!(azul_agent_get_move(ptr::null_mut(), game).is_null());
+        assert!(azul_agent_new(ptr::null()).is_null());
+        assert!(azul_game_from_json(ptr::null()).is_null());
+        assert!(azul_game_state_json(ptr::null()).is_null());
+        assert!(azul_game_legal_moves(ptr::null()).is_null());
+        assert!(azul_game_clone(ptr::null()).is_null());
+        assert_eq!(azul_game_current_player(ptr::null()), -1);
+        assert_eq!(azul_game_score(ptr::null(), 0), -1);
+        assert!(!azul_game_is_over(ptr::null()));
+        azul_game_free(ptr::null_mut());
+        azul_agent_free(ptr::null_mut());
+        azul_string_free(ptr::null_mut());
+        azul_game_free(game);
+    }
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    unsafe {
+        assert!(azul_game_new(1).is_null());
+        assert!(azul_game_new(5).is_null());
+
+        let bad_spec = CString::new("nosuchagent").unwrap();
+        assert!(azul_agent_new(bad_spec.as_ptr()).is_null());
+        assert!(!last_error().is_empty());
+
+        let bad_json = CString::new("{\"players\": []}").unwrap();
+        assert!(azul_game_from_json(bad_json.as_ptr()).is_null());
+
+        let game = azul_game_new_with_seed(3, 1);
+        assert_eq!(azul_game_score(game, 3), -1);
+        let garbled = CString::new("not a move").unwrap();
+        assert_eq!(azul_game_apply_move(game, garbled.as_ptr()), AzulStatus::InvalidArgument);
+        // A well-formed move from a factory the game doesn't have.
+        let missing_factory = CString::new("F9:B->L1").unwrap();
+        assert_eq!(azul_game_apply_move(game, missing_factory.as_ptr()), AzulStatus::IllegalMove);
+        assert!(!last_error().is_empty());
+        // Failed calls leave the game as it was.
+        assert_eq!(azul_game_current_player(game), 0);
+        azul_game_free(game);
+    }
+}
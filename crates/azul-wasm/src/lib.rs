@@ -0,0 +1,699 @@
+//! The `WasmGame` bindings used by the web front-end.
+
+use azul_ai::{
+    analysis::{analysis_engine, evaluate_position, PositionEvaluation}, difficulty::Difficulty, hint::{hint, Hint}, mcts_nn_ai::{check_network, MctsNnAI}, nn::NeuralNetwork,
+    puzzle::{generate_puzzle, Puzzle}, registry::{AgentRegistry, AgentTable},
+    review::evaluate_move, AIAgent,
+};
+use azul_core::save::SavedGame;
+use azul_core::{GamePhase, GameState, Move, MoveSource, Ranking, ScoringEvent, WALL_LAYOUT};
+use model_stream::ModelStream;
+use serde::{Deserialize, Serialize};
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+pub mod model_stream;
+mod types;
+
+/// Iterations for a seat with only a thinking time, high enough that the time runs out first.
+const TIMED_SEARCH_ITERATIONS: u32 = 1_000_000;
+/// Simulations between clock checks in a timed search.
+const TIMED_SLICE_ITERATIONS: u32 = 50;
+
+#[derive(Serialize, Deserialize)]
+struct WasmGameConfig {
+    players: Vec<PlayerConfig>,
+    /// A weights file for every mctsnn seat without a `model` of its own.
+    model_bytes: Option<Vec<u8>>,
+}
+
+fn human() -> String {
+    "human".to_string()
+}
+
+/// One seat, as the page describes it. Seats that compare equal get the same kind of agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlayerConfig {
+    /// A registry agent: "human", "simpleai", "heuristicai", "mctsheuristic", "mctsnn", ...
+    #[serde(default = "human")]
+    agent: String,
+    /// Search iterations per move, for the agents that search.
+    iterations: Option<u32>,
+    /// Thinking time per move for mctsheuristic and mctsnn. They search until it runs out,
+    /// or until `iterations` if that is also given.
+    milliseconds: Option<f64>,
+    /// A weights file for this seat's mctsnn, instead of the game's `model_bytes`.
+    model: Option<Vec<u8>>,
+    seed: Option<u64>,
+    /// A difficulty preset that replaces `agent`.
+    difficulty: Option<Difficulty>,
+}
+
+impl PlayerConfig {
+    fn create_agent(&self, registry: &AgentRegistry) -> Result<Box<dyn AIAgent>, String> {
+        let kind = if self.difficulty.is_some() { "difficulty".to_string() } else { self.agent.trim().to_lowercase() };
+        // The engine's own time limit needs a clock that wasm doesn't have, so the time is
+        // kept by searching in slices instead (`timed_search`).
+        if self.milliseconds.is_some() && !matches!(kind.as_str(), "mctsheuristic" | "mctsnn") {
+            return Err(format!("Agent '{}' takes no thinking time; only mctsheuristic and mctsnn do", kind));
+        }
+        let table = AgentTable {
+            kind: kind.clone(),
+            iterations: self.iterations.or(self.milliseconds.map(|_| TIMED_SEARCH_ITERATIONS)),
+            level: self.difficulty.map(|level| level.to_string()),
+            seed: self.seed,
+            ..AgentTable::default()
+        };
+        let mut agent = registry.create(&table.to_spec()?)?;
+        if let Some(bytes) = &self.model {
+            let nn_agent = agent.as_any().downcast_mut::<MctsNnAI>()
+                .ok_or_else(|| format!("Agent '{}' takes no model; only mctsnn does", kind))?;
+            nn_agent.set_network(read_model(bytes)?)?;
+        }
+        Ok(agent)
+    }
+}
+
+impl WasmGameConfig {
+    fn seats(&self) -> Result<Vec<PlayerConfig>, String> {
+        if !(2..=4).contains(&self.players.len()) {
+            return Err("Invalid player count.".to_string());
+        }
+        Ok(self.players.clone())
+    }
+}
+
+/// Reads a weights file and checks that it fits the MCTS NN agent.
+fn read_model(bytes: &[u8]) -> Result<NeuralNetwork, String> {
+    let network = NeuralNetwork::from_bytes(bytes).map_err(|e| format!("Could not read the model: {}", e))?;
+    fitting_model(network)
+}
+
+/// `network`, if it fits the MCTS NN agent.
+fn fitting_model(network: NeuralNetwork) -> Result<NeuralNetwork, String> {
+    check_network(&network).map_err(|e| format!("The model doesn't fit this engine: {}", e))?;
+    Ok(network)
+}
+
+/// Searches in slices until `agent`'s search has run its iterations or `milliseconds` have
+/// passed. Agents that don't search in slices just pick their move.
+fn timed_search(agent: &mut dyn AIAgent, state: &GameState, milliseconds: f64) -> Option<Move> {
+    let deadline = date_now() + milliseconds;
+    let Some(mut progress) = agent.begin_search(state) else { return agent.get_move(state) };
+    while !progress.is_done() && date_now() < deadline {
+        progress = agent.search_step(TIMED_SLICE_ITERATIONS)?;
+    }
+    agent.end_search()
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// Milliseconds since the epoch, from the browser.
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    fn date_now() -> f64;
+
+    #[wasm_bindgen(js_name = queueMicrotask)]
+    fn queue_microtask(callback: &JsValue);
+}
+
+/// An engine seat's move, searched a slice at a time by `stepAiSearch`.
+struct AiTurn {
+    seat: usize,
+    /// When the search must stop, in `date_now` milliseconds, if it has a time budget.
+    deadline_ms: Option<f64>,
+}
+
+/// A move and the player who made it, as `runAiTurn` and `getLastMove` report it.
+#[derive(Debug, Clone, Serialize)]
+struct PlayedMove {
+    player: usize,
+    #[serde(rename = "move")]
+    mv: Move,
+}
+
+/// What `startAiTurn` and `stepAiSearch` report: simulations run out of those planned, and
+/// whether the move has been played.
+#[derive(Serialize)]
+struct AiTurnProgress {
+    iterations: u32,
+    target: u32,
+    done: bool,
+}
+
+/// What `onEvent` listeners receive, tagged with its `type`.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum GameEvent {
+    /// `tiles` of one color taken, of which `to_floor` didn't fit on the pattern line.
+    TilesDrafted {
+        player: usize,
+        #[serde(rename = "move")]
+        mv: Move,
+        tiles: usize,
+        to_floor: usize,
+    },
+    /// The first player to take from the center took the first-player marker with it.
+    MarkerTaken { player: usize },
+    /// Tiling filled a row of `player`'s wall, so this is the final round.
+    RowCompleted { player: usize, row: usize },
+    /// The walls of `round` (1-based) were tiled, scoring `events`.
+    RoundEnded { round: usize, events: Vec<ScoringEvent> },
+    GameOver { rankings: Vec<Ranking> },
+}
+
+/// The search behind `evaluatePosition`: a number of simulations, or a thinking time.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EvaluationBudget {
+    iterations: Option<u32>,
+    milliseconds: Option<f64>,
+}
+
+fn parse_config(config_js: JsValue) -> Result<WasmGameConfig, JsValue> {
+    serde_wasm_bindgen::from_value(config_js).map_err(|e| JsValue::from_str(&format!("Config error: {}", e)))
+}
+
+#[wasm_bindgen]
+pub struct WasmGame {
+    state: GameState,
+    /// The moves of each round so far, the current round last, for `exportGame`.
+    rounds: Vec<Vec<Move>>,
+    seats: Vec<PlayerConfig>,
+    agents: Vec<Box<dyn AIAgent>>,
+    /// The last model loaded, handed to the MCTS NN seats of later games as well.
+    network: Option<NeuralNetwork>,
+    model_stream: Option<ModelStream>,
+    ai_turn: Option<AiTurn>,
+    last_move: Option<PlayedMove>,
+    /// The puzzle whose position is on the board, set by `loadPuzzle`.
+    puzzle: Option<Puzzle>,
+    listeners: Vec<Function>,
+    /// The position before each move of this game, for `getPositionAt` and
+    /// `evaluatePosition` to look back at.
+    positions: Vec<GameState>,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    /// A new game for `config`: `players` describes each seat, e.g.
+    /// `{ agent: "mctsheuristic", milliseconds: 1000 }` or `{ difficulty: "Hard" }`, and
+    /// `model_bytes`, if given, is a weights file for the MCTS NN seats. A model that can't be
+    /// read, or whose input and policy sizes don't match the engine's, is an error.
+    #[wasm_bindgen(constructor)]
+    pub fn new(#[wasm_bindgen(unchecked_param_type = "WasmGameConfig")] config_js: JsValue) -> Result<WasmGame, JsValue> {
+        let config = parse_config(config_js)?;
+        let seats = config.seats().map_err(|e| JsValue::from_str(&e))?;
+
+        let initial_state = GameState::new(seats.len());
+        
+        let registry = AgentRegistry::new();
+        let agents = seats.iter()
+            .map(|seat| seat.create_agent(&registry).map_err(|e| JsValue::from_str(&e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut game = WasmGame { state: initial_state, rounds: vec![Vec::new()], seats, agents, network: None, model_stream: None, ai_turn: None, last_move: None, puzzle: None, listeners: Vec::new(), positions: Vec::new() };
+        if let Some(bytes) = config.model_bytes {
+            read_model(&bytes).and_then(|network| game.load_model(network)).map_err(|e| JsValue::from_str(&e))?;
+        }
+        Ok(game)
+    }
+
+    /// Starts a rematch: a fresh deal with the same seats, keeping their agents (with any
+    /// search trees cleared) and the loaded model.
+    pub fn reset(&mut self) {
+        self.cancel_ai_search();
+        self.state = GameState::new(self.seats.len());
+        self.rounds = vec![Vec::new()];
+        self.last_move = None;
+        self.puzzle = None;
+        self.positions.clear();
+        for agent in &mut self.agents {
+            agent.reset();
+        }
+    }
+
+    /// Starts a new game with `config`, without reconstructing the module. Seats that keep
+    /// the same agent type reuse their agent, and the loaded model carries over unless
+    /// `config` brings new model bytes.
+    #[wasm_bindgen(js_name = newGameWithConfig)]
+    pub fn new_game_with_config(&mut self, #[wasm_bindgen(unchecked_param_type = "WasmGameConfig")] config_js: JsValue) -> Result<(), JsValue> {
+        let config = parse_config(config_js)?;
+        let seats = config.seats().map_err(|e| JsValue::from_str(&e))?;
+        // The model and the new agents first, so a failure leaves the current game as it was.
+        let network = config.model_bytes.as_deref().map(read_model).transpose().map_err(|e| JsValue::from_str(&e))?;
+        self.cancel_ai_search();
+
+        let registry = AgentRegistry::new();
+        let new_agents = seats.iter().enumerate()
+            .map(|(i, seat)| if self.seats.get(i) == Some(seat) { Ok(None) } else { seat.create_agent(&registry).map(Some) })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| JsValue::from_str(&e))?;
+        let mut old_agents = self.agents.drain(..);
+        let agents = new_agents.into_iter()
+            .map(|new_agent| {
+                let old_agent = old_agents.next();
+                new_agent.unwrap_or_else(|| {
+                    let mut agent = old_agent.expect("a reused seat has an agent");
+                    agent.reset();
+                    agent
+                })
+            })
+            .collect();
+        drop(old_agents);
+
+        self.state = GameState::new(seats.len());
+        self.rounds = vec![Vec::new()];
+        self.last_move = None;
+        self.puzzle = None;
+        self.positions.clear();
+        self.seats = seats;
+        self.agents = agents;
+        match network {
+            Some(network) => self.load_model(network),
+            None => self.share_network(),
+        }
+        .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// What `player` can see of the game (the player to move if omitted): the bag is summarized
+    /// by color and the RNG left out, so a client can't read the coming draws.
+    #[wasm_bindgen(js_name = getState, unchecked_return_type = "Observation")]
+    pub fn get_state(&self, player: Option<usize>) -> Result<JsValue, JsValue> {
+        let player = player.unwrap_or(self.state.current_player_idx);
+        if player >= self.state.players.len() {
+            return Err(JsValue::from_str(&format!("Player {} is not in this game.", player)));
+        }
+        serde_wasm_bindgen::to_value(&self.state.observation_for(player)).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// The whole state as JSON, including the order of the bag and the RNG. For debugging
+    /// only: a client that reads it knows every future draw. JSON because a seeded game's RNG
+    /// state doesn't fit in a JavaScript number.
+    #[wasm_bindgen(js_name = getFullState)]
+    pub fn get_full_state(&self) -> String {
+        serde_json::to_string(&self.state).expect("a game state always serializes")
+    }
+
+    #[wasm_bindgen(js_name = getLegalMoves, unchecked_return_type = "Move[]")]
+    pub fn get_legal_moves(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.state.get_legal_moves()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = applyMove)]
+    pub fn apply_move(&mut self, #[wasm_bindgen(unchecked_param_type = "Move")] move_js: JsValue) -> Result<(), JsValue> {
+        let player_move: Move = serde_wasm_bindgen::from_value(move_js).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let player = self.state.current_player_idx;
+        self.state.validate_move(&player_move).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.play_move(player, player_move);
+        Ok(())
+    }
+
+    /// Finishes the round once drafting is over: tiles the walls, then refills the factories
+    /// or, after the final round, adds the end-game bonuses. Returns what scored, in order:
+    /// each tile placed on a wall with its points and whether it completed a row, each floor
+    /// penalty, and the end-game bonuses. The list is empty while drafting isn't over.
+    #[wasm_bindgen(js_name = handleRoundEnd, unchecked_return_type = "ScoringEvent[]")]
+    pub fn handle_round_end(&mut self) -> Result<JsValue, JsValue> {
+        let mut events = Vec::new();
+        if self.state.phase() == GamePhase::Tiling {
+            events = self.state.advance(None).map_err(|e| JsValue::from_str(&e.to_string()))?;
+            for event in &events {
+                if let ScoringEvent::TilePlaced { player, row, completed_row: true, .. } = *event {
+                    self.emit(GameEvent::RowCompleted { player, row });
+                }
+            }
+            self.emit(GameEvent::RoundEnded { round: self.rounds.len(), events: events.clone() });
+            if self.state.is_game_over() {
+                self.emit(GameEvent::GameOver { rankings: self.state.rankings() });
+            } else {
+                self.rounds.push(Vec::new());
+            }
+        }
+        serde_wasm_bindgen::to_value(&events).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = isGameOver)]
+    pub fn is_game_over(&self) -> bool {
+        self.state.is_game_over()
+    }
+
+    /// The standings with the official tie-break on complete rows; tied players share a rank.
+    #[wasm_bindgen(js_name = getRankings, unchecked_return_type = "Ranking[]")]
+    pub fn get_rankings(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.state.rankings()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = getWallLayout, unchecked_return_type = "WallLayout")]
+    pub fn get_wall_layout(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&WALL_LAYOUT).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Starts streaming a model in chunks, each decoded as it arrives. `total_bytes` (e.g. from
+    /// Content-Length) lets a stream that is cut short or runs over be caught.
+    #[wasm_bindgen(js_name = beginModelStream)]
+    pub fn begin_model_stream(&mut self, total_bytes: Option<usize>) {
+        self.model_stream = Some(ModelStream::new(total_bytes));
+    }
+
+    #[wasm_bindgen(js_name = pushModelChunk)]
+    pub fn push_model_chunk(&mut self, chunk: &[u8]) -> Result<(), JsValue> {
+        let stream = self.model_stream.as_mut()
+            .ok_or_else(|| JsValue::from_str("No model stream in progress. Call beginModelStream first."))?;
+        stream.push_chunk(chunk).map_err(|e| {
+            self.model_stream = None;
+            JsValue::from_str(&e)
+        })
+    }
+
+    /// Completes the model stream and returns the number of bytes received.
+    #[wasm_bindgen(js_name = finishModelStream)]
+    pub fn finish_model_stream(&mut self) -> Result<usize, JsValue> {
+        let stream = self.model_stream.take()
+            .ok_or_else(|| JsValue::from_str("No model stream in progress."))?;
+        let bytes_received = stream.bytes_received();
+        stream.finish()
+            .and_then(fitting_model)
+            .and_then(|network| self.load_model(network))
+            .map_err(|e| JsValue::from_str(&e))?;
+        Ok(bytes_received)
+    }
+
+    #[wasm_bindgen(js_name = hasModel)]
+    pub fn has_model(&self) -> bool {
+        self.network.is_some()
+    }
+
+    /// Suggests a move for the player to move, found by an MCTS search of `iterations`
+    /// simulations, with a one-sentence explanation. Returns null if there is nothing to play.
+    #[wasm_bindgen(js_name = getHint, unchecked_return_type = "Hint | null")]
+    pub fn get_hint(&self, iterations: u32) -> Result<JsValue, JsValue> {
+        if self.state.phase() != GamePhase::Drafting {
+            return Ok(JsValue::NULL);
+        }
+        match hint(&self.state, iterations) {
+            Some(hint) => serde_wasm_bindgen::to_value(&hint).map_err(|e| JsValue::from_str(&e.to_string())),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// Compares `move` with the engine's best move in the current position, before it is
+    /// applied: the points it gives up (in centipoints) and a quality label for "??"-style
+    /// feedback.
+    #[wasm_bindgen(js_name = evaluateMove, unchecked_return_type = "MoveReview")]
+    pub fn evaluate_move(&self, #[wasm_bindgen(unchecked_param_type = "Move")] move_js: JsValue) -> Result<JsValue, JsValue> {
+        let played: Move = serde_wasm_bindgen::from_value(move_js).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let review = evaluate_move(&self.state, &played).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&review).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Plays the current engine seat's move and returns it with the player who made it, or
+    /// null if the seat had no move.
+    #[wasm_bindgen(js_name = runAiTurn, unchecked_return_type = "PlayedMove | null")]
+    pub fn run_ai_turn(&mut self) -> Result<JsValue, JsValue> {
+        let player = self.state.current_player_idx;
+        let agent = self.agents[player].as_mut();
+        let chosen = match self.seats[player].milliseconds {
+            Some(milliseconds) => timed_search(agent, &self.state, milliseconds),
+            None => agent.get_move(&self.state),
+        };
+        let Some(ai_move) = chosen else { return Ok(JsValue::NULL) };
+        self.play_move(player, ai_move);
+        self.get_last_move()
+    }
+
+    /// The last move played and the player who made it, or null before the first move.
+    #[wasm_bindgen(js_name = getLastMove, unchecked_return_type = "PlayedMove | null")]
+    pub fn get_last_move(&self) -> Result<JsValue, JsValue> {
+        match &self.last_move {
+            Some(played) => serde_wasm_bindgen::to_value(played).map_err(|e| JsValue::from_str(&e.to_string())),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// Starts the current engine seat's move as a search that `stepAiSearch` runs a slice at a
+    /// time, so the page stays responsive. With `budgetMs`, or else the seat's `milliseconds`,
+    /// the search stops after that many milliseconds even if it hasn't run all its simulations.
+    /// Seats that don't search in slices play their move right away, and the result then
+    /// reports `done`.
+    #[wasm_bindgen(js_name = startAiTurn, unchecked_return_type = "AiTurnProgress")]
+    pub fn start_ai_turn(&mut self, budget_ms: Option<f64>) -> Result<JsValue, JsValue> {
+        self.cancel_ai_search();
+        if self.state.phase() != GamePhase::Drafting {
+            return Err(JsValue::from_str("There is no move to search for."));
+        }
+        let seat = self.state.current_player_idx;
+        let progress = match self.agents[seat].begin_search(&self.state) {
+            Some(progress) => progress,
+            None => {
+                self.run_ai_turn()?;
+                let played = AiTurnProgress { iterations: 0, target: 0, done: true };
+                return serde_wasm_bindgen::to_value(&played).map_err(|e| JsValue::from_str(&e.to_string()));
+            }
+        };
+        let budget_ms = budget_ms.or(self.seats[seat].milliseconds);
+        self.ai_turn = Some(AiTurn { seat, deadline_ms: budget_ms.map(|budget| date_now() + budget) });
+        if progress.is_done() {
+            self.finish_ai_turn();
+        }
+        let progress = AiTurnProgress { iterations: progress.iterations, target: progress.target, done: progress.is_done() };
+        serde_wasm_bindgen::to_value(&progress).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Runs up to `chunkIterations` more simulations of the search begun by `startAiTurn`.
+    /// Once the search has run them all, or used up its time budget, the move is played and
+    /// the result reports `done`.
+    #[wasm_bindgen(js_name = stepAiSearch, unchecked_return_type = "AiTurnProgress")]
+    pub fn step_ai_search(&mut self, chunk_iterations: u32) -> Result<JsValue, JsValue> {
+        let Some(turn) = &self.ai_turn else {
+            return Err(JsValue::from_str("No AI search in progress. Call startAiTurn first."));
+        };
+        let deadline_ms = turn.deadline_ms;
+        let progress = self.agents[turn.seat].search_step(chunk_iterations)
+            .ok_or_else(|| JsValue::from_str("The AI search was lost."))?;
+        let done = progress.is_done() || deadline_ms.is_some_and(|deadline| date_now() >= deadline);
+        if done {
+            self.finish_ai_turn();
+        }
+        let progress = AiTurnProgress { iterations: progress.iterations, target: progress.target, done };
+        serde_wasm_bindgen::to_value(&progress).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Abandons the search begun by `startAiTurn` without playing a move.
+    #[wasm_bindgen(js_name = cancelAiSearch)]
+    pub fn cancel_ai_search(&mut self) {
+        if let Some(turn) = self.ai_turn.take() {
+            self.agents[turn.seat].end_search();
+        }
+    }
+
+    /// The game so far as a saved-game string (the same JSON as the terminal game's `:save`),
+    /// for keeping in local storage or a file and resuming with `importGame`.
+    #[wasm_bindgen(js_name = exportGame)]
+    pub fn export_game(&self) -> String {
+        SavedGame::new(self.state.clone(), self.rounds.clone()).to_json()
+    }
+
+    /// Replaces the game in progress with one from `exportGame`, keeping the seats' agents and
+    /// any loaded model. The saved game must have as many players as this one.
+    #[wasm_bindgen(js_name = importGame)]
+    pub fn import_game(&mut self, saved: &str) -> Result<(), JsValue> {
+        let saved = SavedGame::from_json(saved).map_err(|e| JsValue::from_str(&e))?;
+        if saved.state.players.len() != self.agents.len() {
+            return Err(JsValue::from_str(&format!(
+                "The saved game has {} players, but this game has {}.", saved.state.players.len(), self.agents.len()
+            )));
+        }
+        self.cancel_ai_search();
+        for agent in &mut self.agents {
+            agent.reset();
+        }
+        self.state = saved.state;
+        self.rounds = if saved.rounds.is_empty() { vec![Vec::new()] } else { saved.rounds };
+        self.last_move = None;
+        self.puzzle = None;
+        self.positions.clear();
+        Ok(())
+    }
+
+    /// Sets up a puzzle: a position in which one move is clearly best. `seedOrJson` is either a
+    /// seed, which always gives the same puzzle (e.g. the date, for a daily puzzle), or a
+    /// puzzle's JSON. Seeded puzzles have as many players as this game, and a JSON one must.
+    #[wasm_bindgen(js_name = loadPuzzle)]
+    pub fn load_puzzle(&mut self, #[wasm_bindgen(unchecked_param_type = "number | string")] seed_or_json: JsValue) -> Result<(), JsValue> {
+        let num_players = self.agents.len();
+        let puzzle = if let Some(seed) = seed_or_json.as_f64() {
+            if seed < 0.0 || seed.fract() != 0.0 {
+                return Err(JsValue::from_str("A puzzle seed must be a whole number of at least 0."));
+            }
+            generate_puzzle(num_players, seed as u64)
+                .ok_or_else(|| JsValue::from_str(&format!("No puzzle was found for seed {}.", seed)))?
+        } else if let Some(json) = seed_or_json.as_string() {
+            let puzzle = Puzzle::from_json(&json).map_err(|e| JsValue::from_str(&e))?;
+            if puzzle.state.players.len() != num_players {
+                return Err(JsValue::from_str(&format!(
+                    "The puzzle has {} players, but this game has {}.", puzzle.state.players.len(), num_players
+                )));
+            }
+            puzzle
+        } else {
+            return Err(JsValue::from_str("loadPuzzle takes a seed or a puzzle's JSON."));
+        };
+        self.cancel_ai_search();
+        for agent in &mut self.agents {
+            agent.reset();
+        }
+        self.state = puzzle.state.clone();
+        self.rounds = vec![Vec::new()];
+        self.last_move = None;
+        self.puzzle = Some(puzzle);
+        self.positions.clear();
+        Ok(())
+    }
+
+    /// Checks an answer to the loaded puzzle without playing it: whether it is the best move,
+    /// and a review of it like `evaluateMove`'s, which names the best move.
+    #[wasm_bindgen(js_name = checkPuzzleAnswer, unchecked_return_type = "PuzzleAnswer")]
+    pub fn check_puzzle_answer(&self, #[wasm_bindgen(unchecked_param_type = "Move")] move_js: JsValue) -> Result<JsValue, JsValue> {
+        let puzzle = self.puzzle.as_ref().ok_or_else(|| JsValue::from_str("No puzzle is loaded."))?;
+        let answer: Move = serde_wasm_bindgen::from_value(move_js).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let result = puzzle.check(&answer).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Calls `callback` with each `GameEvent` as the game goes on: tiles drafted (and the
+    /// first-player marker taken) by any seat, wall rows completed, rounds scored and the game
+    /// ending. Events arrive just after the call that caused them returns, in order.
+    #[wasm_bindgen(js_name = onEvent)]
+    pub fn on_event(&mut self, #[wasm_bindgen(unchecked_param_type = "(event: GameEvent) => void")] callback: Function) {
+        self.listeners.push(callback);
+    }
+
+    /// Stops calling a `callback` passed to `onEvent`.
+    #[wasm_bindgen(js_name = offEvent)]
+    pub fn off_event(&mut self, callback: &Function) {
+        self.listeners.retain(|listener| listener != callback);
+    }
+
+    /// Moves played in this game so far, or since it was imported or set up as a puzzle.
+    #[wasm_bindgen(js_name = getMoveCount)]
+    pub fn get_move_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// What the player to move saw before move `ply` (0-based), as `getState` shows it; `ply`
+    /// equal to `getMoveCount()` is the current position. For stepping back through a game.
+    #[wasm_bindgen(js_name = getPositionAt, unchecked_return_type = "Observation")]
+    pub fn get_position_at(&self, ply: usize) -> Result<JsValue, JsValue> {
+        let position = self.position_at(ply)?;
+        serde_wasm_bindgen::to_value(&position.observation_for(position.current_player_idx)).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Analyzes the current position, or the one before move `ply`: each player's chance of
+    /// winning, from HeuristicAI playouts, and the MCTS search's top moves with their share
+    /// of its visits. `budget` is a number of simulations, or `{ iterations }` or
+    /// `{ milliseconds }`; with both, the search stops at whichever comes first.
+    #[wasm_bindgen(js_name = evaluatePosition, unchecked_return_type = "PositionEvaluation")]
+    pub fn evaluate_position(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "number | { iterations?: number; milliseconds?: number }")] budget: JsValue,
+        ply: Option<usize>,
+    ) -> Result<JsValue, JsValue> {
+        let position = self.position_at(ply.unwrap_or(self.positions.len()))?;
+        let budget = match budget.as_f64() {
+            Some(iterations) => EvaluationBudget { iterations: Some(iterations as u32), milliseconds: None },
+            None => serde_wasm_bindgen::from_value(budget).map_err(|e| JsValue::from_str(&format!("Budget error: {}", e)))?,
+        };
+        let evaluation = match budget {
+            EvaluationBudget { iterations, milliseconds: Some(milliseconds) } => {
+                let mut engine = analysis_engine(iterations.unwrap_or(TIMED_SEARCH_ITERATIONS));
+                if position.phase() == GamePhase::Drafting {
+                    timed_search(&mut engine, position, milliseconds);
+                }
+                PositionEvaluation::from_search(position, &engine)
+            }
+            EvaluationBudget { iterations: Some(iterations), milliseconds: None } => evaluate_position(position, iterations),
+            EvaluationBudget { iterations: None, milliseconds: None } => {
+                return Err(JsValue::from_str("The budget needs iterations or milliseconds."));
+            }
+        };
+        serde_wasm_bindgen::to_value(&evaluation).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// The loaded puzzle's JSON, for sharing a puzzle that isn't from a seed; null without one.
+    #[wasm_bindgen(js_name = exportPuzzle)]
+    pub fn export_puzzle(&self) -> Option<String> {
+        self.puzzle.as_ref().map(Puzzle::to_json)
+    }
+}
+
+impl WasmGame {
+    /// Ends the search in progress and plays the move it found.
+    fn finish_ai_turn(&mut self) {
+        let Some(turn) = self.ai_turn.take() else { return };
+        if let Some(ai_move) = self.agents[turn.seat].end_search() {
+            self.play_move(turn.seat, ai_move);
+        }
+    }
+
+    /// Plays `played`, a legal move for `player`, records it, and tells the listeners.
+    fn play_move(&mut self, player: usize, played: Move) {
+        let drafted = Hint::for_move(&self.state, &played, None);
+        let marker_taken = played.source == MoveSource::Center && self.state.first_player_marker_in_center;
+        self.positions.push(self.state.clone());
+        self.state.apply_move(&played);
+        self.rounds.last_mut().expect("a round is in progress").push(played.clone());
+        self.last_move = Some(PlayedMove { player, mv: played.clone() });
+        self.emit(GameEvent::TilesDrafted { player, mv: played, tiles: drafted.tiles_taken, to_floor: drafted.tiles_to_floor });
+        if marker_taken {
+            self.emit(GameEvent::MarkerTaken { player });
+        }
+    }
+
+    /// Sends `event` to the `onEvent` listeners in a microtask, after the call that raised it
+    /// has returned, so that a listener can call back into the game. A listener that throws
+    /// doesn't stop the others.
+    fn emit(&self, event: GameEvent) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        let Ok(value) = serde_wasm_bindgen::to_value(&event) else { return };
+        let listeners = self.listeners.clone();
+        queue_microtask(&Closure::once_into_js(move || {
+            for listener in &listeners {
+                let _ = listener.call1(&JsValue::NULL, &value);
+            }
+        }));
+    }
+
+    fn position_at(&self, ply: usize) -> Result<&GameState, JsValue> {
+        match ply.cmp(&self.positions.len()) {
+            std::cmp::Ordering::Less => Ok(&self.positions[ply]),
+            std::cmp::Ordering::Equal => Ok(&self.state),
+            std::cmp::Ordering::Greater => Err(JsValue::from_str(&format!(
+                "There is no move {}; {} moves have been played.", ply, self.positions.len()
+            ))),
+        }
+    }
+
+    /// Hands `network` to the MCTS NN seats. Callers check it with `fitting_model` even when
+    /// no seat uses it yet, so a wrong model fails where it is loaded.
+    fn load_model(&mut self, network: NeuralNetwork) -> Result<(), String> {
+        self.network = Some(network);
+        self.share_network()
+    }
+
+    /// Hands the loaded network, if any, to every MCTS NN seat without a model of its own.
+    fn share_network(&mut self) -> Result<(), String> {
+        let Some(network) = &self.network else { return Ok(()) };
+        for (seat, agent) in self.seats.iter().zip(&mut self.agents) {
+            if seat.model.is_some() {
+                continue;
+            }
+            if let Some(nn_agent) = agent.as_any().downcast_mut::<MctsNnAI>() {
+                nn_agent.set_network(network.clone())?;
+            }
+        }
+        Ok(())
+    }
+}
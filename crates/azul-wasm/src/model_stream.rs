@@ -0,0 +1,55 @@
+//! Incremental model loading for the browser.
+//!
+//! Instead of copying a whole model through the JS boundary in one `Vec<u8>`, the front-end
+//! fetches it in chunks and pushes each one into a `ModelStream`, which decodes the layers as
+//! their bytes arrive. Chunks are dropped once read, so peak memory stays close to the size of
+//! the network itself.
+
+use azul_ai::nn::{NeuralNetwork, WeightsDecoder};
+
+pub struct ModelStream {
+    decoder: WeightsDecoder,
+    bytes_received: usize,
+    expected_len: Option<usize>,
+}
+
+impl ModelStream {
+    pub fn new(expected_len: Option<usize>) -> Self {
+        Self { decoder: WeightsDecoder::new(expected_len), bytes_received: 0, expected_len }
+    }
+
+    /// Decodes a chunk, rejecting the stream as soon as it can't be a model.
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> Result<(), String> {
+        if let Some(expected) = self.expected_len {
+            if self.bytes_received + chunk.len() > expected {
+                return Err(format!(
+                    "Model stream overflow: expected {} bytes, received at least {}.",
+                    expected, self.bytes_received + chunk.len()
+                ));
+            }
+        }
+        self.bytes_received += chunk.len();
+        self.decoder.push(chunk).map_err(|e| format!("Could not read the model: {}", e))
+    }
+
+    pub fn bytes_received(&self) -> usize {
+        self.bytes_received
+    }
+
+    pub fn expected_len(&self) -> Option<usize> {
+        self.expected_len
+    }
+
+    /// Completes the stream and hands back the decoded network.
+    pub fn finish(self) -> Result<NeuralNetwork, String> {
+        if let Some(expected) = self.expected_len {
+            if self.bytes_received != expected {
+                return Err(format!(
+                    "Model stream ended early: expected {} bytes, received {}.",
+                    expected, self.bytes_received
+                ));
+            }
+        }
+        self.decoder.finish().map_err(|e| format!("Could not read the model: {}", e))
+    }
+}
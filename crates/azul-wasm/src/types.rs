@@ -0,0 +1,169 @@
+//! TypeScript declarations for the values `WasmGame` passes through `JsValue`, written into the
+//! generated `azul_wasm.d.ts`. They follow serde's default shapes: a unit variant is its name as
+//! a string, a variant with data is an object with the variant name as its only key, and a
+//! `None` field is `undefined`. Keep them in step with the Rust types they describe.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &str = r#"
+export type Tile = "Blue" | "Yellow" | "Red" | "Black" | "White";
+
+export type MoveSource = { Factory: number } | "Center";
+
+export type MoveDestination = { PatternLine: number } | "Floor";
+
+export interface Move {
+    source: MoveSource;
+    tile: Tile;
+    destination: MoveDestination;
+}
+
+export interface PlayedMove {
+    player: number;
+    move: Move;
+}
+
+export interface PlayerBoard {
+    score: number;
+    /** Row i holds up to i + 1 tiles. */
+    pattern_lines: Tile[][];
+    /** 5x5, null where no tile has been placed. */
+    wall: (Tile | null)[][];
+    floor_line: Tile[];
+    has_first_player_marker: boolean;
+}
+
+/** The rows of the wall, each naming the color that goes in every column. */
+export type WallLayout = Tile[][];
+
+export interface TileBagSummary {
+    blue: number;
+    yellow: number;
+    red: number;
+    black: number;
+    white: number;
+}
+
+/** What one player can see of the game, from `getState`. */
+export interface Observation {
+    player: number;
+    players: PlayerBoard[];
+    factories: Tile[][];
+    center: Tile[];
+    bag: TileBagSummary;
+    discard_pile: Tile[];
+    current_player_idx: number;
+    first_player_marker_in_center: boolean;
+    end_game_triggered: boolean;
+}
+
+/** The whole state, from `JSON.parse(getFullState())`. */
+export interface GameState {
+    players: PlayerBoard[];
+    factories: Tile[][];
+    center: Tile[];
+    tile_bag: Tile[];
+    discard_pile: Tile[];
+    current_player_idx: number;
+    first_player_marker_in_center: boolean;
+    end_game_triggered: boolean;
+    /** Null unless the game was seeded. The state is a 64-bit integer, which JavaScript numbers round. */
+    rng: { state: number } | null;
+}
+
+export interface EndGameBonuses {
+    rows: number;
+    columns: number;
+    colors: number;
+}
+
+export type ScoringEvent =
+    | { TilePlaced: { player: number; row: number; col: number; tile: Tile; horizontal_points: number; vertical_points: number; points: number; completed_row: boolean } }
+    | { FloorPenalty: { player: number; floor_tiles: number; penalty: number } }
+    | { EndGameBonus: { player: number; bonuses: EndGameBonuses; points: number } };
+
+/** What `onEvent` listeners receive. */
+export type GameEvent =
+    | { type: "TilesDrafted"; player: number; move: Move; tiles: number; to_floor: number }
+    | { type: "MarkerTaken"; player: number }
+    | { type: "RowCompleted"; player: number; row: number }
+    | { type: "RoundEnded"; round: number; events: ScoringEvent[] }
+    | { type: "GameOver"; rankings: Ranking[] };
+
+export interface Ranking {
+    player: number;
+    /** 1-based; tied players share a rank. */
+    rank: number;
+    score: number;
+    complete_rows: number;
+}
+
+export interface Hint {
+    move: Move;
+    expected_points: number;
+    tiles_taken: number;
+    tiles_to_floor: number;
+    value: number | undefined;
+    explanation: string;
+}
+
+export type MoveQuality = "Good" | "Inaccuracy" | "Mistake" | "Blunder";
+
+export interface MoveReview {
+    played: Move;
+    best_move: Move;
+    played_value: number;
+    best_value: number;
+    centipoints: number;
+    quality: MoveQuality;
+}
+
+export interface CandidateMove {
+    move: Move;
+    /** The move's share of the search's visits, from 0 to 1. */
+    visit_share: number;
+    /** The mover's expected margin over the best opponent after the move, in points. */
+    value: number;
+}
+
+export interface PositionEvaluation {
+    /** Each player's chance of winning, summing to 1. */
+    win_probabilities: number[];
+    top_moves: CandidateMove[];
+    value: number | undefined;
+    iterations: number;
+}
+
+export interface PuzzleAnswer {
+    correct: boolean;
+    review: MoveReview;
+}
+
+export interface AiTurnProgress {
+    iterations: number;
+    target: number;
+    done: boolean;
+}
+
+export type Difficulty = "Beginner" | "Easy" | "Medium" | "Hard" | "Max";
+
+export interface PlayerConfig {
+    /** A registry agent: "human" (the default), "simpleai", "heuristicai", "mctsheuristic", "mctsnn", ... */
+    agent?: string;
+    iterations?: number;
+    /** Thinking time per move, for mctsheuristic and mctsnn. */
+    milliseconds?: number;
+    /** A weights file for this seat's mctsnn. */
+    model?: Uint8Array | number[];
+    seed?: number;
+    /** Replaces `agent`. */
+    difficulty?: Difficulty;
+}
+
+export interface WasmGameConfig {
+    players: PlayerConfig[];
+    /** A weights file for every mctsnn seat without a `model` of its own. */
+    model_bytes?: Uint8Array | number[] | null;
+}
+"#;
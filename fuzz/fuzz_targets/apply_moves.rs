@@ -0,0 +1,47 @@
+//! Plays a whole game where every move (and the tile draws) is chosen by the fuzzer input,
+//! checking the engine invariants after every move and every tiling phase, and that each
+//! tiling phase's scoring events add up to the score changes.
+#![no_main]
+
+use azul_core::{GamePhase, GameState, ScoringEvent};
+use libfuzzer_sys::fuzz_target;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 9 {
+        return;
+    }
+    let num_players = 2 + (data[0] % 3) as usize;
+    let seed = u64::from_le_bytes(data[1..9].try_into().unwrap());
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut game = GameState::new_with_rng(num_players, &mut rng);
+    game.check_invariants().unwrap();
+
+    for &choice in &data[9..] {
+        match game.phase() {
+            GamePhase::GameOver => break,
+            GamePhase::Tiling => {
+                let mut expected: Vec<i64> = game.players.iter().map(|p| p.score as i64).collect();
+                for event in game.advance_with_rng(None, &mut rng).unwrap() {
+                    match event {
+                        ScoringEvent::TilePlaced { player, points, .. } => expected[player] += points as i64,
+                        ScoringEvent::FloorPenalty { player, penalty, .. } => expected[player] -= penalty as i64,
+                        ScoringEvent::EndGameBonus { player, points, .. } => expected[player] += points as i64,
+                    }
+                }
+                game.check_invariants().unwrap();
+                // The scoring events must explain every point gained or lost.
+                let actual: Vec<i64> = game.players.iter().map(|p| p.score as i64).collect();
+                assert_eq!(actual, expected);
+            }
+            GamePhase::Drafting => {
+                let legal_moves = game.get_legal_moves();
+                assert!(!legal_moves.is_empty(), "a drafting state must have legal moves");
+                let chosen = &legal_moves[choice as usize % legal_moves.len()];
+                game.advance_with_rng(Some(chosen), &mut rng).unwrap();
+                game.check_invariants().unwrap();
+            }
+        }
+    }
+});
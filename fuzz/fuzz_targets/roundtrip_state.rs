@@ -0,0 +1,20 @@
+//! Feeds arbitrary JSON to the `GameState` deserializer. Anything that parses must survive a
+//! serialize/deserialize round trip unchanged, and must not panic when its moves are listed.
+#![no_main]
+
+use azul_core::GameState;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(state) = serde_json::from_slice::<GameState>(data) else {
+        return;
+    };
+    let encoded = serde_json::to_string(&state).unwrap();
+    let decoded: GameState = serde_json::from_str(&encoded).unwrap();
+    assert_eq!(encoded, serde_json::to_string(&decoded).unwrap());
+
+    // Only states that pass the validator are guaranteed to be safe to play on.
+    if state.check_invariants().is_ok() && !state.is_round_over() {
+        let _ = state.get_legal_moves();
+    }
+});
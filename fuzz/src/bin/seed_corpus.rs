@@ -0,0 +1,43 @@
+//! Seeds the `roundtrip_state` corpus from a headless game log, one file per recorded
+//! position. Gzipped logs need to be unzipped first.
+//!
+//! Usage: cargo run --bin seed_corpus -- stats/<run>/game_logs.jsonl
+
+use azul_core::{GameState, TurnState};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use serde_json::Value;
+use std::fs;
+use std::io::BufReader;
+
+fn main() -> std::io::Result<()> {
+    let log_path = std::env::args().nth(1).expect("usage: seed_corpus <game_logs.jsonl>");
+    let reader = BufReader::new(fs::File::open(&log_path)?);
+    // One game per line, or a single array in logs from before the streaming format.
+    let games: Vec<Value> = if log_path.ends_with(".json") {
+        serde_json::from_reader::<_, Vec<Value>>(reader)?
+    } else {
+        serde_json::Deserializer::from_reader(reader).into_iter::<Value>().collect::<Result<_, _>>()?
+    };
+    let corpus_dir = "corpus/roundtrip_state";
+    fs::create_dir_all(corpus_dir)?;
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut written = 0;
+    for game in &games {
+        let rounds = game["history"].as_array().map(Vec::as_slice).unwrap_or_default();
+        for round in rounds {
+            let turns = round["turns"].as_array().map(Vec::as_slice).unwrap_or_default();
+            for turn in turns {
+                let Ok(turn_state) = serde_json::from_value::<TurnState>(turn["state_before_move"].clone()) else {
+                    continue;
+                };
+                let state = GameState::from_turn_state_with_rng(&turn_state, &mut rng);
+                fs::write(format!("{}/log_{:06}.json", corpus_dir, written), serde_json::to_vec(&state)?)?;
+                written += 1;
+            }
+        }
+    }
+    println!("Wrote {} corpus entries to {}/", written, corpus_dir);
+    Ok(())
+}
@@ -1,4 +1,5 @@
-import init, { WasmGame } from './pkg/azul_engine.js';
+import init, { WasmGame } from './pkg/azul_wasm.js';
+import { streamModelInto, indexedDbModelCache } from './model_loader.js';
 
 // --- DOM Elements ---
 const setupScreen = document.getElementById('setup-screen');
@@ -8,24 +9,60 @@ const playersContainer = document.getElementById('players-container');
 const numPlayersSelect = document.getElementById('num-players');
 const playerOptionsContainer = document.getElementById('player-options-container');
 const startGameBtn = document.getElementById('start-game-btn');
+const difficultySelect = document.getElementById('difficulty');
+const thinkTimeSelect = document.getElementById('think-time');
+const hintBtn = document.getElementById('hint-btn');
+const hintText = document.getElementById('hint-text');
+const rematchBtn = document.getElementById('rematch-btn');
+const newGameBtn = document.getElementById('new-game-btn');
+const dailyPuzzleBtn = document.getElementById('daily-puzzle-btn');
+const analyzeBtn = document.getElementById('analyze-btn');
+const prevPositionBtn = document.getElementById('prev-position-btn');
+const nextPositionBtn = document.getElementById('next-position-btn');
+
+// MCTS simulations behind the Hint button: enough to be useful, short enough not to stall the page.
+const HINT_ITERATIONS = 2000;
+// MCTS simulations per slice of an engine's search; the page handles input between slices.
+const AI_SLICE_ITERATIONS = 50;
+// The engine agent behind each player type in the setup screen.
+const AGENTS = {
+  Human: 'human',
+  SimpleAI: 'simpleai',
+  HeuristicAI: 'heuristicai',
+  MctsAI: 'mctsheuristic',
+  MctsNnAI: 'mctsnn',
+};
+// MCTS simulations per move when no thinking time is chosen.
+const MCTS_ITERATIONS = 500;
+// MCTS simulations behind each position's evaluation in analysis mode.
+const ANALYSIS_ITERATIONS = 3000;
 
 // --- Game State Variables ---
 let game;
 let WALL_LAYOUT;
 let selectedTake = null;
 let playerConfigs = [];
+// Bumped to abandon the engine turn in progress, e.g. for a rematch.
+let aiTurnId = 0;
+// True while a puzzle waits for its answer; the next move is checked instead of played.
+let puzzleActive = false;
+// In analysis mode, the move whose position is shown (getMoveCount() for the final one); null otherwise.
+let reviewPly = null;
+// Bumped to drop an evaluation that finishes after the position changed.
+let analysisId = 0;
 
 // --- Render Function ---
 async function render() {
   if (!game) return;
 
   try {
-    const state = await game.getState();
+    const state = reviewPly === null ? await game.getState() : game.getPositionAt(reviewPly);
     if (!state) return;
+    const positionOver = game.isGameOver() && (reviewPly === null || reviewPly === game.getMoveCount());
 
     const existingBanner = document.getElementById('end-game-banner');
     if (existingBanner) existingBanner.remove();
-    if (state.end_game_triggered && !game.isGameOver()) {
+    if (state.end_game_triggered && !positionOver) {
         const banner = document.createElement('div');
         banner.id = 'end-game-banner';
         banner.innerText = 'FINAL ROUND';
@@ -76,7 +113,7 @@ async function render() {
     state.players.forEach((player, playerIndex) => {
         const playerDiv = document.createElement('div');
         playerDiv.className = 'player-board';
-        if (playerIndex === state.current_player_idx && !game.isGameOver()) {
+        if (playerIndex === state.current_player_idx && !positionOver) {
             playerDiv.style.borderColor = 'gold';
             playerDiv.style.borderWidth = '2px';
         }
@@ -174,6 +211,11 @@ async function placementClicked(rowIndex) {
     const destination = (rowIndex < 5) ? { PatternLine: rowIndex } : 'Floor';
     const move = { ...selectedTake, destination };
 
+    if (puzzleActive) {
+        answerPuzzle(move);
+        return;
+    }
+
     try {
         await game.applyMove(move);
         selectedTake = null;
@@ -185,21 +227,35 @@ async function placementClicked(rowIndex) {
     }
 }
 
+function showHint() {
+    if (!game || puzzleActive || game.isGameOver() || playerConfigs[game.getState().current_player_idx] !== 'Human') return;
+    try {
+        const hint = game.getHint(HINT_ITERATIONS);
+        if (!hint) return;
+        hintText.innerText = hint.explanation;
+        selectedTake = { tile: hint.move.tile, source: hint.move.source };
+        render();
+    } catch (error) {
+        console.error("Could not get a hint:", error);
+    }
+}
+
 async function handleEndOfTurn() {
+    hintText.innerText = '';
     try {
         const state = await game.getState();
         const isDraftingOver = state.factories.every(f => f.length === 0) && state.center.length === 0;
 
         if (isDraftingOver) {
-            await game.handleRoundEnd();
+            const events = await game.handleRoundEnd();
+            hintText.innerText = describeRoundScoring(events, state.players.length);
         }
 
         if (game.isGameOver()) {
-            await game.applyEndGameScoring();
+            analyzeBtn.style.display = '';
             render();
-            const finalState = await game.getState();
-            const winner = findWinner(finalState);
-            setTimeout(() => alert(`Game Over! Player ${winner.index + 1} wins with ${winner.score} points!`), 100);
+            const message = describeResult(game.getRankings());
+            setTimeout(() => alert(`Game Over! ${message}`), 100);
             return;
         }
 
@@ -210,21 +266,48 @@ async function handleEndOfTurn() {
     }
 }
 
+// Runs the engine's search a slice at a time, showing its progress, until it plays its move.
+// Returns false if the turn was abandoned first.
+async function runAiTurnInSlices(turnId) {
+    let progress = game.startAiTurn(null);
+    while (!progress.done) {
+        await new Promise(resolve => setTimeout(resolve, 0));
+        if (turnId !== aiTurnId) return false;
+        progress = game.stepAiSearch(AI_SLICE_ITERATIONS);
+        hintText.innerText = `Thinking... ${Math.round(100 * progress.iterations / progress.target)}%`;
+    }
+    return true;
+}
+
+function cancelAiTurn() {
+    aiTurnId++;
+    if (game) game.cancelAiSearch();
+    gameContainer.style.pointerEvents = 'auto';
+    playersContainer.style.pointerEvents = 'auto';
+}
+
 function checkForAIMove() {
     try {
         const state = game.getState();
         const currentPlayerType = playerConfigs[state.current_player_idx];
 
         if (currentPlayerType !== 'Human' && !game.isGameOver()) {
-            document.body.style.pointerEvents = 'none';
+            const turnId = ++aiTurnId;
+            gameContainer.style.pointerEvents = 'none';
+            playersContainer.style.pointerEvents = 'none';
             setTimeout(async () => {
+                if (turnId !== aiTurnId) return;
                 try {
-                    await game.runAiTurn();
-                    await handleEndOfTurn();
+                    if (await runAiTurnInSlices(turnId)) {
+                        await handleEndOfTurn();
+                    }
                 } catch(aiError) {
                     console.error("AI Error:", aiError);
                 } finally {
-                    document.body.style.pointerEvents = 'auto';
+                    if (turnId === aiTurnId) {
+                        gameContainer.style.pointerEvents = 'auto';
+                        playersContainer.style.pointerEvents = 'auto';
+                    }
                 }
             }, 500);
         }
@@ -257,47 +340,62 @@ async function startGame() {
   const numPlayers = parseInt(numPlayersSelect.value, 10);
   
   playerConfigs = [];
-  const playerTypesForWasm = [];
+  const players = [];
   let needsModel = false;
+  // A difficulty level replaces the per-player AI choice for every AI seat.
+  const difficulty = difficultySelect.value || null;
+  const thinkTime = thinkTimeSelect.value ? parseInt(thinkTimeSelect.value, 10) : null;
 
   for (let i = 0; i < numPlayers; i++) {
     const selectElement = document.getElementById(`player-type-${i}`);
     const playerType = selectElement.value;
     playerConfigs.push(playerType);
-    
-    if (playerType === 'Human') playerTypesForWasm.push(0);
-    if (playerType === 'SimpleAI') playerTypesForWasm.push(1);
-    if (playerType === 'HeuristicAI') playerTypesForWasm.push(2);
-    if (playerType === 'MctsAI') playerTypesForWasm.push(3);
-    if (playerType === 'MctsNnAI') {
-        playerTypesForWasm.push(4);
-        needsModel = true;
+
+    const agent = AGENTS[playerType];
+    if (agent === 'human') {
+        players.push({ agent });
+    } else if (difficulty) {
+        players.push({ difficulty });
+    } else if (agent === 'mctsheuristic' || agent === 'mctsnn') {
+        players.push(thinkTime ? { agent, milliseconds: thinkTime } : { agent, iterations: MCTS_ITERATIONS });
+        if (agent === 'mctsnn') needsModel = true;
+    } else {
+        players.push({ agent });
     }
   }
 
-  let modelBytes = null;
-  if (needsModel) {
-      // MODIFIED: Always fetch the single release model.
-      const modelPath = 'release_models/azul_alpha.ot';
-      console.log(`Fetching release model: ${modelPath}`);
+  try {
+    const gameConfig = {
+        players,
+        model_bytes: null,
+    };
+    // Later games reuse the engine, keeping its agents and any model already loaded.
+    if (game) {
+      game.newGameWithConfig(gameConfig);
+    } else {
+      game = new WasmGame(gameConfig);
+    }
+  } catch (error) {
+    console.error("Failed to start game:", error);
+    alert(`Could not start the game: ${error}`);
+    return;
+  }
+
+  if (needsModel && !game.hasModel()) {
+      // Stream the single release model into the engine, cached in IndexedDB between visits.
+      const modelPath = 'release_models/azul_alpha.weights';
+      console.log(`Loading release model: ${modelPath}`);
       try {
-          const response = await fetch(modelPath);
-          if (!response.ok) throw new Error(`HTTP error! status: ${response.status}`);
-          const buffer = await response.arrayBuffer();
-          modelBytes = new Uint8Array(buffer);
+          const fromCache = await streamModelInto(game, modelPath, { cache: indexedDbModelCache() });
+          console.log(`Model loaded${fromCache ? ' from cache' : ''}.`);
       } catch (error) {
-          console.error("Failed to fetch the model:", error);
+          console.error("Failed to load the model:", error);
           alert("Could not fetch the release AI model. Please ensure a trained model exists in the 'release_models' directory and the server is running correctly.");
           return;
       }
   }
 
   try {
-    const gameConfig = {
-        player_types: playerTypesForWasm,
-        model_bytes: modelBytes,
-    };
-    game = new WasmGame(gameConfig);
     WALL_LAYOUT = await game.getWallLayout();
 
     setupScreen.style.display = 'none';
@@ -311,20 +409,163 @@ async function startGame() {
   }
 }
 
-// --- Helper Functions ---
-function findWinner(state) {
-  let bestPlayer = { index: 0, score: -1, rows: 0 };
-  state.players.forEach((player, index) => {
-    const completedRows = player.wall.filter(row => row.every(t => t !== null)).length;
-    if (player.score > bestPlayer.score) {
-      bestPlayer = { index, score: player.score, rows: completedRows };
-    } else if (player.score === bestPlayer.score) {
-      if (completedRows > bestPlayer.rows) {
-        bestPlayer = { index, score: player.score, rows: completedRows };
-      }
+// Today's puzzle, the same for everyone: two human seats and a position from the date's seed.
+async function startDailyPuzzle() {
+  cancelAiTurn();
+  leaveAnalysis();
+  const gameConfig = { players: [{ agent: 'human' }, { agent: 'human' }], model_bytes: null };
+  try {
+    if (game) {
+      game.newGameWithConfig(gameConfig);
+    } else {
+      game = new WasmGame(gameConfig);
     }
-  });
-  return bestPlayer;
+    playerConfigs = ['Human', 'Human'];
+    game.loadPuzzle(dailyPuzzleSeed());
+    WALL_LAYOUT = await game.getWallLayout();
+  } catch (error) {
+    console.error("Failed to load the puzzle:", error);
+    alert(`Could not load today's puzzle: ${error}`);
+    return;
+  }
+  puzzleActive = true;
+  selectedTake = null;
+  hintText.innerText = `Daily puzzle: find the best move for Player ${game.getState().current_player_idx + 1}.`;
+  setupScreen.style.display = 'none';
+  gameScreen.style.display = 'flex';
+  render();
+}
+
+function answerPuzzle(move) {
+  try {
+    const answer = game.checkPuzzleAnswer(move);
+    puzzleActive = false;
+    hintText.innerText = answer.correct
+      ? 'Correct! That is the best move.'
+      : `Not quite: that gives up ${(answer.review.centipoints / 100).toFixed(1)} points. The best move was ${describeMove(answer.review.best_move)}.`;
+  } catch (error) {
+    console.error("Could not check the answer:", error);
+  }
+  selectedTake = null;
+  render();
+}
+
+// Analysis mode, once the game is over: step through its positions with each one's evaluation.
+function toggleAnalysis() {
+  if (reviewPly === null) {
+    reviewPly = game.getMoveCount();
+    analyzeBtn.innerText = 'Close Analysis';
+    prevPositionBtn.style.display = '';
+    nextPositionBtn.style.display = '';
+    showReviewPosition();
+  } else {
+    leaveAnalysis();
+    analyzeBtn.style.display = '';
+    render();
+  }
+}
+
+function leaveAnalysis() {
+  reviewPly = null;
+  analysisId++;
+  analyzeBtn.innerText = 'Analyze';
+  analyzeBtn.style.display = 'none';
+  prevPositionBtn.style.display = 'none';
+  nextPositionBtn.style.display = 'none';
+}
+
+function stepReview(delta) {
+  if (reviewPly === null) return;
+  reviewPly = Math.min(Math.max(reviewPly + delta, 0), game.getMoveCount());
+  showReviewPosition();
+}
+
+function showReviewPosition() {
+  render();
+  const id = ++analysisId;
+  hintText.innerText = `Move ${reviewPly} of ${game.getMoveCount()}: analyzing...`;
+  // Lets the position paint before the search blocks the page.
+  setTimeout(() => {
+    if (id !== analysisId) return;
+    try {
+      const evaluation = game.evaluatePosition(ANALYSIS_ITERATIONS, reviewPly);
+      hintText.innerText = `Move ${reviewPly} of ${game.getMoveCount()}: ${describeEvaluation(evaluation)}`;
+    } catch (error) {
+      console.error("Could not evaluate the position:", error);
+    }
+  }, 50);
+}
+
+// Deals a new game with the same seats.
+function rematch() {
+  if (!game) return;
+  cancelAiTurn();
+  leaveAnalysis();
+  puzzleActive = false;
+  game.reset();
+  selectedTake = null;
+  hintText.innerText = '';
+  render();
+  checkForAIMove();
+}
+
+// Back to the setup screen; Start Game then begins the next game in the same engine.
+function showSetup() {
+  cancelAiTurn();
+  leaveAnalysis();
+  puzzleActive = false;
+  selectedTake = null;
+  hintText.innerText = '';
+  const banner = document.getElementById('end-game-banner');
+  if (banner) banner.remove();
+  gameScreen.style.display = 'none';
+  setupScreen.style.display = '';
+}
+
+// --- Helper Functions ---
+// Each player's net points from handleRoundEnd's scoring events, e.g. "Round scoring: Player 1 +7, Player 2 -1".
+function describeRoundScoring(events, numPlayers) {
+  const totals = new Array(numPlayers).fill(0);
+  for (const event of events) {
+    if (event.TilePlaced) totals[event.TilePlaced.player] += event.TilePlaced.points;
+    if (event.FloorPenalty) totals[event.FloorPenalty.player] -= event.FloorPenalty.penalty;
+    if (event.EndGameBonus) totals[event.EndGameBonus.player] += event.EndGameBonus.points;
+  }
+  const parts = totals.map((points, i) => `Player ${i + 1} ${points >= 0 ? '+' : ''}${points}`);
+  return `Round scoring: ${parts.join(', ')}`;
+}
+
+// The local date as a number, e.g. 20261016, so every visitor gets the same puzzle on the same day.
+function dailyPuzzleSeed() {
+  const now = new Date();
+  return now.getFullYear() * 10000 + (now.getMonth() + 1) * 100 + now.getDate();
+}
+
+// A move in words, e.g. "Blue from factory 2 to line 4".
+function describeMove(move) {
+  const source = move.source === 'Center' ? 'the center' : `factory ${move.source.Factory + 1}`;
+  const destination = move.destination === 'Floor' ? 'the floor' : `line ${move.destination.PatternLine + 1}`;
+  return `${move.tile} from ${source} to ${destination}`;
+}
+
+// Win chances and the search's favorite moves, e.g.
+// "Player 1 64%, Player 2 36%. Best: Blue from factory 3 to line 3 (41%), ...".
+function describeEvaluation(evaluation) {
+  const chances = evaluation.win_probabilities.map((p, i) => `Player ${i + 1} ${Math.round(100 * p)}%`).join(', ');
+  if (evaluation.top_moves.length === 0) return `${chances}.`;
+  const moves = evaluation.top_moves.slice(0, 3)
+    .map(candidate => `${describeMove(candidate.move)} (${Math.round(100 * candidate.visit_share)}%)`)
+    .join(', ');
+  return `${chances}. Best: ${moves}`;
+}
+
+function describeResult(rankings) {
+  const winners = rankings.filter(r => r.rank === 1);
+  if (winners.length > 1) {
+    const names = winners.map(r => `Player ${r.player + 1}`).join(' and ');
+    return `Shared victory between ${names} with ${winners[0].score} points!`;
+  }
+  return `Player ${winners[0].player + 1} wins with ${winners[0].score} points!`;
 }
 
 async function highlightLegalPlacements() {
@@ -366,6 +607,13 @@ async function main() {
     await init();
     numPlayersSelect.addEventListener('change', (e) => updatePlayerOptions(e.target.value));
     startGameBtn.addEventListener('click', startGame);
+    hintBtn.addEventListener('click', showHint);
+    rematchBtn.addEventListener('click', rematch);
+    newGameBtn.addEventListener('click', showSetup);
+    dailyPuzzleBtn.addEventListener('click', startDailyPuzzle);
+    analyzeBtn.addEventListener('click', toggleAnalysis);
+    prevPositionBtn.addEventListener('click', () => stepReview(-1));
+    nextPositionBtn.addEventListener('click', () => stepReview(1));
     updatePlayerOptions(numPlayersSelect.value);
 }
 
@@ -0,0 +1,79 @@
+// Streams a model file into a WasmGame chunk by chunk, so the full model never has to be
+// held as one JS buffer and copied across the wasm boundary. Each chunk is let go once the
+// engine has decoded it.
+
+const DB_NAME = 'azul-model-cache';
+const STORE_NAME = 'models';
+
+// A cache hook backed by IndexedDB. Any object with the same `get(key)` / `put(key, blob)`
+// shape (both returning promises) can be passed to `streamModelInto` instead.
+export function indexedDbModelCache() {
+  const open = () => new Promise((resolve, reject) => {
+    const request = indexedDB.open(DB_NAME, 1);
+    request.onupgradeneeded = () => request.result.createObjectStore(STORE_NAME);
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error);
+  });
+
+  const run = async (mode, action) => {
+    const db = await open();
+    return new Promise((resolve, reject) => {
+      const request = action(db.transaction(STORE_NAME, mode).objectStore(STORE_NAME));
+      request.onsuccess = () => resolve(request.result);
+      request.onerror = () => reject(request.error);
+    });
+  };
+
+  return {
+    get: (key) => run('readonly', store => store.get(key)),
+    put: (key, blob) => run('readwrite', store => store.put(blob, key)),
+  };
+}
+
+async function pushStream(game, stream, totalBytes, onProgress) {
+  const reader = stream.getReader();
+  let received = 0;
+  game.beginModelStream(totalBytes);
+  for (;;) {
+    const { done, value } = await reader.read();
+    if (done) break;
+    game.pushModelChunk(value);
+    received += value.length;
+    if (onProgress) onProgress(received, totalBytes);
+  }
+  game.finishModelStream();
+}
+
+// Loads the model at `url` into `game`, using `cache` (if given) to skip the network on
+// later visits. Returns true if the model came from the cache.
+export async function streamModelInto(game, url, { cache = null, onProgress = null } = {}) {
+  if (cache) {
+    const cached = await cache.get(url).catch(() => undefined);
+    if (cached) {
+      await pushStream(game, cached.stream(), cached.size, onProgress);
+      return true;
+    }
+  }
+
+  const response = await fetch(url);
+  if (!response.ok) throw new Error(`HTTP error! status: ${response.status}`);
+  // Content-Length is the encoded size, so only trust it when the response isn't compressed.
+  const length = response.headers.get('Content-Length');
+  const encoded = response.headers.get('Content-Encoding');
+  const totalBytes = length && !encoded ? parseInt(length, 10) : undefined;
+  if (!cache) {
+    await pushStream(game, response.body, totalBytes, onProgress);
+    return false;
+  }
+
+  // The second branch of the stream is collected into a Blob, which the browser can keep out
+  // of the JS heap, and cached once the engine has accepted the model.
+  const [forEngine, forCache] = response.body.tee();
+  const blob = new Response(forCache).blob();
+  blob.catch(() => {});
+  await pushStream(game, forEngine, totalBytes, onProgress);
+  await blob
+    .then(blob => cache.put(url, blob))
+    .catch(error => console.warn('Could not cache model:', error));
+  return false;
+}